@@ -18,11 +18,8 @@ pub fn benchmark_handle_request(c: &mut Criterion) {
 
     let blueprint = Blueprint::try_from(&config_module).unwrap();
 
-    let endpoints = config_module.extensions().endpoint_set.clone();
-    let endpoints_clone = endpoints.clone();
-
     let server_config = tokio_runtime
-        .block_on(ServerConfig::new(blueprint.clone(), endpoints.clone()))
+        .block_on(ServerConfig::new(&config_module, blueprint.clone(), None))
         .unwrap();
     let server_config = Arc::new(server_config);
 
@@ -46,7 +43,7 @@ pub fn benchmark_handle_request(c: &mut Criterion) {
     });
 
     let server_config = tokio_runtime
-        .block_on(ServerConfig::new(blueprint, endpoints_clone))
+        .block_on(ServerConfig::new(&config_module, blueprint, None))
         .unwrap();
     let server_config = Arc::new(server_config);
 
@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use strum_macros::Display;
 use tailcall_version::VERSION;
 
@@ -30,6 +30,25 @@ pub enum Command {
         /// production)
         #[arg(short, long, action = clap::ArgAction::Set, default_value_t = true)]
         verify_ssl: bool,
+
+        /// Serves generated data based on the schema instead of calling the
+        /// upstreams
+        #[arg(long)]
+        mock: bool,
+
+        /// Seed used to generate the mock data, the same seed always produces
+        /// the same responses
+        #[arg(long, default_value_t = 0)]
+        mock_seed: u64,
+
+        /// Records every upstream request and response to the cassette file
+        #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+        record: Option<String>,
+
+        /// Replays the upstream responses from a cassette file created with
+        /// `--record`, requests that weren't recorded fail
+        #[arg(long, value_name = "FILE")]
+        replay: Option<String>,
     },
 
     /// Validate a composition spec
@@ -47,6 +66,19 @@ pub enum Command {
         #[arg(short, long)]
         schema: bool,
 
+        /// Run lint rules on the configuration
+        #[arg(long)]
+        lint: bool,
+
+        /// Output format of the lint diagnostics
+        #[arg(long, value_enum, default_value_t = LintFormat::Text)]
+        lint_format: LintFormat,
+
+        /// Overrides the severity of a lint rule eg. `n-plus-one=error`
+        /// Use `off` as the severity to disable the rule
+        #[arg(long = "lint-rule", value_name = "RULE=SEVERITY")]
+        lint_rules: Vec<String>,
+
         /// Controls SSL/TLS certificate verification for remote config files
         /// Set to false to skip certificate verification (not recommended for
         /// production)
@@ -67,4 +99,125 @@ pub enum Command {
         #[arg(required = true)]
         file_path: String,
     },
+
+    /// Compares two configurations and reports breaking changes
+    Diff {
+        /// Path for the old configuration file or http(s) link to it
+        #[arg(required = true)]
+        old: String,
+
+        /// Path for the new configuration file or http(s) link to it
+        #[arg(required = true)]
+        new: String,
+
+        /// Output format of the detected changes
+        #[arg(short, long, value_enum, default_value_t = DiffFormat::Text)]
+        format: DiffFormat,
+
+        /// Controls SSL/TLS certificate verification for remote config files
+        /// Set to false to skip certificate verification (not recommended for
+        /// production)
+        #[arg(short, long, action = clap::ArgAction::Set, default_value_t = true)]
+        verify_ssl: bool,
+    },
+
+    /// Prints the execution plan of a GraphQL operation without running it
+    Plan {
+        /// Path for the configuration files separated by spaces if more than
+        /// one
+        #[arg(required = true)]
+        file_paths: Vec<String>,
+
+        /// GraphQL operation or path to a file that contains it
+        #[arg(short, long)]
+        query: String,
+
+        /// Name of the operation to plan when the document contains several
+        #[arg(long)]
+        operation_name: Option<String>,
+
+        /// Controls SSL/TLS certificate verification for remote config files
+        /// Set to false to skip certificate verification (not recommended for
+        /// production)
+        #[arg(short, long, action = clap::ArgAction::Set, default_value_t = true)]
+        verify_ssl: bool,
+    },
+
+    /// Generates TypeScript definitions for the script functions and the
+    /// types of the configuration
+    Types {
+        /// Path for the configuration files separated by spaces if more than
+        /// one
+        #[arg(required = true)]
+        file_paths: Vec<String>,
+
+        /// Writes the definitions to the file instead of printing them
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<String>,
+
+        /// Controls SSL/TLS certificate verification for remote config files
+        /// Set to false to skip certificate verification (not recommended for
+        /// production)
+        #[arg(short, long, action = clap::ArgAction::Set, default_value_t = true)]
+        verify_ssl: bool,
+    },
+
+    /// Exports the OpenAPI document of the REST endpoints of the
+    /// configuration
+    #[command(name = "openapi")]
+    OpenApi {
+        /// Path for the configuration files separated by spaces if more than
+        /// one
+        #[arg(required = true)]
+        file_paths: Vec<String>,
+
+        /// Writes the document to the file instead of printing it
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<String>,
+
+        /// Controls SSL/TLS certificate verification for remote config files
+        /// Set to false to skip certificate verification (not recommended for
+        /// production)
+        #[arg(short, long, action = clap::ArgAction::Set, default_value_t = true)]
+        verify_ssl: bool,
+    },
+
+    /// Exports the `.proto` file of the operations exposed as gRPC methods
+    /// with `@rpc`
+    Proto {
+        /// Path for the configuration files separated by spaces if more than
+        /// one
+        #[arg(required = true)]
+        file_paths: Vec<String>,
+
+        /// Writes the file to the given path instead of printing it
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<String>,
+
+        /// Controls SSL/TLS certificate verification for remote config files
+        /// Set to false to skip certificate verification (not recommended for
+        /// production)
+        #[arg(short, long, action = clap::ArgAction::Set, default_value_t = true)]
+        verify_ssl: bool,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum LintFormat {
+    /// Human readable list of diagnostics
+    #[default]
+    Text,
+    /// Machine readable JSON document
+    Json,
+    /// Static Analysis Results Interchange Format
+    Sarif,
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum DiffFormat {
+    /// Human readable list of changes
+    #[default]
+    Text,
+    /// Machine readable JSON document
+    Json,
 }
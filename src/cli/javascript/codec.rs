@@ -1,7 +1,9 @@
 use std::collections::BTreeMap;
 use std::str::FromStr;
 
+use async_graphql_value::{ConstValue, Name};
 use http::header::{HeaderName, HeaderValue};
+use indexmap::IndexMap;
 use rquickjs::{FromJs, IntoJs};
 
 use super::create_header_map;
@@ -200,6 +202,88 @@ impl<'js> FromJs<'js> for WorkerResponse {
     }
 }
 
+/// Value exchanged with the `tailcall` API of the scripts, e.g. the entries
+/// of the cache.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonValue(pub ConstValue);
+
+impl<'js> IntoJs<'js> for JsonValue {
+    fn into_js(self, ctx: &rquickjs::Ctx<'js>) -> rquickjs::Result<rquickjs::Value<'js>> {
+        match self.0 {
+            ConstValue::Null => Ok(rquickjs::Value::new_null(ctx.clone())),
+            ConstValue::Boolean(value) => Ok(rquickjs::Value::new_bool(ctx.clone(), value)),
+            ConstValue::Number(number) => match number.as_i64().map(i32::try_from) {
+                Some(Ok(value)) => Ok(rquickjs::Value::new_int(ctx.clone(), value)),
+                _ => Ok(rquickjs::Value::new_float(
+                    ctx.clone(),
+                    number.as_f64().unwrap_or_default(),
+                )),
+            },
+            ConstValue::String(value) => value.into_js(ctx),
+            ConstValue::Enum(name) => name.as_str().into_js(ctx),
+            ConstValue::Binary(bytes) => String::from_utf8_lossy(&bytes).into_js(ctx),
+            ConstValue::List(values) => {
+                let array = rquickjs::Array::new(ctx.clone())?;
+                for (index, value) in values.into_iter().enumerate() {
+                    array.set(index, JsonValue(value))?;
+                }
+                Ok(array.into_value())
+            }
+            ConstValue::Object(fields) => {
+                let object = rquickjs::Object::new(ctx.clone())?;
+                for (name, value) in fields {
+                    object.set(name.as_str(), JsonValue(value))?;
+                }
+                Ok(object.into_value())
+            }
+        }
+    }
+}
+
+impl<'js> FromJs<'js> for JsonValue {
+    fn from_js(_: &rquickjs::Ctx<'js>, value: rquickjs::Value<'js>) -> rquickjs::Result<Self> {
+        let value = if value.is_null() || value.is_undefined() {
+            ConstValue::Null
+        } else if let Some(value) = value.as_bool() {
+            ConstValue::Boolean(value)
+        } else if let Some(value) = value.as_int() {
+            ConstValue::Number(value.into())
+        } else if let Some(value) = value.as_float() {
+            // integral floats are numbers like the ones parsed by JSON.parse
+            if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+                ConstValue::Number((value as i64).into())
+            } else {
+                serde_json::Number::from_f64(value)
+                    .map(ConstValue::Number)
+                    .unwrap_or(ConstValue::Null)
+            }
+        } else if let Some(value) = value.as_string() {
+            ConstValue::String(value.to_string()?)
+        } else if let Some(array) = value.as_array() {
+            let values = array
+                .iter::<JsonValue>()
+                .map(|value| value.map(|value| value.0))
+                .collect::<rquickjs::Result<Vec<_>>>()?;
+            ConstValue::List(values)
+        } else if let Some(object) = value.as_object().filter(|_| !value.is_function()) {
+            let mut fields = IndexMap::new();
+            for prop in object.props::<String, JsonValue>() {
+                let (name, value) = prop?;
+                fields.insert(Name::new(name), value.0);
+            }
+            ConstValue::Object(fields)
+        } else {
+            return Err(rquickjs::Error::FromJs {
+                from: value.type_name(),
+                to: "JSON value",
+                message: Some("only JSON compatible values are supported".to_string()),
+            });
+        };
+
+        Ok(JsonValue(value))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::BTreeMap;
@@ -457,4 +541,27 @@ mod test {
             );
         });
     }
+
+    #[test]
+    fn test_json_value_round_trip() {
+        let runtime = Runtime::new().unwrap();
+        let context = Context::full(&runtime).unwrap();
+        context.with(|ctx| {
+            let expected = ConstValue::from_json(serde_json::json!({
+                "id": 1,
+                "price": 9.5,
+                "tags": ["a", null, true],
+                "owner": {"name": "tailcall"}
+            }))
+            .unwrap();
+            let value = JsonValue(expected.clone()).into_js(&ctx).unwrap();
+            assert!(value.is_object());
+
+            let actual = JsonValue::from_js(&ctx, value).unwrap();
+            assert_eq!(actual.0, expected);
+
+            let function: rquickjs::Value = ctx.eval("() => 1").unwrap();
+            assert!(JsonValue::from_js(&ctx, function).is_err());
+        });
+    }
 }
@@ -9,13 +9,17 @@ mod runtime;
 
 pub use runtime::Runtime;
 
+use crate::core::runtime::TargetRuntime;
 use crate::core::{blueprint, WorkerIO};
 
-pub fn init_worker_io<T, V>(script: blueprint::Script) -> Arc<dyn WorkerIO<T, V> + Send + Sync>
+pub fn init_worker_io<T, V>(
+    script: blueprint::Script,
+    runtime: &TargetRuntime,
+) -> Arc<dyn WorkerIO<T, V> + Send + Sync>
 where
     Runtime: WorkerIO<T, V>,
 {
-    (Arc::new(Runtime::new(script))) as _
+    (Arc::new(Runtime::new(script, runtime))) as _
 }
 
 fn create_header_map(headers: BTreeMap<String, String>) -> anyhow::Result<headers::HeaderMap> {
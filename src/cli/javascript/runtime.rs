@@ -1,19 +1,35 @@
-use std::cell::{OnceCell, RefCell};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroU64;
+use std::ptr;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::thread;
+use std::time::{Duration, Instant};
 
 use async_graphql_value::ConstValue;
-use rquickjs::{Context, Ctx, FromJs, Function, IntoJs, Value};
+use rquickjs::allocator::{Allocator, RustAllocator};
+use rquickjs::loader::{BuiltinLoader, BuiltinResolver};
+use rquickjs::{Context, Ctx, FromJs, Function, IntoJs, Module, Persistent, Promise, Value};
+use serde::{Deserialize, Serialize};
+use swc_core::common::sync::Lrc;
+use swc_core::common::{FileName, SourceMap};
+use swc_core::ecma::ast::{EsVersion, Program};
+use swc_core::ecma::parser::lexer::Lexer;
+use swc_core::ecma::parser::{Parser, StringInput, Syntax};
+use tailcall_hasher::TailcallHasher;
+use tokio::sync::{mpsc, oneshot};
 
-use crate::core::worker::{Command, Event};
-use crate::core::{blueprint, worker, WorkerIO};
-
-struct LocalRuntime(Context);
-
-thread_local! {
-    // Practically only one JS runtime is created for every Runtime because tokio_runtime is single threaded.
-  static LOCAL_RUNTIME: RefCell<OnceCell<LocalRuntime>> = const { RefCell::new(OnceCell::new()) };
-}
+use super::codec::JsonValue;
+use crate::core::http::ErrorResponse;
+use crate::core::ir::model::IoId;
+use crate::core::runtime::TargetRuntime;
+use crate::core::worker::{Command, Event, Limit};
+use crate::core::{blueprint, worker, EntityCache, EnvIO, HttpIO, WorkerIO};
 
 #[rquickjs::function]
 fn qjs_print(msg: String, is_err: bool) {
@@ -25,35 +41,681 @@ fn qjs_print(msg: String, is_err: bool) {
 }
 
 static CONSOLE_JS: &[u8] = include_bytes!("shim/console.js");
+static FETCH_JS: &[u8] = include_bytes!("shim/fetch.js");
+static TIMERS_JS: &[u8] = include_bytes!("shim/timers.js");
+static TAILCALL_JS: &[u8] = include_bytes!("shim/tailcall.js");
+
+/// Request passed by the `fetch` shim.
+#[derive(Debug, Deserialize)]
+struct FetchRequest {
+    url: String,
+    method: String,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    body: Option<String>,
+}
+
+/// Response handed back to the `fetch` shim.
+#[derive(Debug, Serialize)]
+struct FetchResponse {
+    status: u16,
+    headers: BTreeMap<String, String>,
+    body: String,
+}
+
+async fn fetch(http: Arc<dyn HttpIO>, request: String) -> anyhow::Result<ConstValue> {
+    let request: FetchRequest = serde_json::from_str(&request)?;
+    let method = reqwest::Method::from_bytes(request.method.to_uppercase().as_bytes())?;
+    let mut req = reqwest::Request::new(method, request.url.parse()?);
+    *req.headers_mut() = super::create_header_map(request.headers)?;
+    if let Some(body) = request.body {
+        *req.body_mut() = Some(body.into());
+    }
+
+    // the responses with an error status are responses for the scripts too,
+    // only the transport errors reject
+    let response = match http.execute(req).await {
+        Ok(response) => response,
+        Err(err) => err.downcast::<ErrorResponse>()?.0,
+    };
+    let headers = response
+        .headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+
+    Ok(ConstValue::String(serde_json::to_string(&FetchResponse {
+        status: response.status.as_u16(),
+        headers,
+        body: String::from_utf8_lossy(&response.body).into_owned(),
+    })?))
+}
+
+/// Keys of the entries set by the scripts, they are hashed apart from the
+/// keys of the resolvers sharing the cache.
+fn cache_key(key: &str) -> IoId {
+    let mut hasher = TailcallHasher::default();
+    "tailcall.cache".hash(&mut hasher);
+    key.hash(&mut hasher);
+    IoId::new(hasher.finish())
+}
+
+/// Result of an async operation started by a script, e.g. a `fetch`.
+struct Completion {
+    id: u64,
+    result: Result<ConstValue, String>,
+}
+
+/// Services of the host the scripts have access to.
+#[derive(Clone)]
+struct Host {
+    http: Arc<dyn HttpIO>,
+    env: Arc<dyn EnvIO>,
+    cache: Arc<EntityCache>,
+}
+
+impl From<&TargetRuntime> for Host {
+    fn from(runtime: &TargetRuntime) -> Self {
+        Self {
+            http: runtime.http.clone(),
+            env: runtime.env.clone(),
+            cache: runtime.cache.clone(),
+        }
+    }
+}
+
+/// Resolve and reject functions of the promise of an async operation.
+type Resolvers = (Persistent<Function<'static>>, Persistent<Function<'static>>);
+
+/// Async operations in flight, their promises are settled when the
+/// operation completes.
+struct Ops {
+    next_id: Cell<u64>,
+    resolvers: RefCell<HashMap<u64, Resolvers>>,
+    completions: mpsc::UnboundedSender<Completion>,
+    host: Host,
+}
+
+impl Ops {
+    /// Returns a promise that is settled with the output of the future.
+    fn promise<'js, F>(&self, ctx: &Ctx<'js>, future: F) -> rquickjs::Result<Promise<'js>>
+    where
+        F: Future<Output = anyhow::Result<ConstValue>> + Send + 'static,
+    {
+        let (promise, resolve, reject) = ctx.promise()?;
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        self.resolvers.borrow_mut().insert(
+            id,
+            (
+                Persistent::save(ctx, resolve),
+                Persistent::save(ctx, reject),
+            ),
+        );
 
-fn setup_builtins(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
+        let completions = self.completions.clone();
+        tokio::spawn(async move {
+            let result = future.await.map_err(|err| err.to_string());
+            let _ = completions.send(Completion { id, result });
+        });
+
+        Ok(promise)
+    }
+
+    fn complete(&self, ctx: &Ctx<'_>, completion: Completion) -> rquickjs::Result<()> {
+        let Some((resolve, reject)) = self.resolvers.borrow_mut().remove(&completion.id) else {
+            return Ok(());
+        };
+
+        match completion.result {
+            Ok(value) => resolve.restore(ctx)?.call((JsonValue(value),)),
+            Err(message) => reject.restore(ctx)?.call((message,)),
+        }
+    }
+}
+
+fn setup_builtins<'js>(
+    ctx: &Ctx<'js>,
+    ops: Rc<Ops>,
+    config: ConstValue,
+    env_names: BTreeSet<String>,
+) -> rquickjs::Result<()> {
     ctx.globals().set("__qjs_print", js_qjs_print)?;
     let _: Value = ctx.eval(CONSOLE_JS)?;
 
+    let fetch_ops = ops.clone();
+    let qjs_fetch = Function::new(ctx.clone(), move |ctx: Ctx<'js>, request: String| {
+        fetch_ops.promise(&ctx, fetch(fetch_ops.host.http.clone(), request))
+    })?;
+    ctx.globals().set("__qjs_fetch", qjs_fetch)?;
+    let _: Value = ctx.eval(FETCH_JS)?;
+
+    let sleep_ops = ops.clone();
+    let qjs_sleep = Function::new(ctx.clone(), move |ctx: Ctx<'js>, delay: f64| {
+        let delay = Duration::from_millis(delay.max(0.0) as u64);
+        sleep_ops.promise(&ctx, async move {
+            tokio::time::sleep(delay).await;
+            Ok(ConstValue::Null)
+        })
+    })?;
+    ctx.globals().set("__qjs_sleep", qjs_sleep)?;
+    let _: Value = ctx.eval(TIMERS_JS)?;
+
+    let get_ops = ops.clone();
+    let qjs_cache_get = Function::new(ctx.clone(), move |ctx: Ctx<'js>, key: String| {
+        let cache = get_ops.host.cache.clone();
+        get_ops.promise(&ctx, async move {
+            let value = cache
+                .get(&cache_key(&key))
+                .await
+                .map_err(|err| anyhow::anyhow!("{}", err))?;
+            Ok(value.unwrap_or_default())
+        })
+    })?;
+    ctx.globals().set("__qjs_cache_get", qjs_cache_get)?;
+
+    let set_ops = ops.clone();
+    let qjs_cache_set = Function::new(
+        ctx.clone(),
+        move |ctx: Ctx<'js>, key: String, value: JsonValue, ttl: f64| {
+            let cache = set_ops.host.cache.clone();
+            set_ops.promise(&ctx, async move {
+                let ttl = NonZeroU64::new(ttl.max(0.0) as u64).ok_or(anyhow::anyhow!(
+                    "The ttl of a cache entry must be a positive number"
+                ))?;
+                cache
+                    .set(cache_key(&key), value.0, ttl)
+                    .await
+                    .map_err(|err| anyhow::anyhow!("{}", err))?;
+                Ok(ConstValue::Null)
+            })
+        },
+    )?;
+    ctx.globals().set("__qjs_cache_set", qjs_cache_set)?;
+
+    let env = ops.host.env.clone();
+    // only the allow-listed variables are readable, the others hold the
+    // secrets of the server as well
+    let qjs_env = Function::new(ctx.clone(), move |name: String| {
+        if !env_names.contains(&name) {
+            return None;
+        }
+        env.get(&name).map(|value| value.into_owned())
+    })?;
+    ctx.globals().set("__qjs_env", qjs_env)?;
+    ctx.globals().set("__qjs_config", JsonValue(config))?;
+    let _: Value = ctx.eval(TAILCALL_JS)?;
+
+    Ok(())
+}
+
+/// Scripts with `import` or `export` declarations are evaluated as ES
+/// modules. The syntax errors are left for QuickJS to report.
+fn is_module(source: &str) -> bool {
+    let cm: Lrc<SourceMap> = Default::default();
+    let file = cm.new_source_file(FileName::Anon.into(), source.to_string());
+    let lexer = Lexer::new(
+        Syntax::Es(Default::default()),
+        EsVersion::latest(),
+        StringInput::from(&*file),
+        None,
+    );
+
+    matches!(
+        Parser::new_from(lexer).parse_program(),
+        Ok(Program::Module(_))
+    )
+}
+
+/// Evaluates the entry script, the exports of a module become globals so
+/// that they can be called by name.
+fn eval_script<'js>(ctx: &Ctx<'js>, source: String) -> rquickjs::Result<()> {
+    if !is_module(&source) {
+        return ctx.eval(source);
+    }
+
+    let (module, promise) = Module::declare(ctx.clone(), "main", source)?.eval()?;
+    promise.finish::<()>()?;
+    for export in module.namespace()?.props::<String, Value>() {
+        let (name, value) = export?;
+        ctx.globals().set(name, value)?;
+    }
+
     Ok(())
 }
 
-impl TryFrom<blueprint::Script> for LocalRuntime {
-    type Error = anyhow::Error;
+fn error_message(ctx: &Ctx<'_>, error: rquickjs::Error) -> String {
+    match error {
+        rquickjs::Error::Exception => reason_message(ctx.catch()),
+        error => error.to_string(),
+    }
+}
+
+fn reason_message(reason: Value<'_>) -> String {
+    if let Some(exception) = reason.as_exception() {
+        exception.message().unwrap_or_default()
+    } else if let Some(message) = reason.as_string().and_then(|s| s.to_string().ok()) {
+        message
+    } else {
+        format!("{:?}", reason)
+    }
+}
+
+/// Allocator of the JS runtime that enforces its memory limit, unlike the
+/// limit of QuickJS it records when an allocation is refused.
+struct LimitedAllocator {
+    max_memory: usize,
+    used: usize,
+    exceeded: Rc<Cell<bool>>,
+}
+
+impl LimitedAllocator {
+    fn reserve(&mut self, size: usize) -> bool {
+        let available = self.used.saturating_add(size) <= self.max_memory;
+        if !available {
+            self.exceeded.set(true);
+        }
+        available
+    }
+}
+
+unsafe impl Allocator for LimitedAllocator {
+    fn alloc(&mut self, size: usize) -> *mut u8 {
+        if !self.reserve(size) {
+            return ptr::null_mut();
+        }
+        let ptr = RustAllocator.alloc(size);
+        if !ptr.is_null() {
+            self.used += unsafe { RustAllocator::usable_size(ptr) };
+        }
+        ptr
+    }
 
-    fn try_from(script: blueprint::Script) -> Result<Self, Self::Error> {
-        let source = script.source;
-        let js_runtime = rquickjs::Runtime::new()?;
-        let context = Context::full(&js_runtime)?;
-        let _: () = context.with(|ctx| {
-            setup_builtins(&ctx)?;
-            ctx.eval(source)
-        })?;
+    fn calloc(&mut self, count: usize, size: usize) -> *mut u8 {
+        if !self.reserve(count.saturating_mul(size)) {
+            return ptr::null_mut();
+        }
+        let ptr = RustAllocator.calloc(count, size);
+        if !ptr.is_null() {
+            self.used += unsafe { RustAllocator::usable_size(ptr) };
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&mut self, ptr: *mut u8) {
+        self.used -= RustAllocator::usable_size(ptr);
+        RustAllocator.dealloc(ptr);
+    }
+
+    unsafe fn realloc(&mut self, ptr: *mut u8, new_size: usize) -> *mut u8 {
+        let size = RustAllocator::usable_size(ptr);
+        if new_size > size && !self.reserve(new_size - size) {
+            return ptr::null_mut();
+        }
+        let new_ptr = RustAllocator.realloc(ptr, new_size);
+        if !new_ptr.is_null() {
+            self.used = self.used - size + RustAllocator::usable_size(new_ptr);
+        }
+        new_ptr
+    }
+
+    unsafe fn usable_size(ptr: *mut u8) -> usize {
+        RustAllocator::usable_size(ptr)
+    }
+}
+
+struct LocalRuntime {
+    runtime: rquickjs::Runtime,
+    context: Context,
+    ops: Rc<Ops>,
+    timeout: Duration,
+    call_timeout: Duration,
+    max_memory: Option<usize>,
+    // the interrupt handler stops the JS code running past it
+    deadline: Rc<Cell<Option<Instant>>>,
+    // set when the JS code was stopped by the interrupt handler
+    interrupted: Rc<Cell<bool>>,
+    // set when the allocator refused an allocation
+    out_of_memory: Rc<Cell<bool>>,
+}
+
+impl LocalRuntime {
+    fn new(
+        script: blueprint::Script,
+        host: Host,
+        completions: mpsc::UnboundedSender<Completion>,
+    ) -> anyhow::Result<Self> {
+        let out_of_memory = Rc::new(Cell::new(false));
+        let runtime = match script.max_memory {
+            Some(max_memory) => rquickjs::Runtime::new_with_alloc(LimitedAllocator {
+                max_memory,
+                used: 0,
+                exceeded: out_of_memory.clone(),
+            })?,
+            None => rquickjs::Runtime::new()?,
+        };
+        let deadline: Rc<Cell<Option<Instant>>> = Rc::new(Cell::new(None));
+        let interrupted = Rc::new(Cell::new(false));
+        let interrupt_deadline = deadline.clone();
+        let interrupt_flag = interrupted.clone();
+        runtime.set_interrupt_handler(Some(Box::new(move || {
+            let interrupt = interrupt_deadline
+                .get()
+                .is_some_and(|deadline| Instant::now() > deadline);
+            if interrupt {
+                interrupt_flag.set(true);
+            }
+            interrupt
+        })));
+
+        let mut resolver = BuiltinResolver::default();
+        let mut loader = BuiltinLoader::default();
+        for (name, source) in script.modules {
+            resolver = resolver.with_module(name.as_str());
+            loader = loader.with_module(name.as_str(), source);
+        }
+        runtime.set_loader(resolver, loader);
+
+        let context = Context::full(&runtime)?;
+        let ops = Rc::new(Ops {
+            next_id: Cell::new(0),
+            resolvers: RefCell::new(HashMap::new()),
+            completions,
+            host,
+        });
+        // the top level of the script is interrupted like a function
+        deadline.set(Some(Instant::now() + script.timeout));
+        let result = context.with(|ctx| {
+            setup_builtins(&ctx, ops.clone(), script.config, script.env)?;
+            eval_script(&ctx, script.source)
+                .map_err(|err| anyhow::anyhow!(error_message(&ctx, err)))
+        });
+        deadline.set(None);
+        result?;
 
         tracing::debug!("JS Runtime created: {:?}", thread::current().name());
-        Ok(Self(context))
+        Ok(Self {
+            runtime,
+            context,
+            ops,
+            timeout: script.timeout,
+            call_timeout: script.call_timeout,
+            max_memory: script.max_memory,
+            deadline,
+            interrupted,
+            out_of_memory,
+        })
+    }
+
+    /// Runs JS code, interrupting it once it runs past the timeout.
+    fn limited<A>(&self, f: impl FnOnce() -> A) -> A {
+        self.deadline.set(Some(Instant::now() + self.timeout));
+        let output = f();
+        self.deadline.set(None);
+        output
+    }
+
+    /// Forgets the limits the JS code ran past, before handling the next
+    /// call or completion.
+    fn reset_limits(&self) {
+        self.interrupted.set(false);
+        self.out_of_memory.set(false);
+    }
+
+    /// Error of a function that failed while the JS code ran past one of the
+    /// limits of the runtime.
+    fn limit_exceeded(&self, name: &str) -> Option<worker::Error> {
+        let limit = if self.interrupted.get() {
+            Limit::Time(self.timeout)
+        } else {
+            match self.max_memory {
+                Some(max_memory) if self.out_of_memory.get() => Limit::Memory(max_memory),
+                _ => return None,
+            }
+        };
+        Some(worker::Error::LimitExceeded(name.to_string(), limit))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn reply<'js>(
+        &self,
+        ctx: &Ctx<'js>,
+        reply: Reply,
+        name: &str,
+        result: Result<Value<'js>, String>,
+    ) {
+        // unlike the other errors, running past a limit fails the hooks too
+        match result
+            .as_ref()
+            .err()
+            .and_then(|_| self.limit_exceeded(name))
+        {
+            Some(error) => reply.fail(error),
+            None => reply.complete(ctx, name, result),
+        }
+    }
+
+    fn run_jobs(&self) {
+        loop {
+            match self.limited(|| self.runtime.execute_pending_job()) {
+                Ok(true) => continue,
+                Ok(false) => break,
+                Err(_) => tracing::warn!("Uncaught exception in a JS job"),
+            }
+        }
+    }
+
+    fn start(&self, call: Call, pending: &mut Vec<PendingCall>) {
+        self.context.with(|ctx| {
+            let Call { name, input, reply } = call;
+            match self.limited(|| input.invoke(&ctx, &name)) {
+                Err(error) => reply.fail(error),
+                Ok(Ok(value)) if value.is_promise() => match Promise::from_js(&ctx, value) {
+                    Ok(promise) => pending.push(PendingCall {
+                        promise: Persistent::save(&ctx, promise),
+                        name,
+                        reply,
+                        deadline: Instant::now() + self.call_timeout,
+                    }),
+                    Err(err) => reply.fail(worker::Error::Rquickjs(err.to_string())),
+                },
+                Ok(result) => {
+                    let result = result.map_err(|err| error_message(&ctx, err));
+                    self.reply(&ctx, reply, &name, result)
+                }
+            }
+        })
+    }
+
+    fn complete(&self, completion: Completion) {
+        self.context.with(|ctx| {
+            if let Err(err) = self.limited(|| self.ops.complete(&ctx, completion)) {
+                tracing::warn!(
+                    "Failed to settle a JS promise: {}",
+                    error_message(&ctx, err)
+                );
+            }
+        })
+    }
+
+    /// Replies to the calls whose promise is settled.
+    fn settle(&self, pending: &mut Vec<PendingCall>) {
+        self.context.with(|ctx| {
+            for call in std::mem::take(pending) {
+                let promise = match call.promise.restore(&ctx) {
+                    Ok(promise) => promise,
+                    Err(err) => {
+                        call.reply.fail(worker::Error::Rquickjs(err.to_string()));
+                        continue;
+                    }
+                };
+
+                match promise.result::<Value>() {
+                    // the promise might never settle, e.g. an unresolved
+                    // `new Promise(() => {})`
+                    None if Instant::now() >= call.deadline => {
+                        let limit = Limit::CallTime(self.call_timeout);
+                        call.reply
+                            .fail(worker::Error::LimitExceeded(call.name, limit));
+                    }
+                    None => pending.push(PendingCall {
+                        promise: Persistent::save(&ctx, promise),
+                        name: call.name,
+                        reply: call.reply,
+                        deadline: call.deadline,
+                    }),
+                    Some(result) => {
+                        let result = result.map_err(|err| error_message(&ctx, err));
+                        self.reply(&ctx, call.reply, &call.name, result)
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Arguments of a call to a global JS function.
+#[allow(clippy::large_enum_variant)]
+enum Input {
+    Event(Event),
+    Value(String),
+}
+
+impl Input {
+    fn invoke<'js>(
+        self,
+        ctx: &Ctx<'js>,
+        name: &str,
+    ) -> Result<rquickjs::Result<Value<'js>>, worker::Error> {
+        let function = ctx
+            .globals()
+            .get::<&str, Function>(name)
+            .map_err(|e| worker::Error::GlobalThisNotInitialised(e.to_string()))?;
+
+        match self {
+            Input::Event(event) => {
+                let args = match event {
+                    Event::Request(req) => prepare_args(ctx, "request", req)
+                        .map_err(|e| worker::Error::Rquickjs(e.to_string()))?,
+                    Event::Response(resp) => prepare_args(ctx, "response", resp)
+                        .map_err(|e| worker::Error::Rquickjs(e.to_string()))?,
+                };
+                Ok(function.call(args))
+            }
+            Input::Value(value) => Ok(function.call((value,))),
+        }
+    }
+}
+
+/// Channel the result of a call is sent back on.
+enum Reply {
+    Command(oneshot::Sender<Result<Option<Command>, worker::Error>>),
+    Value(oneshot::Sender<Result<ConstValue, worker::Error>>),
+}
+
+impl Reply {
+    fn fail(self, error: worker::Error) {
+        match self {
+            Reply::Command(sender) => {
+                let _ = sender.send(Err(error));
+            }
+            Reply::Value(sender) => {
+                let _ = sender.send(Err(error));
+            }
+        }
+    }
+
+    /// Converts the value returned by the function, or the value its promise
+    /// resolved to.
+    fn complete<'js>(self, ctx: &Ctx<'js>, name: &str, result: Result<Value<'js>, String>) {
+        match self {
+            Reply::Command(sender) => {
+                // a function that throws leaves the request untouched
+                let command = result
+                    .ok()
+                    .map(|output| Command::from_js(ctx, output))
+                    .transpose()
+                    .map_err(|e| worker::Error::DeserializeFailed(e.to_string()));
+                let _ = sender.send(command);
+            }
+            Reply::Value(sender) => {
+                let value = result
+                    .and_then(|output| String::from_js(ctx, output).map_err(|e| e.to_string()))
+                    .map_err(|e| worker::Error::FunctionValueParseError(e, name.to_string()))
+                    .and_then(|value| Ok(serde_json::from_str(&value)?));
+                let _ = sender.send(value);
+            }
+        }
+    }
+}
+
+struct Call {
+    name: String,
+    input: Input,
+    reply: Reply,
+}
+
+/// Call whose function returned a promise that isn't settled yet.
+struct PendingCall {
+    promise: Persistent<Promise<'static>>,
+    name: String,
+    reply: Reply,
+    // the call fails if its promise isn't settled by then
+    deadline: Instant,
+}
+
+/// Runs the calls on the JS runtime, settling the promises of the async
+/// operations as they complete.
+async fn event_loop(
+    script: blueprint::Script,
+    host: Host,
+    mut calls: mpsc::UnboundedReceiver<Call>,
+) {
+    let (completions_sender, mut completions) = mpsc::unbounded_channel();
+    let runtime = match LocalRuntime::new(script, host, completions_sender) {
+        Ok(runtime) => runtime,
+        Err(err) => {
+            let error = worker::Error::from(err);
+            while let Some(call) = calls.recv().await {
+                call.reply.fail(error.clone());
+            }
+            return;
+        }
+    };
+
+    let mut pending: Vec<PendingCall> = vec![];
+    loop {
+        runtime.reset_limits();
+
+        // wakes up to fail the calls that are still pending at their deadline
+        let deadline = pending.iter().map(|call| call.deadline).min();
+        let expired = async {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            call = calls.recv() => match call {
+                Some(call) => runtime.start(call, &mut pending),
+                None => break,
+            },
+            Some(completion) = completions.recv() => runtime.complete(completion),
+            _ = expired => {}
+        }
+
+        runtime.run_jobs();
+        runtime.settle(&mut pending);
     }
 }
 
 pub struct Runtime {
     script: blueprint::Script,
-    // Single threaded JS runtime, that's shared across all tokio workers.
-    tokio_runtime: Option<tokio::runtime::Runtime>,
+    // Calls are spread over a pool of single threaded JS runtimes, that's
+    // shared across all tokio workers.
+    senders: Vec<mpsc::UnboundedSender<Call>>,
+    next: AtomicUsize,
 }
 
 impl Debug for Runtime {
@@ -63,42 +725,49 @@ impl Debug for Runtime {
 }
 
 impl Runtime {
-    pub fn new(script: blueprint::Script) -> Self {
-        let tokio_runtime = tokio::runtime::Builder::new_multi_thread()
-            .worker_threads(1)
-            .build()
-            .expect("JS runtime not initialized");
+    /// Creates the runtime, its scripts use the http client, env and cache
+    /// of the target runtime.
+    pub fn new(script: blueprint::Script, runtime: &TargetRuntime) -> Self {
+        let host = Host::from(runtime);
+        let senders = (0..script.workers.max(1))
+            .map(|i| {
+                let (sender, calls) = mpsc::unbounded_channel();
+                let loop_script = script.clone();
+                let host = host.clone();
+                thread::Builder::new()
+                    .name(format!("tailcall-js-{}", i))
+                    .spawn(move || {
+                        let runtime = tokio::runtime::Builder::new_current_thread()
+                            .enable_all()
+                            .build()
+                            .expect("JS runtime not initialized");
+                        // the thread exits once the Runtime, and its sender, is dropped
+                        runtime.block_on(event_loop(loop_script, host, calls));
+                    })
+                    .expect("JS runtime not initialized");
+                sender
+            })
+            .collect();
 
-        Self { script, tokio_runtime: Some(tokio_runtime) }
+        Self { script, senders, next: AtomicUsize::new(0) }
     }
-}
 
-impl Drop for Runtime {
-    fn drop(&mut self) {
-        // implicit call implementation to shutdown the tokio runtime
-        // without blocking. Otherwise it will panic on an attempt to
-        // drop AppContext in async runtime (e.g. in tests at least)
-        if let Some(runtime) = self.tokio_runtime.take() {
-            runtime.shutdown_background();
-        }
+    fn send(&self, name: &str, input: Input, reply: Reply) -> Result<(), worker::Error> {
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % self.senders.len();
+        self.senders[i]
+            .send(Call { name: name.to_string(), input, reply })
+            .map_err(|_| worker::Error::JsRuntimeStopped)
     }
 }
 
 #[async_trait::async_trait]
 impl WorkerIO<Event, Command> for Runtime {
     async fn call(&self, name: &str, event: Event) -> Result<Option<Command>, worker::Error> {
-        let script = self.script.clone();
-        let name = name.to_string(); // TODO
-        if let Some(runtime) = &self.tokio_runtime {
-            runtime
-                .spawn(async move {
-                    init_rt(script)?;
-                    call(name, event)
-                })
-                .await?
-        } else {
-            Err(worker::Error::JsRuntimeStopped)
-        }
+        let (sender, receiver) = oneshot::channel();
+        self.send(name, Input::Event(event), Reply::Command(sender))?;
+        receiver
+            .await
+            .map_err(|_| worker::Error::JsRuntimeStopped)?
     }
 }
 
@@ -109,38 +778,16 @@ impl WorkerIO<ConstValue, ConstValue> for Runtime {
         name: &str,
         input: ConstValue,
     ) -> Result<Option<ConstValue>, worker::Error> {
-        let script = self.script.clone();
-        let name = name.to_string();
         let value = serde_json::to_string(&input)?;
-        if let Some(runtime) = &self.tokio_runtime {
-            runtime
-                .spawn(async move {
-                    init_rt(script)?;
-                    execute_inner(name, value).map(Some)
-                })
-                .await?
-        } else {
-            Err(worker::Error::JsRuntimeStopped)
-        }
+        let (sender, receiver) = oneshot::channel();
+        self.send(name, Input::Value(value), Reply::Value(sender))?;
+        receiver
+            .await
+            .map_err(|_| worker::Error::JsRuntimeStopped)?
+            .map(Some)
     }
 }
 
-fn init_rt(script: blueprint::Script) -> anyhow::Result<()> {
-    // initialize runtime if this is the first call
-    // exit if failed to initialize
-    LOCAL_RUNTIME.with(move |cell| {
-        if cell.borrow().get().is_none() {
-            LocalRuntime::try_from(script).and_then(|runtime| {
-                cell.borrow().set(runtime).map_err(|_| {
-                    anyhow::anyhow!("trying to reinitialize an already initialized QuickJS runtime")
-                })
-            })
-        } else {
-            Ok(())
-        }
-    })
-}
-
 fn prepare_args<'js, T: IntoJs<'js>>(
     ctx: &Ctx<'js>,
     label: &str,
@@ -151,51 +798,278 @@ fn prepare_args<'js, T: IntoJs<'js>>(
     Ok((object.into_value(),))
 }
 
-fn call(name: String, event: Event) -> Result<Option<Command>, worker::Error> {
-    LOCAL_RUNTIME.with_borrow_mut(|cell| {
-        let runtime = cell.get_mut().ok_or(worker::Error::RuntimeNotInitialized)?;
-        runtime.0.with(|ctx| {
-            let fn_as_value = ctx
-                .globals()
-                .get::<&str, Function>(name.as_str())
-                .map_err(|e| worker::Error::GlobalThisNotInitialised(e.to_string()))?;
-
-            let function = fn_as_value
-                .as_function()
-                .ok_or(worker::Error::InvalidFunction(name))?;
-
-            let args = match event {
-                Event::Request(req) => prepare_args(&ctx, "request", req)
-                    .map_err(|e| worker::Error::Rquickjs(e.to_string()))?,
-                Event::Response(resp) => prepare_args(&ctx, "response", resp)
-                    .map_err(|e| worker::Error::Rquickjs(e.to_string()))?,
-            };
-
-            let command: Option<Value> = function.call(args).ok();
-            command
-                .map(|output| Command::from_js(&ctx, output))
-                .transpose()
-                .map_err(|e| worker::Error::DeserializeFailed(e.to_string()))
-        })
-    })
-}
-
-fn execute_inner(name: String, value: String) -> Result<ConstValue, worker::Error> {
-    LOCAL_RUNTIME.with_borrow_mut(|cell| {
-        let runtime = cell.get_mut().ok_or(worker::Error::RuntimeNotInitialized)?;
-        runtime.0.with(|ctx| {
-            let fn_as_value = ctx
-                .globals()
-                .get::<_, rquickjs::Function>(&name)
-                .map_err(|e| worker::Error::GlobalThisNotInitialised(e.to_string()))?;
-
-            let function = fn_as_value
-                .as_function()
-                .ok_or(worker::Error::InvalidFunction(name.clone()))?;
-            let val: String = function
-                .call((value,))
-                .map_err(|e| worker::Error::FunctionValueParseError(e.to_string(), name))?;
-            Ok::<_, worker::Error>(serde_json::from_str(&val)?)
-        })
-    })
+#[cfg(test)]
+mod tests {
+    use hyper::body::Bytes;
+    use serde_json::json;
+
+    use super::*;
+    use crate::core::http::Response;
+
+    struct EchoHttp;
+
+    #[async_trait::async_trait]
+    impl HttpIO for EchoHttp {
+        async fn execute(&self, request: reqwest::Request) -> anyhow::Result<Response<Bytes>> {
+            let body = Bytes::from(json!({ "url": request.url().as_str() }).to_string());
+            match request.url().path() {
+                "/missing" => {
+                    let response = Response {
+                        status: reqwest::StatusCode::NOT_FOUND,
+                        headers: Default::default(),
+                        body,
+                    };
+                    Err(anyhow::anyhow!("404 Not Found").context(ErrorResponse(response)))
+                }
+                "/down" => Err(anyhow::anyhow!("connection refused")),
+                _ => Ok(Response {
+                    status: reqwest::StatusCode::OK,
+                    headers: Default::default(),
+                    body,
+                }),
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_module() {
+        assert!(is_module(
+            "import {double} from \"math\"\nfunction main() {}"
+        ));
+        assert!(is_module("export function main() {}"));
+        assert!(is_module("function main() {}\nexport {main}"));
+        // the keywords aren't declarations here
+        assert!(!is_module("const text = `\nexport the data\n`"));
+        assert!(!is_module("// import {double} from \"math\""));
+        assert!(!is_module("const math = import(\"math\")"));
+    }
+
+    #[tokio::test]
+    async fn test_async_function() {
+        let source = r#"
+import {double} from "math"
+
+export async function resolve(input) {
+  const response = await fetch("http://localhost:3000/value")
+  const {url} = await response.json()
+  await new Promise((resolve) => setTimeout(resolve, 1))
+  return JSON.stringify({url, value: double(JSON.parse(input))})
+}
+"#;
+        let script = blueprint::Script {
+            source: source.to_string(),
+            timeout: Duration::from_secs(30),
+            call_timeout: Duration::from_secs(30),
+            max_memory: None,
+            workers: 1,
+            modules: BTreeMap::from([(
+                "math".to_string(),
+                "export const double = (x) => x * 2".to_string(),
+            )]),
+            config: ConstValue::Null,
+            env: BTreeSet::new(),
+        };
+        let mut target = crate::core::runtime::test::init(None);
+        target.http = Arc::new(EchoHttp);
+        let runtime = Runtime::new(script, &target);
+
+        let output = WorkerIO::<ConstValue, ConstValue>::call(&runtime, "resolve", 21.into())
+            .await
+            .unwrap();
+        let expected = json!({ "url": "http://localhost:3000/value", "value": 42 });
+        assert_eq!(output, Some(ConstValue::from_json(expected).unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_error_status() {
+        let source = r#"
+export async function missing() {
+  const response = await fetch("http://localhost:3000/missing")
+  const {url} = await response.json()
+  return JSON.stringify({status: response.status, ok: response.ok, url})
+}
+
+export async function down() {
+  try {
+    await fetch("http://localhost:3000/down")
+    return JSON.stringify("resolved")
+  } catch (error) {
+    return JSON.stringify(error.name)
+  }
+}
+"#;
+        let script = blueprint::Script {
+            source: source.to_string(),
+            timeout: Duration::from_secs(30),
+            call_timeout: Duration::from_secs(30),
+            max_memory: None,
+            workers: 1,
+            modules: BTreeMap::new(),
+            config: ConstValue::Null,
+            env: BTreeSet::new(),
+        };
+        let mut target = crate::core::runtime::test::init(None);
+        target.http = Arc::new(EchoHttp);
+        let runtime = Runtime::new(script, &target);
+
+        let output =
+            WorkerIO::<ConstValue, ConstValue>::call(&runtime, "missing", ConstValue::Null)
+                .await
+                .unwrap();
+        let expected =
+            json!({ "status": 404, "ok": false, "url": "http://localhost:3000/missing" });
+        assert_eq!(output, Some(ConstValue::from_json(expected).unwrap()));
+
+        let output = WorkerIO::<ConstValue, ConstValue>::call(&runtime, "down", ConstValue::Null)
+            .await
+            .unwrap();
+        assert_eq!(
+            output,
+            Some(ConstValue::from_json(json!("TypeError")).unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tailcall_api() {
+        let source = r#"
+export async function resolve(input) {
+  const key = `user:${JSON.parse(input)}`
+  const cached = await tailcall.cache.get(key)
+  if (cached !== null) {
+    return JSON.stringify({...cached, cached: true})
+  }
+  const user = {
+    id: JSON.parse(input),
+    port: tailcall.config.server.port,
+    home: "HOME" in tailcall.env,
+    path: tailcall.env.PATH ?? null,
+  }
+  await tailcall.cache.set(key, user, 1000)
+  return JSON.stringify(user)
+}
+
+export function mutate() {
+  tailcall.config.server.port = 1
+}
+"#;
+        let script = blueprint::Script {
+            source: source.to_string(),
+            timeout: Duration::from_secs(30),
+            call_timeout: Duration::from_secs(30),
+            max_memory: None,
+            workers: 1,
+            modules: BTreeMap::new(),
+            config: ConstValue::from_json(json!({ "server": { "port": 8000 } })).unwrap(),
+            env: BTreeSet::from(["HOME".to_string()]),
+        };
+        let target = crate::core::runtime::test::init(None);
+        let runtime = Runtime::new(script, &target);
+
+        let mut outputs = vec![];
+        for _ in 0..2 {
+            let output = WorkerIO::<ConstValue, ConstValue>::call(&runtime, "resolve", 1.into())
+                .await
+                .unwrap();
+            outputs.push(output.unwrap());
+        }
+        let home = std::env::var("HOME").is_ok();
+        assert_eq!(
+            outputs[0],
+            ConstValue::from_json(json!({ "id": 1, "port": 8000, "home": home, "path": null }))
+                .unwrap()
+        );
+        assert_eq!(
+            outputs[1],
+            ConstValue::from_json(
+                json!({ "id": 1, "port": 8000, "home": home, "path": null, "cached": true })
+            )
+            .unwrap()
+        );
+
+        let error = WorkerIO::<ConstValue, ConstValue>::call(&runtime, "mutate", ConstValue::Null)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("read-only"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_limits() {
+        let source = r#"
+function spin() {
+  while (true) {}
+}
+
+function allocate() {
+  let text = "tailcall"
+  while (true) {
+    text = text + text
+  }
+}
+
+function answer() {
+  return JSON.stringify(42)
+}
+
+function never() {
+  return new Promise(() => {})
+}
+
+function fail() {
+  throw new Error("interrupted")
+}
+"#;
+        let script = blueprint::Script {
+            source: source.to_string(),
+            timeout: Duration::from_millis(50),
+            call_timeout: Duration::from_millis(100),
+            max_memory: Some(16 << 20),
+            workers: 2,
+            modules: BTreeMap::new(),
+            config: ConstValue::Null,
+            env: BTreeSet::new(),
+        };
+        let target = crate::core::runtime::test::init(None);
+        let runtime = Runtime::new(script, &target);
+
+        let error = WorkerIO::<ConstValue, ConstValue>::call(&runtime, "spin", ConstValue::Null)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "spin exceeded the time limit of 50ms");
+
+        let error =
+            WorkerIO::<ConstValue, ConstValue>::call(&runtime, "allocate", ConstValue::Null)
+                .await
+                .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "allocate exceeded the memory limit of 16777216 bytes"
+        );
+
+        let error = WorkerIO::<ConstValue, ConstValue>::call(&runtime, "never", ConstValue::Null)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "never exceeded the call time limit of 100ms"
+        );
+
+        // the errors of the script aren't mistaken for a limit
+        let error = WorkerIO::<ConstValue, ConstValue>::call(&runtime, "fail", ConstValue::Null)
+            .await
+            .unwrap_err();
+        assert!(
+            !matches!(error, worker::Error::LimitExceeded(..)),
+            "{}",
+            error
+        );
+
+        // both runtimes of the pool keep serving calls
+        for _ in 0..2 {
+            let output =
+                WorkerIO::<ConstValue, ConstValue>::call(&runtime, "answer", ConstValue::Null)
+                    .await
+                    .unwrap();
+            assert_eq!(output, Some(ConstValue::from(42)));
+        }
+    }
 }
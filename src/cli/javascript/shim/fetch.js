@@ -0,0 +1,37 @@
+class Response {
+  constructor({status, headers, body}) {
+    this.status = status
+    this.ok = status >= 200 && status < 300
+    this.headers = headers
+    this.body = body
+  }
+
+  async text() {
+    return this.body
+  }
+
+  async json() {
+    return JSON.parse(this.body)
+  }
+}
+
+// Requests go through the upstream HTTP client. Like the standard `fetch`, it
+// resolves with the responses of every status and rejects on network failures.
+function fetch(input, init = {}) {
+  const request = {
+    url: String(input),
+    method: init.method || "GET",
+    headers: init.headers || {},
+    body: init.body === undefined || init.body === null ? null : String(init.body),
+  }
+
+  return globalThis.__qjs_fetch(JSON.stringify(request)).then(
+    (response) => new Response(JSON.parse(response)),
+    (error) => {
+      throw new TypeError(error)
+    },
+  )
+}
+
+globalThis.Response = Response
+globalThis.fetch = fetch
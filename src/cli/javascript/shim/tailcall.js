@@ -0,0 +1,39 @@
+function deepFreeze(value) {
+  if (value !== null && typeof value === "object") {
+    Object.values(value).forEach(deepFreeze)
+    Object.freeze(value)
+  }
+  return value
+}
+
+function readOnly() {
+  throw new TypeError("tailcall.env is read-only")
+}
+
+// Entries live in the cache of the server, so they outlive the call that set
+// them. `get` resolves to null for the keys that aren't cached.
+const tailcallCache = Object.freeze({
+  get(key) {
+    return globalThis.__qjs_cache_get(String(key))
+  },
+  set(key, value, ttl = 60000) {
+    return globalThis.__qjs_cache_set(String(key), value, Number(ttl))
+  },
+})
+
+const tailcallEnv = new Proxy(
+  {},
+  {
+    get: (_, name) => (typeof name === "string" ? globalThis.__qjs_env(name) : undefined),
+    has: (_, name) => typeof name === "string" && globalThis.__qjs_env(name) !== undefined,
+    set: readOnly,
+    deleteProperty: readOnly,
+    defineProperty: readOnly,
+  },
+)
+
+globalThis.tailcall = Object.freeze({
+  cache: tailcallCache,
+  env: tailcallEnv,
+  config: deepFreeze(globalThis.__qjs_config),
+})
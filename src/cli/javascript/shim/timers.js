@@ -0,0 +1,20 @@
+let nextTimerId = 1
+const timers = new Set()
+
+function setTimeout(callback, delay = 0, ...args) {
+  const id = nextTimerId++
+  timers.add(id)
+  globalThis.__qjs_sleep(Number(delay) || 0).then(() => {
+    if (timers.delete(id)) {
+      callback(...args)
+    }
+  })
+  return id
+}
+
+function clearTimeout(id) {
+  timers.delete(id)
+}
+
+globalThis.setTimeout = setTimeout
+globalThis.clearTimeout = clearTimeout
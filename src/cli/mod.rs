@@ -5,6 +5,8 @@ pub mod generator;
 pub mod javascript;
 mod llm;
 pub mod metrics;
+#[cfg(feature = "plugin")]
+pub mod plugin;
 pub mod runtime;
 pub mod server;
 mod tc;
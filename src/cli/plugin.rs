@@ -0,0 +1,479 @@
+//! WebAssembly plugins linked with `@link(type: Plugin)`.
+//!
+//! A plugin is a WASI (preview 1) module exporting its `memory`, an
+//! `alloc(len: i32) -> i32` function and any number of functions with the
+//! signature `(ptr: i32, len: i32) -> i64`. The input of a function is JSON
+//! written in the memory returned by `alloc`, the output is JSON located by
+//! the returned value as `ptr << 32 | len`, a length of `0` means no output.
+//!
+//! Resolvers referenced by `@js(name:)` receive and return GraphQL values,
+//! the `onRequest` / `onResponseBody` hooks receive `{"request": {..}}` or
+//! `{"response": {..}}` and return either of them, the same shapes the JS
+//! hooks work with. Functions of a plugin linked with an `id` can also be
+//! referenced as `<id>.<function>`.
+//!
+//! Every call runs in a new instance with a fuel and memory budget, so a
+//! plugin can't keep state across calls nor run away with the server.
+//!
+//! Custom scalars are validated by the functions mapped to them in the `meta`
+//! of the link, e.g. `meta: {scalars: {Email: "is_email"}}`. Such a function
+//! receives the value of an argument and returns `true` when it's valid, the
+//! arguments are validated before the operation is executed.
+
+use std::collections::{BTreeMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context};
+use async_graphql_value::ConstValue;
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use wasmtime::{
+    Config, Engine, ExternType, InstancePre, Linker, Module, Store, StoreLimits,
+    StoreLimitsBuilder, Trap,
+};
+use wasmtime_wasi::preview1::WasiP1Ctx;
+use wasmtime_wasi::WasiCtxBuilder;
+
+use crate::core::http::Response;
+use crate::core::worker::{Command, Event, Uri, WorkerRequest, WorkerResponse};
+use crate::core::{blueprint, worker, WorkerIO};
+
+struct State {
+    wasi: WasiP1Ctx,
+    limits: StoreLimits,
+}
+
+pub struct Plugin {
+    id: Option<String>,
+    engine: Engine,
+    instance: InstancePre<State>,
+    functions: HashSet<String>,
+    fuel: u64,
+    max_memory: usize,
+}
+
+impl Plugin {
+    pub fn try_new(plugin: &blueprint::Plugin) -> anyhow::Result<Self> {
+        let bytes = std::fs::read(&plugin.path)
+            .with_context(|| format!("Failed to read plugin {}", plugin.path))?;
+        Self::from_bytes(plugin, &bytes)
+    }
+
+    fn from_bytes(plugin: &blueprint::Plugin, bytes: &[u8]) -> anyhow::Result<Self> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config)?;
+        let module = Module::new(&engine, bytes)?;
+
+        let mut linker = Linker::<State>::new(&engine);
+        wasmtime_wasi::preview1::add_to_linker_sync(&mut linker, |state: &mut State| {
+            &mut state.wasi
+        })?;
+        let instance = linker.instantiate_pre(&module)?;
+
+        let functions = module
+            .exports()
+            .filter(|export| matches!(export.ty(), ExternType::Func(_)))
+            .map(|export| export.name().to_string())
+            .filter(|name| name != "alloc" && name != "_initialize")
+            .collect();
+
+        Ok(Self {
+            id: plugin.id.clone(),
+            engine,
+            instance,
+            functions,
+            fuel: plugin.fuel,
+            max_memory: plugin.max_memory,
+        })
+    }
+
+    /// Name of the exported function `name` refers to.
+    fn function<'a>(&self, name: &'a str) -> Option<&'a str> {
+        let name = match &self.id {
+            Some(id) => name
+                .strip_prefix(id.as_str())
+                .and_then(|name| name.strip_prefix('.'))
+                .unwrap_or(name),
+            None => name,
+        };
+        self.functions.contains(name).then_some(name)
+    }
+
+    fn call_sync(&self, name: &str, input: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        let state = State {
+            wasi: WasiCtxBuilder::new().build_p1(),
+            limits: StoreLimitsBuilder::new()
+                .memory_size(self.max_memory)
+                .build(),
+        };
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(self.fuel)?;
+
+        let instance = self.instance.instantiate(&mut store)?;
+        if let Ok(initialize) = instance.get_typed_func::<(), ()>(&mut store, "_initialize") {
+            initialize.call(&mut store, ())?;
+        }
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or(anyhow!("Plugin doesn't export its memory"))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let function = instance.get_typed_func::<(i32, i32), i64>(&mut store, name)?;
+
+        let len = i32::try_from(input.len())?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory.write(&mut store, ptr as usize, input)?;
+
+        let output = function.call(&mut store, (ptr, len))? as u64;
+        let (ptr, len) = ((output >> 32) as usize, (output & 0xffff_ffff) as usize);
+        if len == 0 {
+            return Ok(None);
+        }
+        if ptr.saturating_add(len) > memory.data_size(&store) {
+            return Err(anyhow!("Output of {} is out of the plugin memory", name));
+        }
+        let mut buf = vec![0; len];
+        memory.read(&store, ptr, &mut buf)?;
+
+        Ok(Some(buf))
+    }
+
+    async fn call(
+        self: Arc<Self>,
+        name: String,
+        input: Vec<u8>,
+    ) -> Result<Option<Vec<u8>>, worker::Error> {
+        tokio::task::spawn_blocking(move || {
+            self.call_sync(&name, &input).map_err(|error| {
+                if error.downcast_ref::<Trap>() == Some(&Trap::OutOfFuel) {
+                    anyhow!("Plugin function {} ran out of fuel", name)
+                } else {
+                    error.context(format!("Plugin function {} failed", name))
+                }
+            })
+        })
+        .await?
+        .map_err(worker::Error::from)
+    }
+}
+
+/// Loads the plugins, fails on the first one that can't be compiled.
+pub fn init_plugins(plugins: &[blueprint::Plugin]) -> anyhow::Result<Vec<Arc<Plugin>>> {
+    plugins
+        .iter()
+        .map(|plugin| {
+            Plugin::try_new(plugin)
+                .map(Arc::new)
+                .with_context(|| format!("Failed to load plugin {}", plugin.path))
+        })
+        .collect()
+}
+
+/// Calls the functions exported by the plugins, the other functions are
+/// called on the script worker if there is one.
+pub struct PluginWorker<In, Out> {
+    plugins: Vec<Arc<Plugin>>,
+    fallback: Option<Arc<dyn WorkerIO<In, Out>>>,
+}
+
+impl<In: Send + 'static, Out: Send + 'static> PluginWorker<In, Out> {
+    pub fn new(plugins: Vec<Arc<Plugin>>, fallback: Option<Arc<dyn WorkerIO<In, Out>>>) -> Self {
+        Self { plugins, fallback }
+    }
+
+    fn find(&self, name: &str) -> Option<(Arc<Plugin>, String)> {
+        self.plugins.iter().find_map(|plugin| {
+            plugin
+                .function(name)
+                .map(|function| (plugin.clone(), function.to_string()))
+        })
+    }
+
+    async fn call_fallback(&self, name: &str, input: In) -> Result<Option<Out>, worker::Error> {
+        match &self.fallback {
+            Some(worker) => worker.call(name, input).await,
+            None => Err(worker::Error::InvalidFunction(name.to_string())),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum Message {
+    Request(RequestMessage),
+    Response(ResponseMessage),
+}
+
+#[derive(Serialize, Deserialize)]
+struct RequestMessage {
+    uri: Uri,
+    method: String,
+    headers: BTreeMap<String, String>,
+    body: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ResponseMessage {
+    status: u16,
+    headers: BTreeMap<String, String>,
+    body: Option<String>,
+}
+
+impl TryFrom<Event> for Message {
+    type Error = worker::Error;
+
+    fn try_from(event: Event) -> Result<Self, worker::Error> {
+        Ok(match event {
+            Event::Request(request) => Message::Request(RequestMessage {
+                uri: request.uri(),
+                method: request.method(),
+                headers: request.headers()?,
+                body: request.body(),
+            }),
+            Event::Response(response) => Message::Response(ResponseMessage {
+                status: response.status(),
+                headers: response.headers(),
+                body: response.body(),
+            }),
+        })
+    }
+}
+
+impl TryFrom<Message> for Command {
+    type Error = anyhow::Error;
+
+    fn try_from(message: Message) -> anyhow::Result<Self> {
+        Ok(match message {
+            Message::Request(message) => {
+                let method = reqwest::Method::from_bytes(message.method.as_bytes())?;
+                let mut request = reqwest::Request::new(method, message.uri.to_string().parse()?);
+                *request.headers_mut() = header_map(message.headers)?;
+                if let Some(body) = message.body {
+                    *request.body_mut() = Some(reqwest::Body::from(body));
+                }
+                Command::Request(WorkerRequest(request))
+            }
+            Message::Response(message) => Command::Response(WorkerResponse(Response {
+                status: reqwest::StatusCode::from_u16(message.status)?,
+                headers: header_map(message.headers)?,
+                body: message.body.unwrap_or_default(),
+            })),
+        })
+    }
+}
+
+fn header_map(headers: BTreeMap<String, String>) -> anyhow::Result<HeaderMap> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        map.insert(HeaderName::from_str(&name)?, HeaderValue::from_str(&value)?);
+    }
+    Ok(map)
+}
+
+#[async_trait::async_trait]
+impl WorkerIO<Event, Command> for PluginWorker<Event, Command> {
+    async fn call(&self, name: &str, event: Event) -> Result<Option<Command>, worker::Error> {
+        let Some((plugin, function)) = self.find(name) else {
+            return self.call_fallback(name, event).await;
+        };
+
+        let input = serde_json::to_vec(&Message::try_from(event)?)?;
+        match plugin.call(function, input).await? {
+            Some(output) => {
+                let message: Message = serde_json::from_slice(&output)?;
+                Ok(Some(Command::try_from(message)?))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl WorkerIO<ConstValue, ConstValue> for PluginWorker<ConstValue, ConstValue> {
+    async fn call(
+        &self,
+        name: &str,
+        input: ConstValue,
+    ) -> Result<Option<ConstValue>, worker::Error> {
+        let Some((plugin, function)) = self.find(name) else {
+            return self.call_fallback(name, input).await;
+        };
+
+        let input = serde_json::to_vec(&input)?;
+        match plugin.call(function, input).await? {
+            Some(output) => Ok(Some(serde_json::from_slice(&output)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    // echoes its input back, spins forever in `spin`, points past its memory
+    // in `overflow` and tells whether the last digit of a number is even in
+    // `even`
+    const MODULE: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (data (i32.const 0) "true")
+          (data (i32.const 8) "false")
+          (func (export "alloc") (param i32) (result i32) i32.const 1024)
+          (func (export "echo") (param i32 i32) (result i64)
+            (i64.or
+              (i64.shl (i64.extend_i32_u (local.get 0)) (i64.const 32))
+              (i64.extend_i32_u (local.get 1))))
+          (func (export "spin") (param i32 i32) (result i64)
+            (loop (br 0))
+            i64.const 0)
+          (func (export "overflow") (param i32 i32) (result i64)
+            i64.const 0x7fffffff)
+          (func (export "even") (param i32 i32) (result i64)
+            (if (result i64)
+              (i32.and
+                (i32.load8_u (i32.sub (i32.add (local.get 0) (local.get 1)) (i32.const 1)))
+                (i32.const 1))
+              (then (i64.const 0x800000005))
+              (else (i64.const 4)))))
+    "#;
+
+    fn options(id: Option<&str>, path: &str) -> blueprint::Plugin {
+        blueprint::Plugin {
+            id: id.map(str::to_string),
+            path: path.to_string(),
+            fuel: 100_000,
+            max_memory: 1 << 20,
+            scalars: BTreeMap::new(),
+        }
+    }
+
+    fn worker(id: Option<&str>) -> PluginWorker<ConstValue, ConstValue> {
+        let plugin = Plugin::from_bytes(&options(id, "test.wasm"), MODULE.as_bytes()).unwrap();
+        PluginWorker::new(vec![Arc::new(plugin)], None)
+    }
+
+    #[tokio::test]
+    async fn test_call() {
+        let worker = worker(Some("utils"));
+        let input = ConstValue::from_json(json!({"id": 1, "tags": ["a"]})).unwrap();
+
+        let actual = worker.call("echo", input.clone()).await.unwrap();
+        assert_eq!(actual, Some(input.clone()));
+
+        let actual = worker.call("utils.echo", input.clone()).await.unwrap();
+        assert_eq!(actual, Some(input.clone()));
+
+        let error = worker.call("missing", input).await.unwrap_err();
+        assert_eq!(error.to_string(), "missing is not a function");
+    }
+
+    #[tokio::test]
+    async fn test_out_of_fuel() {
+        let worker = worker(None);
+        let error = worker.call("spin", ConstValue::Null).await.unwrap_err();
+        assert!(error.to_string().contains("spin ran out of fuel"));
+    }
+
+    #[test]
+    fn test_output_out_of_memory() {
+        let plugin = Plugin::from_bytes(&options(None, "test.wasm"), MODULE.as_bytes()).unwrap();
+        let error = plugin.call_sync("overflow", b"null").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Output of overflow is out of the plugin memory"
+        );
+    }
+
+    #[test]
+    fn test_init_plugins_error() {
+        let Err(error) = init_plugins(&[options(None, "missing.wasm")]) else {
+            panic!("The missing plugin should fail to load");
+        };
+        assert_eq!(error.to_string(), "Failed to load plugin missing.wasm");
+    }
+
+    #[tokio::test]
+    async fn test_scalar_validation() {
+        use hyper::{Body, Request};
+        use tailcall_valid::Validator;
+
+        use crate::core::app_context::AppContext;
+        use crate::core::async_graphql_hyper::GraphQLRequest;
+        use crate::core::blueprint::Blueprint;
+        use crate::core::config::{Config, ConfigModule, Content, Extensions, PluginLink};
+        use crate::core::http::handle_request;
+        use crate::core::rest::EndpointSet;
+
+        let sdl = r#"
+            schema {
+                query: Query
+            }
+
+            scalar Even
+
+            input Pair {
+                left: Even
+                right: Even
+            }
+
+            type Query {
+                even(value: Even!): Int @expr(body: "{{.args.value}}")
+                evens(values: [Even], pair: Pair): Boolean @expr(body: true)
+            }
+        "#;
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+        let link = PluginLink {
+            path: "test.wasm".to_string(),
+            scalars: BTreeMap::from([("Even".to_string(), "even".to_string())]),
+        };
+        let extensions = Extensions {
+            plugins: vec![Content { id: None, content: link }],
+            ..Default::default()
+        };
+        let blueprint = Blueprint::try_from(&ConfigModule::new(config, extensions)).unwrap();
+        let plugin = Plugin::from_bytes(&blueprint.server.plugins[0], MODULE.as_bytes()).unwrap();
+        let mut runtime = crate::core::runtime::test::init(None);
+        runtime.worker = Some(Arc::new(PluginWorker::new(vec![Arc::new(plugin)], None)));
+        let app_ctx = Arc::new(AppContext::new(blueprint, runtime, EndpointSet::default()));
+
+        let execute = |query: &str| {
+            let request = Request::post("http://localhost:8000/graphql")
+                .header("Content-Type", "application/json")
+                .body(Body::from(json!({ "query": query }).to_string()))
+                .unwrap();
+            let app_ctx = app_ctx.clone();
+            async move {
+                let response = handle_request::<GraphQLRequest>(request, app_ctx)
+                    .await
+                    .unwrap();
+                let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+                String::from_utf8(body.to_vec()).unwrap()
+            }
+        };
+
+        let body = execute("{ even(value: 4) }").await;
+        assert!(body.contains(r#""even":4"#), "{}", body);
+
+        let body = execute("{ even(value: 3) }").await;
+        assert!(
+            body.contains("Argument `value` for field `even` is not a valid `Even`"),
+            "{}",
+            body
+        );
+
+        let body = execute("{ evens(values: [2, 4], pair: {left: 6, right: 8}) }").await;
+        assert!(body.contains(r#""evens":true"#), "{}", body);
+
+        let body = execute("{ evens(values: [2, 4], pair: {left: 6, right: 7}) }").await;
+        assert!(
+            body.contains("Argument `pair` for field `evens` is not a valid `Even`"),
+            "{}",
+            body
+        );
+    }
+}
@@ -0,0 +1,419 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Context, Result};
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use hyper::body::Bytes;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use super::HttpIO;
+use crate::core::http::{ErrorResponse, Response};
+use crate::core::ir::Error;
+use crate::core::runtime::TargetRuntime;
+
+/// Selects how upstream traffic is captured in a cassette file.
+#[derive(Clone, Debug)]
+pub enum CassetteMode {
+    /// Forwards the requests to the upstream and writes every request/response
+    /// pair to the file.
+    Record(PathBuf),
+    /// Serves the responses stored in the file without any network access.
+    /// Requests without a recording fail.
+    Replay(PathBuf),
+}
+
+impl CassetteMode {
+    /// Wraps the http clients of the runtime. HTTP, gRPC and GraphQL upstreams
+    /// all go through these clients.
+    pub fn wrap(&self, runtime: &mut TargetRuntime) -> Result<()> {
+        match self {
+            CassetteMode::Record(path) => {
+                let recorder = Arc::new(Recorder::new(path.clone()));
+                runtime.http = Arc::new(RecordingHttp::new(runtime.http.clone(), recorder.clone()));
+                runtime.http2_only =
+                    Arc::new(RecordingHttp::new(runtime.http2_only.clone(), recorder));
+            }
+            CassetteMode::Replay(path) => {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read cassette {}", path.display()))?;
+                let cassette: Cassette = serde_json::from_str(&content)
+                    .with_context(|| format!("Invalid cassette {}", path.display()))?;
+                let replay = Arc::new(ReplayHttp::new(cassette));
+                runtime.http = replay.clone();
+                runtime.http2_only = replay;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    /// Base64 encoded, because gRPC bodies are binary
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+impl RecordedRequest {
+    // Headers are not part of the request identity, they contain values like
+    // trace ids that change between runs.
+    fn new(request: &reqwest::Request) -> Self {
+        Self {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(|bytes| BASE64_STANDARD.encode(bytes)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordedResponse {
+    Ok {
+        status: u16,
+        headers: BTreeMap<String, String>,
+        /// Base64 encoded body
+        body: String,
+    },
+    Error {
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        body: Option<String>,
+        /// Status of the responses with an error status
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        status: Option<u16>,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        headers: BTreeMap<String, String>,
+    },
+}
+
+impl RecordedResponse {
+    fn new(response: &Result<Response<Bytes>>) -> Self {
+        match response {
+            Ok(response) => Self::Ok {
+                status: response.status.as_u16(),
+                headers: recorded_headers(&response.headers),
+                body: BASE64_STANDARD.encode(&response.body),
+            },
+            Err(err) => match err.downcast_ref::<Error>() {
+                Some(Error::HTTP { message, body }) => {
+                    let response = err.downcast_ref::<ErrorResponse>();
+                    Self::Error {
+                        message: message.clone(),
+                        body: Some(body.clone()),
+                        status: response.map(|response| response.0.status.as_u16()),
+                        headers: response
+                            .map(|response| recorded_headers(&response.0.headers))
+                            .unwrap_or_default(),
+                    }
+                }
+                _ => Self::Error {
+                    message: format!("{:#}", err),
+                    body: None,
+                    status: None,
+                    headers: BTreeMap::new(),
+                },
+            },
+        }
+    }
+
+    fn to_response(&self) -> Result<Response<Bytes>> {
+        match self {
+            Self::Ok { status, headers, body } => Ok(Response {
+                status: reqwest::StatusCode::from_u16(*status)?,
+                headers: header_map(headers)?,
+                body: Bytes::from(BASE64_STANDARD.decode(body)?),
+            }),
+            Self::Error { message, body: Some(body), status, headers } => {
+                let err = anyhow::Error::new(Error::HTTP {
+                    message: message.clone(),
+                    body: body.clone(),
+                });
+                // the cassettes recorded without the status only keep the body
+                match status {
+                    Some(status) => Err(err.context(ErrorResponse(Response {
+                        status: reqwest::StatusCode::from_u16(*status)?,
+                        headers: header_map(headers)?,
+                        body: Bytes::from(body.clone()),
+                    }))),
+                    None => Err(err.context(body.clone())),
+                }
+            }
+            Self::Error { message, body: None, .. } => Err(anyhow!(message.clone())),
+        }
+    }
+}
+
+fn recorded_headers(headers: &reqwest::header::HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+fn header_map(headers: &BTreeMap<String, String>) -> Result<reqwest::header::HeaderMap> {
+    let mut header_map = reqwest::header::HeaderMap::new();
+    for (name, value) in headers {
+        header_map.append(
+            reqwest::header::HeaderName::from_bytes(name.as_bytes())?,
+            reqwest::header::HeaderValue::from_str(value)?,
+        );
+    }
+    Ok(header_map)
+}
+
+/// Collects the interactions of all the recording clients into one file. The
+/// file is rewritten by a background task so that recording doesn't block the
+/// requests, the interactions recorded while it's being written are written
+/// together by the next write.
+struct Recorder {
+    sender: mpsc::UnboundedSender<Interaction>,
+}
+
+impl Recorder {
+    fn new(path: PathBuf) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut cassette = Cassette::default();
+            while let Some(interaction) = receiver.recv().await {
+                cassette.interactions.push(interaction);
+                while let Ok(interaction) = receiver.try_recv() {
+                    cassette.interactions.push(interaction);
+                }
+
+                if let Err(err) = write_cassette(&path, &cassette).await {
+                    tracing::error!("{:#}", err);
+                }
+            }
+        });
+
+        Self { sender }
+    }
+
+    fn record(&self, interaction: Interaction) -> Result<()> {
+        self.sender
+            .send(interaction)
+            .map_err(|_| anyhow!("Cassette writer has stopped"))
+    }
+}
+
+/// Writes the cassette next to the file and renames it over the file, so that
+/// the file always holds a complete recording even if the server is stopped.
+async fn write_cassette(path: &Path, cassette: &Cassette) -> Result<()> {
+    let content = serde_json::to_string_pretty(cassette)?;
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, content)
+        .await
+        .with_context(|| format!("Failed to write cassette {}", tmp.display()))?;
+    tokio::fs::rename(&tmp, path)
+        .await
+        .with_context(|| format!("Failed to write cassette {}", path.display()))
+}
+
+struct RecordingHttp {
+    inner: Arc<dyn HttpIO>,
+    recorder: Arc<Recorder>,
+}
+
+impl RecordingHttp {
+    fn new(inner: Arc<dyn HttpIO>, recorder: Arc<Recorder>) -> Self {
+        Self { inner, recorder }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpIO for RecordingHttp {
+    async fn execute(&self, request: reqwest::Request) -> Result<Response<Bytes>> {
+        let recorded_request = RecordedRequest::new(&request);
+        let response = self.inner.execute(request).await;
+        let interaction = Interaction {
+            request: recorded_request,
+            response: RecordedResponse::new(&response),
+        };
+
+        if let Err(err) = self.recorder.record(interaction) {
+            tracing::error!("{:#}", err);
+        }
+
+        response
+    }
+}
+
+/// Serves the recorded responses. Identical requests are answered in the order
+/// they were recorded, the last response is repeated once the recordings run
+/// out.
+pub struct ReplayHttp {
+    responses: Mutex<HashMap<RecordedRequest, VecDeque<RecordedResponse>>>,
+}
+
+impl ReplayHttp {
+    pub fn new(cassette: Cassette) -> Self {
+        let mut responses: HashMap<_, VecDeque<_>> = HashMap::new();
+        for interaction in cassette.interactions {
+            responses
+                .entry(interaction.request)
+                .or_default()
+                .push_back(interaction.response);
+        }
+
+        Self { responses: Mutex::new(responses) }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpIO for ReplayHttp {
+    async fn execute(&self, request: reqwest::Request) -> Result<Response<Bytes>> {
+        let request = RecordedRequest::new(&request);
+        let response = {
+            let mut responses = self
+                .responses
+                .lock()
+                .map_err(|_| anyhow!("Cassette lock is poisoned"))?;
+
+            let recorded = responses.get_mut(&request).ok_or_else(|| {
+                anyhow!(
+                    "No recorded interaction for {} {}",
+                    request.method,
+                    request.url
+                )
+            })?;
+
+            if recorded.len() > 1 {
+                recorded.pop_front()
+            } else {
+                recorded.front().cloned()
+            }
+        };
+
+        response
+            .ok_or_else(|| {
+                anyhow!(
+                    "No recorded response for {} {}",
+                    request.method,
+                    request.url
+                )
+            })?
+            .to_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: reqwest::Method, url: &str, body: Option<&str>) -> reqwest::Request {
+        let mut request = reqwest::Request::new(method, url.parse().unwrap());
+        if let Some(body) = body {
+            *request.body_mut() = Some(reqwest::Body::from(body.to_string()));
+        }
+        request
+    }
+
+    struct StaticHttp;
+
+    #[async_trait::async_trait]
+    impl HttpIO for StaticHttp {
+        async fn execute(&self, request: reqwest::Request) -> Result<Response<Bytes>> {
+            if request.url().path() == "/error" {
+                let err = Error::HTTP {
+                    message: "404 Not Found".to_string(),
+                    body: "missing".to_string(),
+                };
+                let response = Response {
+                    status: reqwest::StatusCode::NOT_FOUND,
+                    body: Bytes::from("missing"),
+                    ..Default::default()
+                };
+                return Err(anyhow::Error::new(err).context(ErrorResponse(response)));
+            }
+
+            Ok(Response {
+                body: Bytes::from(format!("{} {}", request.method(), request.url())),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let path = std::env::temp_dir().join(format!("cassette-{}.json", std::process::id()));
+        let recorder = Arc::new(Recorder::new(path.clone()));
+        let http = RecordingHttp::new(Arc::new(StaticHttp), recorder);
+
+        let users = "http://localhost/users";
+        let recorded = http
+            .execute(request(reqwest::Method::POST, users, Some("{}")))
+            .await
+            .unwrap();
+        assert!(http
+            .execute(request(
+                reqwest::Method::GET,
+                "http://localhost/error",
+                None
+            ))
+            .await
+            .is_err());
+
+        // the cassette is written in the background
+        let cassette = loop {
+            let cassette = tokio::fs::read_to_string(&path)
+                .await
+                .ok()
+                .and_then(|content| serde_json::from_str::<Cassette>(&content).ok());
+            match cassette {
+                Some(cassette) if cassette.interactions.len() == 2 => break cassette,
+                _ => tokio::time::sleep(std::time::Duration::from_millis(10)).await,
+            }
+        };
+        std::fs::remove_file(&path).unwrap();
+
+        let replay = ReplayHttp::new(cassette);
+        let replayed = replay
+            .execute(request(reqwest::Method::POST, users, Some("{}")))
+            .await
+            .unwrap();
+        assert_eq!(replayed.body, recorded.body);
+
+        let err = replay
+            .execute(request(
+                reqwest::Method::GET,
+                "http://localhost/error",
+                None,
+            ))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::HTTP { .. })
+        ));
+        let response = err.downcast_ref::<ErrorResponse>().unwrap();
+        assert_eq!(response.0.status, reqwest::StatusCode::NOT_FOUND);
+        assert_eq!(response.0.body, Bytes::from("missing"));
+
+        // the body is part of the request identity
+        let unmatched = replay
+            .execute(request(reqwest::Method::POST, users, Some("[]")))
+            .await;
+        assert!(unmatched.is_err());
+    }
+}
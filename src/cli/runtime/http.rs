@@ -1,8 +1,10 @@
+use std::io::Read;
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use http_cache_reqwest::{Cache, CacheMode, HttpCache, HttpCacheOptions};
 use hyper::body::Bytes;
+use hyper::header::{HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH};
 use once_cell::sync::Lazy;
 use opentelemetry::metrics::Counter;
 use opentelemetry::trace::SpanKind;
@@ -72,6 +74,7 @@ pub struct NativeHttp {
     client: ClientWithMiddleware,
     http2_only: bool,
     enable_telemetry: bool,
+    max_response_size: usize,
 }
 
 impl Default for NativeHttp {
@@ -80,6 +83,7 @@ impl Default for NativeHttp {
             client: ClientBuilder::new(Client::new()).build(),
             http2_only: false,
             enable_telemetry: false,
+            max_response_size: Upstream::default().max_response_size,
         }
     }
 }
@@ -126,10 +130,38 @@ impl NativeHttp {
             client: client.build(),
             http2_only: upstream.http2_only,
             enable_telemetry: telemetry.export.is_some(),
+            max_response_size: upstream.max_response_size,
         }
     }
 }
 
+fn response_too_large(max_size: usize) -> anyhow::Error {
+    anyhow!("Response exceeds the limit of {} bytes", max_size)
+}
+
+/// Decodes the zstd encoded responses, reqwest decodes the other encodings.
+/// The decoded body is capped like the bodies reqwest decodes.
+fn decode_zstd(mut response: Response<Bytes>, max_size: usize) -> Result<Response<Bytes>> {
+    let is_zstd = response
+        .headers
+        .get(CONTENT_ENCODING)
+        .is_some_and(|encoding| encoding == "zstd");
+    if is_zstd {
+        let mut body = Vec::new();
+        zstd::stream::read::Decoder::new(response.body.as_ref())?
+            .take(max_size as u64 + 1)
+            .read_to_end(&mut body)?;
+        if body.len() > max_size {
+            return Err(response_too_large(max_size));
+        }
+        response.body = Bytes::from(body);
+        response.headers.remove(CONTENT_ENCODING);
+        response.headers.remove(CONTENT_LENGTH);
+    }
+
+    Ok(response)
+}
+
 #[async_trait::async_trait]
 impl HttpIO for NativeHttp {
     #[allow(clippy::blocks_in_conditions)]
@@ -145,10 +177,21 @@ impl HttpIO for NativeHttp {
             network.protocol.version = ?request.version()
         )
     )]
-    async fn execute(&self, mut request: reqwest::Request) -> Result<Response<Bytes>> {
+    async fn execute_inspected(
+        &self,
+        mut request: reqwest::Request,
+        inspect: &mut (dyn for<'a> FnMut(&'a [u8]) -> Result<()> + Send),
+    ) -> Result<Response<Bytes>> {
         if self.http2_only {
             *request.version_mut() = reqwest::Version::HTTP_2;
         }
+        // the encodings reqwest advertises by default, plus zstd
+        if !request.headers().contains_key(ACCEPT_ENCODING) {
+            request.headers_mut().insert(
+                ACCEPT_ENCODING,
+                HeaderValue::from_static("gzip, br, deflate, zstd"),
+            );
+        }
 
         let mut req_counter = RequestCounter::new(self.enable_telemetry, &request);
 
@@ -181,7 +224,23 @@ impl HttpIO for NativeHttp {
         // Get the response
         let response = response?;
 
-        Response::from_reqwest_with_error_handling(response).await
+        // reqwest passes the chunks decoded already, except for zstd
+        let max_size = self.max_response_size;
+        let mut size = 0;
+        let mut inspect = |chunk: &[u8]| {
+            size += chunk.len();
+            if size > max_size {
+                return Err(response_too_large(max_size));
+            }
+            inspect(chunk)
+        };
+        let response = Response::from_reqwest_inspected(response, &mut inspect).await?;
+
+        decode_zstd(response, max_size)
+    }
+
+    async fn execute(&self, request: reqwest::Request) -> Result<Response<Bytes>> {
+        self.execute_inspected(request, &mut |_| Ok(())).await
     }
 }
 
@@ -234,6 +293,58 @@ mod tests {
         header_serv.assert_hits(2);
     }
 
+    #[tokio::test]
+    async fn test_native_http_decompression() {
+        let server = start_mock_server();
+        let body = "tailcall ".repeat(100);
+
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut gzip, body.as_bytes()).unwrap();
+        let gzip = gzip.finish().unwrap();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path("/gzip")
+                .header("accept-encoding", "gzip, br, deflate, zstd");
+            then.status(200)
+                .header("content-encoding", "gzip")
+                .body(gzip);
+        });
+
+        let zstd = zstd::encode_all(body.as_bytes(), 0).unwrap();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path("/zstd")
+                .header("accept-encoding", "gzip, br, deflate, zstd");
+            then.status(200)
+                .header("content-encoding", "zstd")
+                .body(zstd);
+        });
+
+        let native_http = NativeHttp::init(&Default::default(), &Default::default());
+        for encoding in ["gzip", "zstd"] {
+            let url = format!("http://localhost:{}/{}", server.port(), encoding);
+            let response = make_request(&url, &native_http).await;
+
+            assert_eq!(response.status, reqwest::StatusCode::OK);
+            assert_eq!(response.body, Bytes::from(body.clone()));
+            assert!(response.headers.get("content-encoding").is_none());
+        }
+
+        // the limit applies to the decompressed bodies
+        let upstream = Upstream { max_response_size: body.len() - 1, ..Default::default() };
+        let native_http = NativeHttp::init(&upstream, &Default::default());
+        for encoding in ["gzip", "zstd"] {
+            let url = format!("http://localhost:{}/{}", server.port(), encoding);
+            let request = reqwest::Request::new(Method::GET, url.parse().unwrap());
+            let error = native_http.execute(request).await.unwrap_err();
+
+            assert_eq!(
+                error.to_string(),
+                format!("Response exceeds the limit of {} bytes", body.len() - 1)
+            );
+        }
+    }
+
     #[tokio::test]
     async fn test_native_http_get_request_with_cache() {
         let server = start_mock_server();
@@ -1,3 +1,4 @@
+mod cassette;
 mod env;
 mod file;
 mod http;
@@ -6,6 +7,7 @@ use std::fs;
 use std::hash::Hash;
 use std::sync::Arc;
 
+pub use cassette::{Cassette, CassetteMode, ReplayHttp};
 pub use http::NativeHttp;
 use inquire::{Confirm, Select};
 
@@ -27,28 +29,63 @@ fn init_file() -> Arc<dyn FileIO> {
 
 fn init_http_worker_io(
     script: Option<blueprint::Script>,
+    runtime: &TargetRuntime,
 ) -> Option<Arc<dyn WorkerIO<Event, Command>>> {
     #[cfg(feature = "js")]
-    return Some(super::javascript::init_worker_io(script?));
+    return Some(super::javascript::init_worker_io(script?, runtime));
     #[cfg(not(feature = "js"))]
     {
-        let _ = script;
+        let _ = (script, runtime);
         None
     }
 }
 
 fn init_resolver_worker_io(
     script: Option<blueprint::Script>,
+    runtime: &TargetRuntime,
 ) -> Option<Arc<dyn WorkerIO<async_graphql::Value, async_graphql::Value>>> {
     #[cfg(feature = "js")]
-    return Some(super::javascript::init_worker_io(script?));
+    return Some(super::javascript::init_worker_io(script?, runtime));
     #[cfg(not(feature = "js"))]
     {
-        let _ = script;
+        let _ = (script, runtime);
         None
     }
 }
 
+/// Creates the script workers, their `fetch` calls go through the http
+/// client of the runtime and `tailcall.cache` uses its cache.
+fn init_script_workers(runtime: &mut TargetRuntime, server: &blueprint::Server) {
+    let script = server.script.clone();
+    runtime.cmd_worker = init_http_worker_io(script.clone(), runtime);
+    runtime.worker = init_resolver_worker_io(script, runtime);
+}
+
+/// Creates the script workers and loads the plugins, the functions exported
+/// by the plugins take precedence over the ones of the script. Fails if a
+/// plugin can't be loaded.
+pub fn init_workers(runtime: &mut TargetRuntime, server: &blueprint::Server) -> anyhow::Result<()> {
+    init_script_workers(runtime, server);
+
+    #[cfg(feature = "plugin")]
+    if !server.plugins.is_empty() {
+        use super::plugin::{init_plugins, PluginWorker};
+
+        let plugins = init_plugins(&server.plugins)?;
+        runtime.cmd_worker = Some(Arc::new(PluginWorker::new(
+            plugins.clone(),
+            runtime.cmd_worker.take(),
+        )));
+        runtime.worker = Some(Arc::new(PluginWorker::new(plugins, runtime.worker.take())));
+    }
+    #[cfg(not(feature = "plugin"))]
+    if !server.plugins.is_empty() {
+        anyhow::bail!("Plugins require the `plugin` feature");
+    }
+
+    Ok(())
+}
+
 // Provides access to http in native rust environment
 fn init_http(blueprint: &Blueprint) -> Arc<dyn HttpIO> {
     Arc::new(http::NativeHttp::init(
@@ -69,20 +106,25 @@ fn init_in_memory_cache<K: Hash + Eq, V: Clone>() -> InMemoryCache<K, V> {
     InMemoryCache::default()
 }
 
+/// Creates the runtime with the script workers of the blueprint, the plugins
+/// are loaded by [init_workers].
 pub fn init(blueprint: &Blueprint) -> TargetRuntime {
     #[cfg(not(feature = "js"))]
     tracing::warn!("JS capabilities are disabled in this build");
 
-    TargetRuntime {
+    let mut runtime = TargetRuntime {
         http: init_http(blueprint),
         http2_only: init_http2_only(blueprint),
         env: init_env(),
         file: init_file(),
         cache: Arc::new(init_in_memory_cache()),
         extensions: Arc::new(vec![]),
-        cmd_worker: init_http_worker_io(blueprint.server.script.clone()),
-        worker: init_resolver_worker_io(blueprint.server.script.clone()),
-    }
+        cmd_worker: None,
+        worker: None,
+    };
+    init_script_workers(&mut runtime, &blueprint.server);
+
+    runtime
 }
 
 pub async fn confirm_and_write(
@@ -0,0 +1,262 @@
+use std::io::Write;
+
+use hyper::body::HttpBody;
+use hyper::header::{
+    HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, ETAG, VARY,
+};
+use hyper::{Body, Request, Response, StatusCode};
+
+use crate::core::app_context::AppContext;
+use crate::core::http::request_route;
+
+/// Encodings supported by the server, in order of preference.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Encoding {
+    Brotli,
+    Zstd,
+    Gzip,
+}
+
+impl Encoding {
+    const ALL: [Encoding; 3] = [Encoding::Brotli, Encoding::Zstd, Encoding::Gzip];
+
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Zstd => "zstd",
+            Encoding::Gzip => "gzip",
+        }
+    }
+
+    fn encode(self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Encoding::Brotli => {
+                let mut out = Vec::new();
+                // the default quality is too slow for responses compressed on the fly
+                let params = brotli::enc::BrotliEncoderParams { quality: 5, ..Default::default() };
+                brotli::BrotliCompress(&mut &body[..], &mut out, &params)?;
+                Ok(out)
+            }
+            Encoding::Zstd => zstd::encode_all(body, zstd::DEFAULT_COMPRESSION_LEVEL),
+            Encoding::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+/// Picks the encoding with the highest quality value in `Accept-Encoding`,
+/// ties are broken by the server preference.
+fn negotiate(accept_encoding: &str) -> Option<Encoding> {
+    let mut selected: Option<(Encoding, f32)> = None;
+    for item in accept_encoding.split(',') {
+        let mut params = item.split(';');
+        let name = params.next().unwrap_or_default().trim();
+        let quality = params
+            .find_map(|param| param.trim().strip_prefix("q="))
+            .and_then(|quality| quality.parse::<f32>().ok())
+            .unwrap_or(1.0);
+
+        let encodings = Encoding::ALL
+            .into_iter()
+            .filter(|encoding| name == "*" || name.eq_ignore_ascii_case(encoding.name()));
+        for encoding in encodings {
+            let is_better = match selected {
+                Some((current, current_quality)) => {
+                    quality > current_quality || (quality == current_quality && encoding < current)
+                }
+                None => true,
+            };
+            if quality > 0.0 && is_better {
+                selected = Some((encoding, quality));
+            }
+        }
+    }
+
+    selected.map(|(encoding, _)| encoding)
+}
+
+/// Encoding of the response to the request, `None` if it isn't compressed.
+pub fn encoding(req: &Request<Body>, app_ctx: &AppContext) -> Option<Encoding> {
+    let compression = app_ctx.blueprint.server.compression.as_ref()?;
+    if let Some(route) = request_route(req, app_ctx) {
+        if !compression.is_enabled(route) {
+            return None;
+        }
+    }
+
+    let accept_encoding = req.headers().get(ACCEPT_ENCODING)?.to_str().ok()?;
+    negotiate(accept_encoding)
+}
+
+/// Compresses the body of the response unless it's already encoded or
+/// smaller than `min_size`. gRPC responses are never compressed, their
+/// messages carry their own compression flag.
+pub async fn compress(
+    response: Response<Body>,
+    encoding: Encoding,
+    min_size: usize,
+) -> anyhow::Result<Response<Body>> {
+    let (mut parts, mut body) = response.into_parts();
+    let is_grpc = parts
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/grpc"));
+    if is_grpc {
+        return Ok(Response::from_parts(parts, body));
+    }
+
+    parts
+        .headers
+        .append(VARY, HeaderValue::from_static("accept-encoding"));
+
+    let is_empty = matches!(
+        parts.status,
+        StatusCode::NO_CONTENT | StatusCode::NOT_MODIFIED
+    );
+    let is_small = body
+        .size_hint()
+        .exact()
+        .is_some_and(|size| size < min_size as u64);
+    if is_empty || is_small || parts.headers.contains_key(CONTENT_ENCODING) {
+        return Ok(Response::from_parts(parts, body));
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        bytes.extend_from_slice(&chunk?);
+    }
+    let trailers = body.trailers().await?;
+
+    let bytes = if bytes.len() < min_size {
+        bytes
+    } else {
+        parts.headers.remove(CONTENT_LENGTH);
+        parts
+            .headers
+            .insert(CONTENT_ENCODING, HeaderValue::from_static(encoding.name()));
+        // the encoded bytes differ from the ones the strong ETag was computed on
+        if let Some(etag) = parts.headers.get(ETAG).and_then(|etag| etag.to_str().ok()) {
+            if !etag.starts_with("W/") {
+                let etag = HeaderValue::from_str(&format!("W/{}", etag))?;
+                parts.headers.insert(ETAG, etag);
+            }
+        }
+        encoding.encode(&bytes)?
+    };
+
+    let body = match trailers {
+        Some(trailers) => {
+            let (mut sender, body) = Body::channel();
+            tokio::spawn(async move {
+                if sender.send_data(bytes.into()).await.is_ok() {
+                    let _ = sender.send_trailers(trailers).await;
+                }
+            });
+            body
+        }
+        None => Body::from(bytes),
+    };
+
+    Ok(Response::from_parts(parts, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use hyper::HeaderMap;
+
+    use super::*;
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(negotiate("gzip"), Some(Encoding::Gzip));
+        assert_eq!(negotiate("gzip, deflate, br, zstd"), Some(Encoding::Brotli));
+        assert_eq!(negotiate("br;q=0.5, zstd"), Some(Encoding::Zstd));
+        assert_eq!(negotiate("*"), Some(Encoding::Brotli));
+        assert_eq!(negotiate("gzip;q=0, deflate"), None);
+        assert_eq!(negotiate("identity"), None);
+    }
+
+    #[tokio::test]
+    async fn test_compress() {
+        let body = "tailcall ".repeat(200);
+        let response = Response::new(Body::from(body.clone()));
+        let response = compress(response, Encoding::Gzip, 1024).await.unwrap();
+
+        assert_eq!(response.headers()[CONTENT_ENCODING], "gzip");
+        let compressed = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let mut actual = String::new();
+        flate2::read::GzDecoder::new(compressed.as_ref())
+            .read_to_string(&mut actual)
+            .unwrap();
+        assert_eq!(actual, body);
+
+        let response = Response::new(Body::from("small"));
+        let response = compress(response, Encoding::Gzip, 1024).await.unwrap();
+        assert!(!response.headers().contains_key(CONTENT_ENCODING));
+    }
+
+    #[tokio::test]
+    async fn test_compress_etag() {
+        let response = |body: String| {
+            Response::builder()
+                .header(ETAG, "\"abc\"")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let compressed = compress(response("tailcall ".repeat(200)), Encoding::Zstd, 1024)
+            .await
+            .unwrap();
+        assert_eq!(compressed.headers()[ETAG], "W/\"abc\"");
+
+        let small = compress(response("small".to_string()), Encoding::Zstd, 1024)
+            .await
+            .unwrap();
+        assert_eq!(small.headers()[ETAG], "\"abc\"");
+    }
+
+    #[tokio::test]
+    async fn test_compress_trailers() {
+        let body = "tailcall ".repeat(200);
+        let mut trailers = HeaderMap::new();
+        trailers.insert("x-checksum", HeaderValue::from_static("abc"));
+        let (mut sender, response_body) = Body::channel();
+        let data = body.clone();
+        let sent = trailers.clone();
+        tokio::spawn(async move {
+            sender.send_data(data.into()).await.unwrap();
+            sender.send_trailers(sent).await.unwrap();
+        });
+
+        let response = Response::new(response_body);
+        let response = compress(response, Encoding::Gzip, 1024).await.unwrap();
+        assert_eq!(response.headers()[CONTENT_ENCODING], "gzip");
+
+        let mut body = response.into_body();
+        let mut compressed = Vec::new();
+        while let Some(chunk) = body.data().await {
+            compressed.extend_from_slice(&chunk.unwrap());
+        }
+        assert!(!compressed.is_empty());
+        assert_eq!(body.trailers().await.unwrap(), Some(trailers));
+    }
+
+    #[tokio::test]
+    async fn test_compress_grpc() {
+        let response = Response::builder()
+            .header(CONTENT_TYPE, "application/grpc")
+            .body(Body::from("tailcall ".repeat(200)))
+            .unwrap();
+        let response = compress(response, Encoding::Gzip, 1024).await.unwrap();
+
+        assert!(!response.headers().contains_key(CONTENT_ENCODING));
+        assert!(!response.headers().contains_key(VARY));
+    }
+}
@@ -1,4 +1,5 @@
 #![allow(clippy::too_many_arguments)]
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 use hyper::server::conn::AddrIncoming;
@@ -9,18 +10,21 @@ use rustls_pki_types::CertificateDer;
 use tokio::sync::oneshot;
 
 use super::server_config::ServerConfig;
+use super::shutdown::Shutdown;
 use crate::core::async_graphql_hyper::{GraphQLBatchRequest, GraphQLRequest};
+use crate::core::blueprint::Listener;
 use crate::core::config::PrivateKey;
-use crate::core::http::handle_request;
 use crate::core::Errata;
 
 pub async fn start_http_2(
     sc: Arc<ServerConfig>,
+    listener: Arc<Listener>,
+    addr: SocketAddr,
     cert: Vec<CertificateDer<'static>>,
     key: PrivateKey,
     server_up_sender: Option<oneshot::Sender<()>>,
+    shutdown: Shutdown,
 ) -> anyhow::Result<()> {
-    let addr = sc.addr();
     let incoming = AddrIncoming::bind(&addr)?;
     let acceptor = TlsAcceptor::builder()
         .with_single_cert(cert, key.into_inner())?
@@ -28,25 +32,27 @@ pub async fn start_http_2(
         .with_incoming(incoming);
     let make_svc_single_req = make_service_fn(|_conn| {
         let state = Arc::clone(&sc);
+        let listener = Arc::clone(&listener);
         async move {
             Ok::<_, anyhow::Error>(service_fn(move |req| {
-                handle_request::<GraphQLRequest>(req, state.app_ctx.clone())
+                super::handle::<GraphQLRequest>(req, state.clone(), listener.clone())
             }))
         }
     });
 
     let make_svc_batch_req = make_service_fn(|_conn| {
         let state = Arc::clone(&sc);
+        let listener = Arc::clone(&listener);
         async move {
             Ok::<_, anyhow::Error>(service_fn(move |req| {
-                handle_request::<GraphQLBatchRequest>(req, state.app_ctx.clone())
+                super::handle::<GraphQLBatchRequest>(req, state.clone(), listener.clone())
             }))
         }
     });
 
     let builder = Server::builder(acceptor).http2_only(true);
 
-    super::log_launch(sc.as_ref());
+    super::log_launch(sc.as_ref(), listener.as_ref());
 
     if let Some(sender) = server_up_sender {
         sender
@@ -56,9 +62,15 @@ pub async fn start_http_2(
 
     let server: std::prelude::v1::Result<(), hyper::Error> =
         if sc.blueprint.server.enable_batch_requests {
-            builder.serve(make_svc_batch_req).await
+            builder
+                .serve(make_svc_batch_req)
+                .with_graceful_shutdown(shutdown.wait())
+                .await
         } else {
-            builder.serve(make_svc_single_req).await
+            builder
+                .serve(make_svc_single_req)
+                .with_graceful_shutdown(shutdown.wait())
+                .await
         };
 
     let result = server.map_err(Errata::from);
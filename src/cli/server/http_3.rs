@@ -0,0 +1,178 @@
+#![allow(clippy::too_many_arguments)]
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use hyper::body::{Buf, Bytes};
+use hyper::{Body, Request};
+use rustls_pki_types::CertificateDer;
+use serde::de::DeserializeOwned;
+use tokio::sync::oneshot;
+
+use super::http_2::start_http_2;
+use super::server_config::ServerConfig;
+use super::shutdown::Shutdown;
+use crate::core::app_context::AppContext;
+use crate::core::async_graphql_hyper::{GraphQLBatchRequest, GraphQLRequest, GraphQLRequestLike};
+use crate::core::blueprint::Listener;
+use crate::core::config::PrivateKey;
+
+type RequestStream = h3::server::RequestStream<h3_quinn::BidiStream<Bytes>, Bytes>;
+
+/// Starts the QUIC listener along with an HTTP/2 listener on the same TCP
+/// port. Clients connect over TCP first and discover HTTP/3 through the
+/// `Alt-Svc` header.
+pub async fn start_http_3(
+    sc: Arc<ServerConfig>,
+    listener: Arc<Listener>,
+    addr: SocketAddr,
+    cert: Vec<CertificateDer<'static>>,
+    key: PrivateKey,
+    server_up_sender: Option<oneshot::Sender<()>>,
+    shutdown: Shutdown,
+) -> anyhow::Result<()> {
+    let endpoint = bind(addr, cert.clone(), key.clone())?;
+
+    let quic = {
+        let sc = sc.clone();
+        let listener = listener.clone();
+        let shutdown = shutdown.clone();
+        async move {
+            if sc.blueprint.server.enable_batch_requests {
+                serve::<GraphQLBatchRequest>(endpoint, sc, listener, shutdown).await
+            } else {
+                serve::<GraphQLRequest>(endpoint, sc, listener, shutdown).await
+            }
+        }
+    };
+    let tcp = start_http_2(
+        sc.clone(),
+        listener,
+        addr,
+        cert,
+        key,
+        server_up_sender,
+        shutdown.clone(),
+    );
+
+    tokio::try_join!(quic, tcp)?;
+
+    Ok(())
+}
+
+fn bind(
+    addr: SocketAddr,
+    cert: Vec<CertificateDer<'static>>,
+    key: PrivateKey,
+) -> anyhow::Result<quinn::Endpoint> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut tls = rustls::ServerConfig::builder_with_provider(provider)
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .with_no_client_auth()
+        .with_single_cert(cert, key.into_inner())?;
+    tls.alpn_protocols = vec![b"h3".to_vec()];
+
+    let crypto = quinn::crypto::rustls::QuicServerConfig::try_from(tls)?;
+    let config = quinn::ServerConfig::with_crypto(Arc::new(crypto));
+
+    Ok(quinn::Endpoint::server(config, addr)?)
+}
+
+async fn serve<T: DeserializeOwned + GraphQLRequestLike + 'static>(
+    endpoint: quinn::Endpoint,
+    sc: Arc<ServerConfig>,
+    listener: Arc<Listener>,
+    shutdown: Shutdown,
+) -> anyhow::Result<()> {
+    loop {
+        let incoming = tokio::select! {
+            incoming = endpoint.accept() => incoming,
+            _ = shutdown.clone().wait() => None,
+        };
+        let Some(incoming) = incoming else {
+            break;
+        };
+
+        let app_ctx = sc.app_ctx.clone();
+        let listener = listener.clone();
+        tokio::spawn(async move {
+            if let Err(err) = serve_connection::<T>(incoming, app_ctx, listener).await {
+                tracing::debug!("HTTP/3 connection closed: {}", err);
+            }
+        });
+    }
+
+    // refuse new connections and let the open ones complete
+    endpoint.set_server_config(None);
+    endpoint.wait_idle().await;
+
+    Ok(())
+}
+
+async fn serve_connection<T: DeserializeOwned + GraphQLRequestLike + 'static>(
+    incoming: quinn::Incoming,
+    app_ctx: Arc<AppContext>,
+    listener: Arc<Listener>,
+) -> anyhow::Result<()> {
+    let connection = h3_quinn::Connection::new(incoming.await?);
+    let mut connection: h3::server::Connection<_, Bytes> =
+        h3::server::Connection::new(connection).await?;
+
+    while let Some((req, stream)) = connection.accept().await? {
+        let app_ctx = app_ctx.clone();
+        let listener = listener.clone();
+        tokio::spawn(async move {
+            if let Err(err) = serve_request::<T>(req, stream, app_ctx, &listener).await {
+                tracing::warn!("Failed to serve HTTP/3 request: {}", err);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Bridges the HTTP/3 stream to the hyper request handler.
+async fn serve_request<T: DeserializeOwned + GraphQLRequestLike>(
+    req: http1::Request<()>,
+    mut stream: RequestStream,
+    app_ctx: Arc<AppContext>,
+    listener: &Listener,
+) -> anyhow::Result<()> {
+    // the body is buffered as it arrives, stop reading once it's over the limit
+    let max_body_size = *app_ctx.blueprint.server.limits.max_body_size();
+    let mut body = Vec::new();
+    while let Some(mut chunk) = stream.recv_data().await? {
+        if max_body_size.is_some_and(|max_size| body.len() + chunk.remaining() > max_size) {
+            let response = http1::Response::builder()
+                .status(http1::StatusCode::PAYLOAD_TOO_LARGE)
+                .body(())?;
+            stream.send_response(response).await?;
+            stream.finish().await?;
+            return Ok(());
+        }
+        body.extend_from_slice(&chunk.copy_to_bytes(chunk.remaining()));
+    }
+
+    let mut builder = Request::builder()
+        .method(req.method().as_str())
+        .uri(req.uri().to_string())
+        .version(hyper::Version::HTTP_3);
+    for (name, value) in req.headers() {
+        builder = builder.header(name.as_str(), value.as_bytes());
+    }
+    let req = builder.body(Body::from(body))?;
+
+    let (parts, body) = super::handle_compressed::<T>(req, app_ctx, listener)
+        .await?
+        .into_parts();
+    let mut builder = http1::Response::builder().status(parts.status.as_u16());
+    for (name, value) in parts.headers.iter() {
+        builder = builder.header(name.as_str(), value.as_bytes());
+    }
+    let body = hyper::body::to_bytes(body).await?;
+
+    stream.send_response(builder.body(())?).await?;
+    stream.send_data(body).await?;
+    stream.finish().await?;
+
+    Ok(())
+}
@@ -1,25 +1,54 @@
 use std::ops::Deref;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
+use futures_util::future::try_join_all;
 use tokio::sync::oneshot::{self};
 
 use super::http_1::start_http_1;
 use super::http_2::start_http_2;
+use super::http_3::start_http_3;
 use super::server_config::ServerConfig;
-use crate::cli::telemetry::init_opentelemetry;
-use crate::core::blueprint::{Blueprint, Http};
+use super::shutdown::{self, Shutdown};
+#[cfg(unix)]
+use super::unix::start_unix;
+use crate::cli::runtime::CassetteMode;
+use crate::cli::telemetry::{init_opentelemetry, shutdown_opentelemetry};
+use crate::core::blueprint::{Blueprint, Http, Listener, ListenerAddress};
 use crate::core::config::ConfigModule;
+use crate::core::mock::Mocker;
 use crate::core::Errata;
 
 pub struct Server {
     config_module: ConfigModule,
     server_up_sender: Option<oneshot::Sender<()>>,
+    shutdown_receiver: Option<oneshot::Receiver<()>>,
+    mocker: Option<Mocker>,
+    cassette: Option<CassetteMode>,
 }
 
 impl Server {
     pub fn new(config_module: ConfigModule) -> Self {
-        Self { config_module, server_up_sender: None }
+        Self {
+            config_module,
+            server_up_sender: None,
+            shutdown_receiver: None,
+            mocker: None,
+            cassette: None,
+        }
+    }
+
+    /// Serves generated data instead of calling the upstreams
+    pub fn mock(mut self, mocker: Mocker) -> Self {
+        self.mocker = Some(mocker);
+        self
+    }
+
+    /// Records the upstream traffic to a cassette or replays it from one
+    pub fn cassette(mut self, cassette: CassetteMode) -> Self {
+        self.cassette = Some(cassette);
+        self
     }
 
     pub fn server_up_receiver(&mut self) -> oneshot::Receiver<()> {
@@ -30,20 +59,96 @@ impl Server {
         rx
     }
 
+    /// Sender that starts the graceful shutdown, the same way `SIGTERM` does
+    pub fn shutdown_sender(&mut self) -> oneshot::Sender<()> {
+        let (tx, rx) = oneshot::channel();
+
+        self.shutdown_receiver = Some(rx);
+
+        tx
+    }
+
     /// Starts the server in the current Runtime
-    pub async fn start(self) -> Result<()> {
-        let blueprint = Blueprint::try_from(&self.config_module).map_err(Errata::from)?;
-        let endpoints = self.config_module.extensions().endpoint_set.clone();
-        let server_config = Arc::new(ServerConfig::new(blueprint.clone(), endpoints).await?);
+    pub async fn start(mut self) -> Result<()> {
+        let mut blueprint = Blueprint::try_from(&self.config_module).map_err(Errata::from)?;
+        if let Some(mocker) = self.mocker.as_ref() {
+            blueprint = mocker.mock(blueprint);
+        }
+        let server_config = Arc::new(
+            ServerConfig::new(
+                &self.config_module,
+                blueprint.clone(),
+                self.cassette.as_ref(),
+            )
+            .await?,
+        );
 
         init_opentelemetry(blueprint.telemetry.clone(), &server_config.app_ctx.runtime)?;
 
-        match blueprint.server.http.clone() {
-            Http::HTTP2 { cert, key } => {
-                start_http_2(server_config, cert, key, self.server_up_sender).await
+        let app_ctx = server_config.app_ctx.clone();
+        let (shutdown_sender, shutdown) = Shutdown::channel();
+        let shutdown_receiver = self.shutdown_receiver.take();
+        tokio::spawn({
+            let app_ctx = app_ctx.clone();
+            async move {
+                shutdown::signal(shutdown_receiver).await;
+                tracing::info!("Shutting down, draining the in-flight requests");
+                app_ctx.start_draining();
+                let _ = shutdown_sender.send(true);
+            }
+        });
+
+        let drain_timeout = blueprint.server.drain_timeout;
+        let mut listeners = vec![];
+        let mut listeners_up = vec![];
+        for listener in blueprint.server.listeners {
+            let (tx, rx) = oneshot::channel();
+            listeners.push(start_listener(
+                server_config.clone(),
+                listener,
+                tx,
+                shutdown.clone(),
+            ));
+            listeners_up.push(rx);
+        }
+
+        // The server is up once all the listeners are bound
+        let server_up = async move {
+            for listener_up in listeners_up {
+                listener_up.await?;
+            }
+            if let Some(sender) = self.server_up_sender {
+                sender
+                    .send(())
+                    .or(Err(anyhow::anyhow!("Failed to send message")))?;
+            }
+            Ok::<_, anyhow::Error>(())
+        };
+
+        let serve = async {
+            tokio::try_join!(try_join_all(listeners), server_up)?;
+            // background cache refreshes might still be calling the upstreams
+            // and the JS workers
+            while !app_ctx.cache_refreshes.is_empty() {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            Ok::<_, anyhow::Error>(())
+        };
+        let timeout = async {
+            shutdown.wait().await;
+            tokio::time::sleep(drain_timeout).await;
+        };
+
+        tokio::select! {
+            result = serve => result?,
+            _ = timeout => {
+                tracing::warn!("Drain timeout elapsed, dropping the in-flight requests");
             }
-            Http::HTTP1 => start_http_1(server_config, self.server_up_sender).await,
         }
+
+        shutdown_opentelemetry().await;
+
+        Ok(())
     }
 
     /// Starts the server in its own multithreaded Runtime
@@ -59,3 +164,35 @@ impl Server {
         result
     }
 }
+
+async fn start_listener(
+    sc: Arc<ServerConfig>,
+    listener: Listener,
+    listener_up: oneshot::Sender<()>,
+    shutdown: Shutdown,
+) -> Result<()> {
+    let listener_up = Some(listener_up);
+    let address = listener.address.clone();
+    let http = listener.http.clone();
+    let listener = Arc::new(listener);
+
+    match (address, http) {
+        #[cfg(unix)]
+        (ListenerAddress::Unix(path), _) => {
+            start_unix(sc, listener, path, listener_up, shutdown).await
+        }
+        #[cfg(not(unix))]
+        (ListenerAddress::Unix(_), _) => Err(anyhow::anyhow!(
+            "Unix domain sockets are not supported on this platform"
+        )),
+        (ListenerAddress::Tcp(addr), Http::HTTP1) => {
+            start_http_1(sc, listener, addr, listener_up, shutdown).await
+        }
+        (ListenerAddress::Tcp(addr), Http::HTTP2 { cert, key }) => {
+            start_http_2(sc, listener, addr, cert, key, listener_up, shutdown).await
+        }
+        (ListenerAddress::Tcp(addr), Http::HTTP3 { cert, key }) => {
+            start_http_3(sc, listener, addr, cert, key, listener_up, shutdown).await
+        }
+    }
+}
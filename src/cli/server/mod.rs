@@ -1,24 +1,79 @@
+pub mod compression;
 pub mod http_1;
 pub mod http_2;
+pub mod http_3;
 pub mod http_server;
 pub mod playground;
 pub mod server_config;
+pub mod shutdown;
+#[cfg(unix)]
+pub mod unix;
 
 pub use http_server::Server;
 
+use std::sync::Arc;
+
+use hyper::header::ALT_SVC;
+use hyper::{Body, Request, Response};
+use serde::de::DeserializeOwned;
+
 use self::server_config::ServerConfig;
+use crate::core::app_context::AppContext;
+use crate::core::async_graphql_hyper::GraphQLRequestLike;
+use crate::core::blueprint::Listener;
+use crate::core::config::ListenerRoute;
+use crate::core::http::handle_listener_request;
 
-fn log_launch(sc: &ServerConfig) {
-    let addr = sc.addr().to_string();
+fn log_launch(sc: &ServerConfig, listener: &Listener) {
     tracing::info!(
         "🚀 Tailcall launched at [{}] over {}",
-        addr,
-        sc.http_version()
+        listener.address,
+        sc.http_version(listener)
     );
 
-    let gql_slug = sc.app_ctx.blueprint.server.routes.graphql();
+    if !listener.serves(ListenerRoute::Playground) {
+        return;
+    }
+
+    if let Some(graphiql_url) = sc.graphiql_url(listener) {
+        let gql_slug = sc.app_ctx.blueprint.server.routes.graphql();
+        let url = playground::build_url(&(graphiql_url + gql_slug));
+        tracing::info!("🌍 Playground: {}", url);
+    }
+}
+
+/// Handles a request received by the listener, compressing the response and
+/// advertising the HTTP/3 listeners serving its routes.
+async fn handle<T: DeserializeOwned + GraphQLRequestLike>(
+    req: Request<Body>,
+    sc: Arc<ServerConfig>,
+    listener: Arc<Listener>,
+) -> anyhow::Result<Response<Body>> {
+    let mut response = handle_compressed::<T>(req, sc.app_ctx.clone(), &listener).await?;
+    if let Some(alt_svc) = sc.alt_svc(&listener) {
+        response.headers_mut().insert(ALT_SVC, alt_svc);
+    }
+
+    Ok(response)
+}
+
+async fn handle_compressed<T: DeserializeOwned + GraphQLRequestLike>(
+    req: Request<Body>,
+    app_ctx: Arc<AppContext>,
+    listener: &Listener,
+) -> anyhow::Result<Response<Body>> {
+    let encoding = compression::encoding(&req, &app_ctx);
+    let min_size = app_ctx
+        .blueprint
+        .server
+        .compression
+        .as_ref()
+        .map(|compression| compression.get_min_size())
+        .unwrap_or_default();
+    let response = handle_listener_request::<T>(req, app_ctx, listener).await?;
 
-    let graphiql_url = sc.graphiql_url() + gql_slug;
-    let url = playground::build_url(&graphiql_url);
-    tracing::info!("🌍 Playground: {}", url);
+    match encoding {
+        Some(encoding) => compression::compress(response, encoding, min_size).await,
+        None => Ok(response),
+    }
 }
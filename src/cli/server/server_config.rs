@@ -2,12 +2,14 @@ use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::Arc;
 
 use async_graphql_extension_apollo_tracing::ApolloTracing;
+use hyper::header::HeaderValue;
 
-use crate::cli::runtime::init;
+use crate::cli::runtime::{init, init_workers, CassetteMode};
 use crate::core::app_context::AppContext;
 use crate::core::blueprint::telemetry::TelemetryExporter;
-use crate::core::blueprint::{Blueprint, Http};
-use crate::core::rest::{EndpointSet, Unchecked};
+use crate::core::blueprint::{Blueprint, Http, Listener, ListenerAddress};
+use crate::core::config::ConfigModule;
+use crate::core::rpc::Gateway;
 use crate::core::schema_extension::SchemaExtension;
 
 pub struct ServerConfig {
@@ -17,10 +19,18 @@ pub struct ServerConfig {
 
 impl ServerConfig {
     pub async fn new(
+        config_module: &ConfigModule,
         blueprint: Blueprint,
-        endpoints: EndpointSet<Unchecked>,
+        cassette: Option<&CassetteMode>,
     ) -> anyhow::Result<Self> {
         let mut rt = init(&blueprint);
+        if let Some(cassette) = cassette {
+            cassette.wrap(&mut rt)?;
+        }
+        // the scripts fetch through the wrapped clients too
+        if cassette.is_some() || !blueprint.server.plugins.is_empty() {
+            init_workers(&mut rt, &blueprint.server)?;
+        }
 
         let mut extensions = vec![];
 
@@ -37,34 +47,47 @@ impl ServerConfig {
         }
         rt.add_extensions(extensions);
 
+        let endpoints = config_module.extensions().endpoint_set.clone();
         let endpoints = endpoints.into_checked(&blueprint, rt.clone()).await?;
-        let app_context = Arc::new(AppContext::new(blueprint.clone(), rt, endpoints));
+        // the config is served by the admin endpoints
+        let config = config_module.config().clone().redacted().to_sdl();
+        let mut app_context = AppContext::new(blueprint.clone(), rt, endpoints).with_config(config);
+        let methods = &config_module.extensions().rpc_methods;
+        if !methods.is_empty() {
+            app_context = app_context.with_rpc(Gateway::try_new(methods, &blueprint)?);
+        }
+        let app_context = Arc::new(app_context);
 
         Ok(Self { app_ctx: app_context, blueprint })
     }
 
-    pub fn addr(&self) -> SocketAddr {
-        (self.blueprint.server.hostname, self.blueprint.server.port).into()
-    }
-
-    pub fn http_version(&self) -> String {
-        match self.blueprint.server.http {
+    pub fn http_version(&self, listener: &Listener) -> String {
+        match listener.http {
             Http::HTTP2 { cert: _, key: _ } => "HTTP/2".to_string(),
+            Http::HTTP3 { cert: _, key: _ } => "HTTP/3".to_string(),
             _ => "HTTP/1.1".to_string(),
         }
     }
 
-    pub fn graphiql_url(&self) -> String {
-        let protocol = match self.http_version().as_str() {
-            "HTTP/2" => "https",
+    /// `Alt-Svc` header of the responses sent by the listener.
+    pub fn alt_svc(&self, listener: &Listener) -> Option<HeaderValue> {
+        self.blueprint.server.alt_svc(listener)
+    }
+
+    /// URL of the listener, `None` for Unix domain sockets.
+    pub fn graphiql_url(&self, listener: &Listener) -> Option<String> {
+        let ListenerAddress::Tcp(mut addr) = listener.address else {
+            return None;
+        };
+        let protocol = match self.http_version(listener).as_str() {
+            "HTTP/2" | "HTTP/3" => "https",
             _ => "http",
         };
-        let mut addr = self.addr();
 
         if addr.ip().is_unspecified() {
             addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), addr.port());
         }
 
-        format!("{}://{}", protocol, addr)
+        Some(format!("{}://{}", protocol, addr))
     }
 }
@@ -0,0 +1,56 @@
+use tokio::sync::{oneshot, watch};
+
+/// Notifies the listeners that the server is shutting down.
+#[derive(Clone)]
+pub struct Shutdown(watch::Receiver<bool>);
+
+impl Shutdown {
+    pub fn channel() -> (watch::Sender<bool>, Self) {
+        let (tx, rx) = watch::channel(false);
+        (tx, Self(rx))
+    }
+
+    /// Resolves once the shutdown has started.
+    pub async fn wait(mut self) {
+        // the sender being dropped means that there won't be any shutdown
+        if self.0.wait_for(|started| *started).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+/// Resolves when the process receives `SIGTERM` or once `trigger` fires.
+/// `Ctrl+C` keeps stopping the server right away.
+pub async fn signal(trigger: Option<oneshot::Receiver<()>>) {
+    let triggered = async move {
+        if let Some(trigger) = trigger {
+            // the sender being dropped doesn't start the shutdown
+            if trigger.await.is_ok() {
+                return;
+            }
+        }
+        std::future::pending::<()>().await
+    };
+
+    tokio::select! {
+        _ = terminate() => {}
+        _ = triggered => {}
+    }
+}
+
+async fn terminate() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+                return;
+            }
+            Err(err) => tracing::warn!("Failed to listen for SIGTERM: {}", err),
+        }
+    }
+
+    std::future::pending::<()>().await;
+}
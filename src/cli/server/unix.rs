@@ -0,0 +1,95 @@
+#![allow(clippy::too_many_arguments)]
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use hyper::server::accept;
+use hyper::service::{make_service_fn, service_fn};
+use tokio::net::UnixListener;
+use tokio::sync::oneshot;
+
+use super::server_config::ServerConfig;
+use super::shutdown::Shutdown;
+use crate::core::async_graphql_hyper::{GraphQLBatchRequest, GraphQLRequest};
+use crate::core::blueprint::Listener;
+use crate::core::Errata;
+
+/// Removes the socket file when dropped, closing the socket leaves the file
+/// behind. The server is dropped without returning when the drain timeout
+/// elapses.
+struct SocketFile(PathBuf);
+
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        if let Err(err) = std::fs::remove_file(&self.0) {
+            tracing::warn!("Failed to remove {}: {}", self.0.display(), err);
+        }
+    }
+}
+
+/// Serves HTTP/1 over a Unix domain socket. A socket file left behind by a
+/// previous run is replaced, the socket file is removed once the server
+/// stops.
+pub async fn start_unix(
+    sc: Arc<ServerConfig>,
+    listener: Arc<Listener>,
+    path: PathBuf,
+    server_up_sender: Option<oneshot::Sender<()>>,
+    shutdown: Shutdown,
+) -> anyhow::Result<()> {
+    if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+    let socket = UnixListener::bind(&path)?;
+    let _socket_file = SocketFile(path);
+    let incoming = accept::poll_fn(move |cx| {
+        socket
+            .poll_accept(cx)
+            .map(|result| Some(result.map(|(stream, _)| stream)))
+    });
+
+    let make_svc_single_req = make_service_fn(|_conn| {
+        let state = Arc::clone(&sc);
+        let listener = Arc::clone(&listener);
+        async move {
+            Ok::<_, anyhow::Error>(service_fn(move |req| {
+                super::handle::<GraphQLRequest>(req, state.clone(), listener.clone())
+            }))
+        }
+    });
+
+    let make_svc_batch_req = make_service_fn(|_conn| {
+        let state = Arc::clone(&sc);
+        let listener = Arc::clone(&listener);
+        async move {
+            Ok::<_, anyhow::Error>(service_fn(move |req| {
+                super::handle::<GraphQLBatchRequest>(req, state.clone(), listener.clone())
+            }))
+        }
+    });
+    let builder = hyper::Server::builder(incoming)
+        .http1_pipeline_flush(sc.app_ctx.blueprint.server.pipeline_flush);
+    super::log_launch(sc.as_ref(), listener.as_ref());
+
+    if let Some(sender) = server_up_sender {
+        sender
+            .send(())
+            .or(Err(anyhow::anyhow!("Failed to send message")))?;
+    }
+
+    let server: std::prelude::v1::Result<(), hyper::Error> =
+        if sc.blueprint.server.enable_batch_requests {
+            builder
+                .serve(make_svc_batch_req)
+                .with_graceful_shutdown(shutdown.wait())
+                .await
+        } else {
+            builder
+                .serve(make_svc_single_req)
+                .with_graceful_shutdown(shutdown.wait())
+                .await
+        };
+
+    let result = server.map_err(Errata::from);
+
+    Ok(result?)
+}
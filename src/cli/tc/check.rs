@@ -1,9 +1,13 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 use super::helpers::{display_schema, log_endpoint_set};
+use crate::cli::command::LintFormat;
 use crate::cli::fmt::Fmt;
 use crate::core::blueprint::Blueprint;
+use crate::core::config::lint::{Linter, Report, Sarif, Severity};
 use crate::core::config::reader::ConfigReader;
+use crate::core::config::Config;
+use crate::core::rpc::Gateway;
 use crate::core::runtime::TargetRuntime;
 use crate::core::Errata;
 
@@ -11,11 +15,17 @@ pub(super) struct CheckParams {
     pub(super) file_paths: Vec<String>,
     pub(super) n_plus_one_queries: bool,
     pub(super) schema: bool,
+    pub(super) lint: Option<LintParams>,
     pub(super) runtime: TargetRuntime,
 }
 
+pub(super) struct LintParams {
+    pub(super) format: LintFormat,
+    pub(super) rules: Vec<String>,
+}
+
 pub(super) async fn check_command(params: CheckParams, config_reader: &ConfigReader) -> Result<()> {
-    let CheckParams { file_paths, n_plus_one_queries, schema, runtime } = params;
+    let CheckParams { file_paths, n_plus_one_queries, schema, lint, runtime } = params;
 
     let config_module = (config_reader.read_all(&file_paths)).await?;
     log_endpoint_set(&config_module.extensions().endpoint_set);
@@ -32,12 +42,75 @@ pub(super) async fn check_command(params: CheckParams, config_reader: &ConfigRea
                 .clone()
                 .into_checked(&blueprint, runtime)
                 .await?;
+            // Check that the plugins can be loaded
+            #[cfg(feature = "plugin")]
+            crate::cli::plugin::init_plugins(&blueprint.server.plugins)?;
+            #[cfg(not(feature = "plugin"))]
+            if !blueprint.server.plugins.is_empty() {
+                return Err(anyhow!("Plugins require the `plugin` feature"));
+            }
+            // Check that the gRPC methods can be generated
+            let methods = &config_module.extensions().rpc_methods;
+            if !methods.is_empty() {
+                Gateway::try_new(methods, &blueprint)?;
+            }
             if schema {
                 display_schema(&blueprint);
             }
+            if let Some(lint) = lint {
+                lint_command(lint, config_module.config(), &file_paths)?;
+            }
 
             Ok(())
         }
         Err(e) => Err(e.into()),
     }
 }
+
+fn lint_command(params: LintParams, config: &Config, file_paths: &[String]) -> Result<()> {
+    let linter = params
+        .rules
+        .iter()
+        .try_fold(Linter::default(), |linter, rule| {
+            let (id, severity) = rule
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Invalid lint rule `{}`, expected RULE=SEVERITY", rule))?;
+            let severity = match severity {
+                "off" => None,
+                severity => Some(severity.parse::<Severity>().map_err(|e| anyhow!(e))?),
+            };
+            linter.severity(id, severity)
+        })?;
+
+    let report = linter.lint(config);
+
+    match params.format {
+        LintFormat::Text => display_report(&report),
+        LintFormat::Json => Fmt::display(serde_json::to_string_pretty(&report)?),
+        LintFormat::Sarif => {
+            let sarif = Sarif::new(&linter, &report, file_paths).to_value();
+            Fmt::display(serde_json::to_string_pretty(&sarif)?)
+        }
+    }
+
+    if report.has_errors() {
+        Err(anyhow!("Lint failed with errors"))
+    } else {
+        Ok(())
+    }
+}
+
+fn display_report(report: &Report) {
+    if report.is_empty() {
+        tracing::info!("Lint ... ok");
+        return;
+    }
+
+    for diagnostic in report.diagnostics.iter() {
+        match diagnostic.severity {
+            Severity::Error => tracing::error!("{}", diagnostic),
+            Severity::Warning => tracing::warn!("{}", diagnostic),
+            Severity::Info => tracing::info!("{}", diagnostic),
+        }
+    }
+}
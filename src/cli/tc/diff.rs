@@ -0,0 +1,64 @@
+use anyhow::{anyhow, Result};
+
+use crate::cli::command::DiffFormat;
+use crate::cli::fmt::Fmt;
+use crate::core::blueprint::{Blueprint, Criticality, SchemaDiff};
+use crate::core::config::reader::ConfigReader;
+use crate::core::Errata;
+
+pub(super) async fn diff_command(
+    old: &str,
+    new: &str,
+    format: DiffFormat,
+    config_reader: &ConfigReader,
+) -> Result<()> {
+    let old_blueprint = read_blueprint(old, config_reader).await?;
+    let new_blueprint = read_blueprint(new, config_reader).await?;
+
+    let diff = SchemaDiff::new(&old_blueprint, &new_blueprint);
+
+    match format {
+        DiffFormat::Json => Fmt::display(serde_json::to_string_pretty(&diff)?),
+        DiffFormat::Text => display_diff(&diff),
+    }
+
+    if diff.has_breaking_changes() {
+        let count = diff.filter(Criticality::Breaking).count();
+        Err(anyhow!(
+            "Detected {} breaking {}",
+            count,
+            pluralizer::pluralize("change", count as isize, false)
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+async fn read_blueprint(path: &str, config_reader: &ConfigReader) -> Result<Blueprint> {
+    let config_module = config_reader.read(path).await?;
+    Ok(Blueprint::try_from(&config_module).map_err(Errata::from)?)
+}
+
+fn display_diff(diff: &SchemaDiff) {
+    if diff.is_empty() {
+        tracing::info!("No changes detected");
+        return;
+    }
+
+    for criticality in [
+        Criticality::Breaking,
+        Criticality::Dangerous,
+        Criticality::Safe,
+    ] {
+        let changes = diff.filter(criticality).collect::<Vec<_>>();
+        if changes.is_empty() {
+            continue;
+        }
+
+        let heading = format!("{} changes ({}):", criticality, changes.len());
+        Fmt::display(Fmt::heading(&heading));
+        for change in changes {
+            Fmt::display(format!("  {} {}", Fmt::meta(&change.path), change.message));
+        }
+    }
+}
@@ -1,7 +1,12 @@
 mod check;
+mod diff;
 mod gen;
 mod helpers;
 mod init;
+mod openapi;
+mod plan;
+mod proto;
 pub mod run;
 mod start;
+mod types;
 mod validate_rc;
@@ -0,0 +1,32 @@
+use anyhow::Result;
+
+use crate::cli::fmt::Fmt;
+use crate::core::blueprint::Blueprint;
+use crate::core::config::reader::ConfigReader;
+use crate::core::runtime::TargetRuntime;
+use crate::core::Errata;
+
+pub(super) async fn openapi_command(
+    file_paths: &[String],
+    output: Option<String>,
+    runtime: TargetRuntime,
+    config_reader: &ConfigReader,
+) -> Result<()> {
+    let config_module = config_reader.read_all(file_paths).await?;
+    let blueprint = Blueprint::try_from(&config_module).map_err(Errata::from)?;
+    let document = config_module
+        .extensions()
+        .endpoint_set
+        .to_openapi(&blueprint);
+    let document = serde_json::to_string_pretty(&document)?;
+
+    match output {
+        Some(path) => {
+            runtime.file.write(&path, document.as_bytes()).await?;
+            tracing::info!("OpenAPI document written to {}", path);
+        }
+        None => Fmt::display(document),
+    }
+
+    Ok(())
+}
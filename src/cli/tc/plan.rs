@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use anyhow::Result;
+use async_graphql_value::ConstValue;
+
+use crate::cli::fmt::Fmt;
+use crate::core::app_context::AppContext;
+use crate::core::blueprint::Blueprint;
+use crate::core::config::reader::ConfigReader;
+use crate::core::jit::{Explain, Request};
+use crate::core::rest::EndpointSet;
+use crate::core::runtime::TargetRuntime;
+use crate::core::Errata;
+
+pub(super) struct PlanParams {
+    pub(super) file_paths: Vec<String>,
+    pub(super) query: String,
+    pub(super) operation_name: Option<String>,
+}
+
+pub(super) async fn plan_command(
+    params: PlanParams,
+    runtime: TargetRuntime,
+    config_reader: &ConfigReader,
+) -> Result<()> {
+    let PlanParams { file_paths, query, operation_name } = params;
+    let config_module = config_reader.read_all(&file_paths).await?;
+    let blueprint = Blueprint::try_from(&config_module).map_err(Errata::from)?;
+    // the data loaders of the batched fields are assigned by the app context
+    let app_ctx = AppContext::new(blueprint, runtime, EndpointSet::default());
+
+    let query = if Path::new(&query).is_file() {
+        tokio::fs::read_to_string(&query).await?
+    } else {
+        query
+    };
+
+    let mut request = Request::<ConstValue>::new(&query);
+    request.operation_name = operation_name;
+    let plan = request.create_plan(&app_ctx.blueprint)?;
+
+    Fmt::display(serde_json::to_string_pretty(&Explain::new(&plan))?);
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use anyhow::Result;
+
+use crate::cli::fmt::Fmt;
+use crate::core::blueprint::Blueprint;
+use crate::core::config::reader::ConfigReader;
+use crate::core::rpc::Gateway;
+use crate::core::runtime::TargetRuntime;
+use crate::core::Errata;
+
+pub(super) async fn proto_command(
+    file_paths: &[String],
+    output: Option<String>,
+    runtime: TargetRuntime,
+    config_reader: &ConfigReader,
+) -> Result<()> {
+    let config_module = config_reader.read_all(file_paths).await?;
+    let blueprint = Blueprint::try_from(&config_module).map_err(Errata::from)?;
+    let gateway = Gateway::try_new(&config_module.extensions().rpc_methods, &blueprint)?;
+
+    match output {
+        Some(path) => {
+            runtime
+                .file
+                .write(&path, gateway.get_proto().as_bytes())
+                .await?;
+            tracing::info!("Proto file written to {}", path);
+        }
+        None => Fmt::display(gateway.get_proto().to_string()),
+    }
+
+    Ok(())
+}
@@ -4,8 +4,9 @@ use dotenvy::dotenv;
 
 use super::helpers::TRACKER;
 use super::validate_rc::validate_rc_config_files;
-use super::{check, gen, init, start};
+use super::{check, diff, gen, init, openapi, plan, proto, start, types};
 use crate::cli::command::{Cli, Command};
+use crate::cli::runtime::CassetteMode;
 use crate::cli::{self, update_checker};
 use crate::core::blueprint::Blueprint;
 use crate::core::config::reader::ConfigReader;
@@ -41,16 +42,32 @@ fn get_runtime_and_config_reader(verify_ssl: bool) -> (TargetRuntime, ConfigRead
 
 async fn run_command(cli: Cli) -> Result<()> {
     match cli.command {
-        Command::Start { file_paths, verify_ssl } => {
+        Command::Start { file_paths, verify_ssl, mock, mock_seed, record, replay } => {
             let (runtime, config_reader) = get_runtime_and_config_reader(verify_ssl);
             validate_rc_config_files(runtime, &file_paths).await;
-            start::start_command(file_paths, &config_reader).await?;
+            let params = start::StartParams {
+                file_paths,
+                mock_seed: mock.then_some(mock_seed),
+                cassette: record
+                    .map(|path| CassetteMode::Record(path.into()))
+                    .or(replay.map(|path| CassetteMode::Replay(path.into()))),
+            };
+            start::start_command(params, &config_reader).await?;
         }
-        Command::Check { file_paths, n_plus_one_queries, schema, verify_ssl } => {
+        Command::Check {
+            file_paths,
+            n_plus_one_queries,
+            schema,
+            lint,
+            lint_format,
+            lint_rules,
+            verify_ssl,
+        } => {
             let (runtime, config_reader) = get_runtime_and_config_reader(verify_ssl);
             validate_rc_config_files(runtime.clone(), &file_paths).await;
+            let lint = lint.then_some(check::LintParams { format: lint_format, rules: lint_rules });
             check::check_command(
-                check::CheckParams { file_paths, n_plus_one_queries, schema, runtime },
+                check::CheckParams { file_paths, n_plus_one_queries, schema, lint, runtime },
                 &config_reader,
             )
             .await?;
@@ -63,6 +80,27 @@ async fn run_command(cli: Cli) -> Result<()> {
             let (runtime, _) = get_runtime_and_config_reader(true);
             gen::gen_command(&file_path, runtime).await?;
         }
+        Command::Diff { old, new, format, verify_ssl } => {
+            let (_, config_reader) = get_runtime_and_config_reader(verify_ssl);
+            diff::diff_command(&old, &new, format, &config_reader).await?;
+        }
+        Command::Plan { file_paths, query, operation_name, verify_ssl } => {
+            let (runtime, config_reader) = get_runtime_and_config_reader(verify_ssl);
+            let params = plan::PlanParams { file_paths, query, operation_name };
+            plan::plan_command(params, runtime, &config_reader).await?;
+        }
+        Command::Types { file_paths, output, verify_ssl } => {
+            let (runtime, config_reader) = get_runtime_and_config_reader(verify_ssl);
+            types::types_command(&file_paths, output, runtime, &config_reader).await?;
+        }
+        Command::OpenApi { file_paths, output, verify_ssl } => {
+            let (runtime, config_reader) = get_runtime_and_config_reader(verify_ssl);
+            openapi::openapi_command(&file_paths, output, runtime, &config_reader).await?;
+        }
+        Command::Proto { file_paths, output, verify_ssl } => {
+            let (runtime, config_reader) = get_runtime_and_config_reader(verify_ssl);
+            proto::proto_command(&file_paths, output, runtime, &config_reader).await?;
+        }
     }
     Ok(())
 }
@@ -2,17 +2,42 @@ use anyhow::Result;
 
 use super::helpers::log_endpoint_set;
 use crate::cli::fmt::Fmt;
+use crate::cli::runtime::CassetteMode;
 use crate::cli::server::Server;
 use crate::core::config::reader::ConfigReader;
+use crate::core::mock::Mocker;
+
+pub(super) struct StartParams {
+    pub(super) file_paths: Vec<String>,
+    pub(super) mock_seed: Option<u64>,
+    pub(super) cassette: Option<CassetteMode>,
+}
+
+pub(super) async fn start_command(params: StartParams, config_reader: &ConfigReader) -> Result<()> {
+    let StartParams { file_paths, mock_seed, cassette } = params;
 
-pub(super) async fn start_command(
-    file_paths: Vec<String>,
-    config_reader: &ConfigReader,
-) -> Result<()> {
     let config_module = config_reader.read_all(&file_paths).await?;
     log_endpoint_set(&config_module.extensions().endpoint_set);
     Fmt::log_n_plus_one(false, config_module.config());
-    let server = Server::new(config_module);
+    let mocker = mock_seed
+        .map(|seed| Mocker::from_config_module(seed, &config_module))
+        .transpose()?;
+    let mut server = Server::new(config_module);
+    if let Some(mocker) = mocker {
+        tracing::info!("Mock mode enabled, upstreams will not be called");
+        server = server.mock(mocker);
+    }
+    if let Some(cassette) = cassette {
+        match &cassette {
+            CassetteMode::Record(path) => {
+                tracing::info!("Recording upstream traffic to {}", path.display())
+            }
+            CassetteMode::Replay(path) => {
+                tracing::info!("Replaying upstream traffic from {}", path.display())
+            }
+        }
+        server = server.cassette(cassette);
+    }
     server.fork_start().await?;
     Ok(())
 }
@@ -0,0 +1,25 @@
+use anyhow::Result;
+
+use crate::cli::fmt::Fmt;
+use crate::core::config::reader::ConfigReader;
+use crate::core::runtime::TargetRuntime;
+
+pub(super) async fn types_command(
+    file_paths: &[String],
+    output: Option<String>,
+    runtime: TargetRuntime,
+    config_reader: &ConfigReader,
+) -> Result<()> {
+    let config_module = config_reader.read_all(file_paths).await?;
+    let definitions = config_module.to_typescript();
+
+    match output {
+        Some(path) => {
+            runtime.file.write(&path, definitions.as_bytes()).await?;
+            tracing::info!("TypeScript definitions written to {}", path);
+        }
+        None => Fmt::display(definitions),
+    }
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use std::io::Write;
+use std::sync::Mutex;
 
 use anyhow::{anyhow, Result};
 use once_cell::sync::Lazy;
@@ -9,14 +10,13 @@ use opentelemetry::{global, KeyValue};
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use opentelemetry_otlp::{TonicExporterBuilder, WithExportConfig};
 use opentelemetry_sdk::logs::{Logger, LoggerProvider};
-use opentelemetry_sdk::metrics::{MeterProviderBuilder, PeriodicReader};
+use opentelemetry_sdk::metrics::{MeterProviderBuilder, PeriodicReader, SdkMeterProvider};
 use opentelemetry_sdk::propagation::TraceContextPropagator;
 use opentelemetry_sdk::runtime::Tokio;
 use opentelemetry_sdk::trace::{Tracer, TracerProvider};
 use opentelemetry_sdk::{runtime, Resource};
 use serde::Serialize;
 use tonic::metadata::MetadataMap;
-use tracing::level_filters::LevelFilter;
 use tracing::Subscriber;
 use tracing_opentelemetry::OpenTelemetryLayer;
 use tracing_subscriber::filter::dynamic_filter_fn;
@@ -27,7 +27,7 @@ use super::metrics::init_metrics;
 use crate::core::blueprint::telemetry::{OtlpExporter, Telemetry, TelemetryExporter};
 use crate::core::runtime::TargetRuntime;
 use crate::core::tracing::{
-    default_tracing, default_tracing_tailcall, get_log_level, tailcall_filter_target,
+    default_tracing, default_tracing_tailcall, log_level_filter, tailcall_filter_target,
 };
 use crate::core::Errata;
 
@@ -44,6 +44,11 @@ static RESOURCE: Lazy<Resource> = Lazy::new(|| {
     ]))
 });
 
+// The logger and meter providers aren't kept by the global API, so they are
+// stored to be flushed on shutdown
+static LOGGER_PROVIDER: Mutex<Option<LoggerProvider>> = Mutex::new(None);
+static METER_PROVIDER: Mutex<Option<SdkMeterProvider>> = Mutex::new(None);
+
 fn pretty_encoder<T: Serialize>(writer: &mut dyn Write, data: T) -> Result<()> {
     // convert to buffer first to use write_all and minimize
     // interleaving for std stream output
@@ -139,6 +144,9 @@ fn set_logger_provider(
     };
 
     let otel_tracing_appender = OpenTelemetryTracingBridge::new(&provider);
+    if let Ok(mut logger_provider) = LOGGER_PROVIDER.lock() {
+        *logger_provider = Some(provider);
+    }
 
     Ok(Some(otel_tracing_appender))
 }
@@ -180,6 +188,9 @@ fn set_meter_provider(exporter: &TelemetryExporter) -> MetricsResult<()> {
         _ => return Ok(()),
     };
 
+    if let Ok(mut meter_provider) = METER_PROVIDER.lock() {
+        *meter_provider = Some(provider.clone());
+    }
     global::set_meter_provider(provider);
 
     Ok(())
@@ -232,9 +243,7 @@ pub fn init_opentelemetry(config: Telemetry, runtime: &TargetRuntime) -> anyhow:
                 })),
             )
             .with(tailcall_filter_target())
-            .with(LevelFilter::from_level(
-                get_log_level().unwrap_or(tracing::Level::INFO),
-            ));
+            .with(log_level_filter());
 
         init_metrics(runtime)?;
 
@@ -245,3 +254,28 @@ pub fn init_opentelemetry(config: Telemetry, runtime: &TargetRuntime) -> anyhow:
 
     Ok(())
 }
+
+/// Exports the pending spans, logs and metrics.
+pub async fn shutdown_opentelemetry() {
+    // the providers block while flushing
+    let result = tokio::task::spawn_blocking(|| {
+        global::shutdown_tracer_provider();
+
+        if let Some(provider) = LOGGER_PROVIDER.lock().ok().and_then(|mut p| p.take()) {
+            if let Err(err) = provider.shutdown() {
+                tracing::warn!("Failed to flush the logs: {}", err);
+            }
+        }
+
+        if let Some(provider) = METER_PROVIDER.lock().ok().and_then(|mut p| p.take()) {
+            if let Err(err) = provider.shutdown() {
+                tracing::warn!("Failed to flush the metrics: {}", err);
+            }
+        }
+    })
+    .await;
+
+    if let Err(err) = result {
+        tracing::warn!("Failed to flush the telemetry: {}", err);
+    }
+}
@@ -1,12 +1,14 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use async_graphql::dynamic::{self, DynamicRequest};
 use async_graphql_value::ConstValue;
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 
 use super::jit::AnyResponse;
 use crate::core::async_graphql_hyper::OperationId;
 use crate::core::blueprint::{Blueprint, Definition, SchemaModifiers};
+use crate::core::cache::InMemoryCache;
 use crate::core::data_loader::{DataLoader, DedupeResult};
 use crate::core::graphql::GraphqlDataLoader;
 use crate::core::grpc;
@@ -16,6 +18,7 @@ use crate::core::ir::model::{DataLoaderId, IoId, IO, IR};
 use crate::core::ir::Error;
 use crate::core::jit::{OPHash, OperationPlan};
 use crate::core::rest::{Checked, EndpointSet};
+use crate::core::rpc::Gateway;
 use crate::core::runtime::TargetRuntime;
 
 pub struct AppContext {
@@ -26,10 +29,18 @@ pub struct AppContext {
     pub gql_data_loaders: Arc<Vec<DataLoader<DataLoaderRequest, GraphqlDataLoader>>>,
     pub grpc_data_loaders: Arc<Vec<DataLoader<grpc::DataLoaderRequest, GrpcDataLoader>>>,
     pub endpoints: EndpointSet<Checked>,
+    /// Operations served as gRPC methods, if any is exposed with `@rpc`.
+    pub rpc: Option<Arc<Gateway>>,
     pub dedupe_handler: Arc<DedupeResult<IoId, ConstValue, Error>>,
     pub dedupe_operation_handler: DedupeResult<OperationId, AnyResponse<Vec<u8>>, Error>,
     pub operation_plans: DashMap<OPHash, OperationPlan<async_graphql_value::Value>>,
     pub const_execution_cache: DashMap<OPHash, AnyResponse<Vec<u8>>>,
+    pub cache_refreshes: Arc<DashSet<IoId>>,
+    pub response_cache: Option<InMemoryCache<OPHash, AnyResponse<Vec<u8>>>>,
+    /// SDL of the config the blueprint was built from without its secrets, if
+    /// known.
+    pub config: Option<String>,
+    draining: AtomicBool,
 }
 
 impl AppContext {
@@ -130,6 +141,12 @@ impl AppContext {
             }
         }
 
+        let response_cache = blueprint
+            .server
+            .response_cache
+            .as_ref()
+            .map(|response_cache| InMemoryCache::new(response_cache.get_max_size()));
+
         let schema = blueprint
             .to_schema_with(SchemaModifiers::default().extensions(runtime.extensions.clone()));
 
@@ -141,14 +158,37 @@ impl AppContext {
             gql_data_loaders: Arc::new(gql_data_loaders),
             grpc_data_loaders: Arc::new(grpc_data_loaders),
             endpoints,
+            rpc: None,
 
             dedupe_handler: Arc::new(DedupeResult::new(false)),
             dedupe_operation_handler: DedupeResult::new(false),
             operation_plans: DashMap::new(),
             const_execution_cache: DashMap::default(),
+            cache_refreshes: Arc::new(DashSet::new()),
+            response_cache,
+            config: None,
+            draining: AtomicBool::new(false),
         }
     }
 
+    pub fn with_config(self, config: String) -> Self {
+        Self { config: Some(config), ..self }
+    }
+
+    pub fn with_rpc(self, rpc: Gateway) -> Self {
+        Self { rpc: Some(Arc::new(rpc)), ..self }
+    }
+
+    /// Marks the server as shutting down, the status route reports it as
+    /// unavailable from then on.
+    pub fn start_draining(&self) {
+        self.draining.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Relaxed)
+    }
+
     pub async fn execute(&self, request: impl Into<DynamicRequest>) -> async_graphql::Response {
         self.schema.execute(request).await
     }
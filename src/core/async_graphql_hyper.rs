@@ -27,6 +27,11 @@ pub trait GraphQLRequestLike: Hash + Send {
 
     fn parse_query(&mut self) -> Option<&ExecutableDocument>;
 
+    /// Number of operations in the request.
+    fn batch_len(&self) -> usize {
+        1
+    }
+
     fn is_query(&mut self) -> bool {
         self.parse_query()
             .map(|a| {
@@ -93,6 +98,13 @@ impl GraphQLRequestLike for GraphQLBatchRequest {
     fn parse_query(&mut self) -> Option<&ExecutableDocument> {
         None
     }
+
+    fn batch_len(&self) -> usize {
+        match &self.0 {
+            async_graphql::BatchRequest::Single(_) => 1,
+            async_graphql::BatchRequest::Batch(requests) => requests.len(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -133,6 +145,76 @@ impl GraphQLRequestLike for GraphQLRequest {
     }
 }
 
+/// Deepest nesting of the variables across the operations of a JSON encoded
+/// request, an object counts as one level. The body is scanned without being
+/// parsed so that deeply nested variables are rejected before they are
+/// deserialized.
+pub fn variables_depth(bytes: &[u8]) -> usize {
+    // the operations are the root object or the elements of the root array
+    let operation_depth = match bytes.iter().find(|byte| !byte.is_ascii_whitespace()) {
+        Some(b'[') => 2,
+        _ => 1,
+    };
+    let mut depth = 0usize;
+    let mut max_depth = 1;
+    // depth outside of the variables being scanned
+    let mut variables = None;
+    let mut is_variables_key = false;
+    let mut string_start = None;
+    let mut key = None;
+    let mut escaped = false;
+
+    for (i, byte) in bytes.iter().enumerate() {
+        if let Some(start) = string_start {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => {
+                    string_start = None;
+                    key = Some(&bytes[start..=i]);
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match byte {
+            b'"' => string_start = Some(i),
+            b':' if depth == operation_depth => {
+                is_variables_key = key.is_some_and(is_variables);
+            }
+            b'{' | b'[' => {
+                if is_variables_key && depth == operation_depth {
+                    variables = Some(depth);
+                }
+                is_variables_key = false;
+                depth += 1;
+                if let Some(outer) = variables {
+                    max_depth = max_depth.max(depth - outer);
+                }
+            }
+            b'}' | b']' => {
+                depth = depth.saturating_sub(1);
+                if variables == Some(depth) {
+                    variables = None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    max_depth
+}
+
+/// Checks if the JSON string is `"variables"`, escapes included.
+fn is_variables(key: &[u8]) -> bool {
+    if key.contains(&b'\\') {
+        serde_json::from_slice::<String>(key).is_ok_and(|key| key == "variables")
+    } else {
+        key == b"\"variables\""
+    }
+}
+
 // TODO: drop this type since we can use jit::response?
 #[derive(Debug, Serialize)]
 pub struct GraphQLResponse(pub async_graphql::BatchResponse);
@@ -407,6 +489,21 @@ impl GraphQLArcResponse {
     pub fn into_response(self) -> Result<Response<hyper::Body>> {
         self.build_response(StatusCode::OK, self.default_body()?)
     }
+
+    /// Strong ETag derived from the body of the response.
+    pub fn etag(&self) -> String {
+        let mut hasher = TailcallHasher::default();
+        match &self.response {
+            JITBatchResponse::Batch(responses) => {
+                for response in responses {
+                    response.body.hash(&mut hasher);
+                }
+            }
+            JITBatchResponse::Single(response) => response.body.hash(&mut hasher),
+        }
+
+        format!("\"{:x}\"", hasher.finish())
+    }
 }
 
 #[cfg(test)]
@@ -534,4 +631,42 @@ mod tests {
             Some("no-cache, private".to_string())
         );
     }
+
+    #[test]
+    fn test_limits_metrics() {
+        let request: GraphQLBatchRequest = serde_json::from_value(json!([
+            {"query": "{ a }"},
+            {"query": "{ b }", "variables": {"a": {"b": [{"c": 1}]}}}
+        ]))
+        .unwrap();
+        assert_eq!(request.batch_len(), 2);
+
+        let request: GraphQLRequest =
+            serde_json::from_value(json!({"query": "{ a }", "variables": {"a": 1}})).unwrap();
+        assert_eq!(request.batch_len(), 1);
+    }
+
+    #[test]
+    fn test_variables_depth() {
+        let depth = |body: serde_json::Value| variables_depth(body.to_string().as_bytes());
+
+        assert_eq!(depth(json!({"query": "{ a }"})), 1);
+        assert_eq!(depth(json!({"query": "{ a }", "variables": {"a": 1}})), 1);
+        assert_eq!(
+            depth(json!([
+                {"query": "{ a }"},
+                {"query": "{ b }", "variables": {"a": {"b": [{"c": 1}]}}}
+            ])),
+            4
+        );
+        // only the variables are measured
+        assert_eq!(
+            depth(json!({"query": "{ a }", "extensions": {"a": {"b": {}}}, "variables": {}})),
+            1
+        );
+        // brackets in strings don't count
+        assert_eq!(depth(json!({"variables": {"a": "{[\"{"}})), 1);
+        // escaped keys are the same key
+        assert_eq!(variables_depth(br#"{"variabl\u0065s": {"a": {}}}"#), 2);
+    }
 }
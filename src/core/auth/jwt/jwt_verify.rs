@@ -20,6 +20,7 @@ pub enum OneOrMany<T> {
 pub struct JwtClaim {
     pub aud: Option<OneOrMany<String>>,
     pub iss: Option<String>,
+    pub sub: Option<String>,
 }
 
 pub struct JwtVerifier {
@@ -46,10 +47,18 @@ impl JwtVerifier {
         Ok(value.map(|token| token.token().to_owned()))
     }
 
-    async fn validate_token(&self, token: &str) -> Verification {
+    /// Validates the token, recording its subject in the request once it's
+    /// verified.
+    async fn validate_token(&self, token: &str, request: &RequestContext) -> Verification {
         Verification::from_result(
             self.decoder.decode(token),
-            |claims| self.validate_claims(&claims),
+            |claims| {
+                let verification = self.validate_claims(&claims);
+                if let (Verification::Succeed, Some(sub)) = (&verification, claims.sub) {
+                    request.set_jwt_subject(sub);
+                }
+                verification
+            },
             |err| Verification::fail(Error::Parse(err.to_string())),
         )
     }
@@ -74,7 +83,7 @@ impl Verify for JwtVerifier {
             return Verification::fail(Error::Missing);
         };
 
-        self.validate_token(&token).await
+        self.validate_token(&token, request).await
     }
 }
 
@@ -173,6 +182,23 @@ pub mod tests {
         req_context
     }
 
+    #[tokio::test]
+    async fn verified_subject() {
+        let jwt_provider = JwtVerifier::new(blueprint::Jwt::test_value());
+
+        let request = create_jwt_auth_request(JWT_VALID_TOKEN_WITH_KID);
+        jwt_provider.verify(&request).await;
+        assert_eq!(request.jwt_subject(), Some("you".to_owned()));
+
+        let jwt_provider = JwtVerifier::new(blueprint::Jwt {
+            issuer: Some("another".to_owned()),
+            ..blueprint::Jwt::test_value()
+        });
+        let request = create_jwt_auth_request(JWT_VALID_TOKEN_WITH_KID);
+        jwt_provider.verify(&request).await;
+        assert_eq!(request.jwt_subject(), None);
+    }
+
     #[tokio::test]
     async fn validate_token_iss() {
         let jwt_options = blueprint::Jwt::test_value();
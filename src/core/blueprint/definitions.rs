@@ -10,9 +10,9 @@ use union_resolver::update_union_resolver;
 use crate::core::blueprint::*;
 use crate::core::config::{Config, Enum, Field, GraphQLOperationType, Protected, Union};
 use crate::core::directive::DirectiveCodec;
-use crate::core::ir::model::{Cache, IR};
+use crate::core::ir::model::{Cache, CacheKeyOptions, Stale, IR};
 use crate::core::try_fold::TryFold;
-use crate::core::{config, scalar, Type};
+use crate::core::{config, scalar, Mustache, Type};
 
 pub fn to_scalar_type_definition(name: &str) -> Valid<Definition, BlueprintError> {
     if scalar::Scalar::is_predefined(name) {
@@ -372,8 +372,19 @@ pub fn update_cache_resolvers<'a>() -> TryFold<
 > {
     TryFold::<(&ConfigModule, &Field, &config::Type, &str), FieldDefinition, BlueprintError>::new(
         move |(_config, field, typ, _name), mut b_field| {
-            if let Some(config::Cache { max_age }) = field.cache.as_ref().or(typ.cache.as_ref()) {
-                b_field.map_expr(|expression| Cache::wrap(*max_age, expression))
+            if let Some(cache) = field.cache.as_ref().or(typ.cache.as_ref()) {
+                let tags = cache.tags.iter().map(|tag| Mustache::parse(tag)).collect();
+                let stale = Stale {
+                    while_revalidate: cache.stale_while_revalidate,
+                    if_error: cache.stale_if_error,
+                };
+                let key = CacheKeyOptions {
+                    template: cache.key.as_deref().map(Mustache::parse),
+                    private: cache.get_scope() == config::CacheScope::Private,
+                    vary_headers: cache.vary_headers.clone(),
+                };
+                b_field
+                    .map_expr(|expression| Cache::wrap(cache.max_age, tags, stale, key, expression))
             }
 
             Valid::succeed(b_field)
@@ -547,6 +558,7 @@ pub fn to_field_definition(
         .and(update_modify().trace(config::Modify::trace_name().as_str()))
         .and(fix_dangling_resolvers())
         .and(update_cache_resolvers())
+        .and(update_invalidate().trace(config::Invalidate::trace_name().as_str()))
         .and(update_protected(object_name).trace(Protected::trace_name().as_str()))
         .and(update_enum_alias())
         .and(update_union_resolver())
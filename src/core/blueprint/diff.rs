@@ -0,0 +1,650 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+use serde::Serialize;
+
+use super::directive::Directive;
+use super::{
+    Blueprint, Definition, EnumTypeDefinition, FieldDefinition, InputFieldDefinition,
+    InputObjectTypeDefinition, UnionTypeDefinition,
+};
+use crate::core::Type;
+
+/// Classifies how a schema change affects existing clients.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Criticality {
+    /// The change can't break any existing client.
+    Safe,
+    /// The change won't break validation of existing operations but might
+    /// change runtime behaviour of clients eg. a new enum value.
+    Dangerous,
+    /// Existing operations can fail or receive unexpected data.
+    Breaking,
+}
+
+impl Display for Criticality {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Criticality::Safe => "safe",
+            Criticality::Dangerous => "dangerous",
+            Criticality::Breaking => "breaking",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChangeKind {
+    TypeAdded,
+    TypeRemoved,
+    TypeKindChanged,
+    FieldAdded,
+    FieldRemoved,
+    FieldTypeChanged,
+    ArgumentAdded,
+    ArgumentRemoved,
+    ArgumentTypeChanged,
+    ArgumentDefaultChanged,
+    InputFieldAdded,
+    InputFieldRemoved,
+    InputFieldTypeChanged,
+    EnumValueAdded,
+    EnumValueRemoved,
+    UnionMemberAdded,
+    UnionMemberRemoved,
+    InterfaceAdded,
+    InterfaceRemoved,
+    DirectiveAdded,
+    DirectiveRemoved,
+    DirectiveChanged,
+    RootTypeChanged,
+}
+
+/// A single difference between two blueprints.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub criticality: Criticality,
+    /// Schema coordinate of the changed element eg. `Query.user(id:)`
+    pub path: String,
+    pub message: String,
+}
+
+impl Change {
+    fn new(
+        kind: ChangeKind,
+        criticality: Criticality,
+        path: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            criticality,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Result of comparing two blueprints.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SchemaDiff {
+    pub changes: Vec<Change>,
+}
+
+impl SchemaDiff {
+    /// Computes the changes required to go from `old` to `new`.
+    pub fn new(old: &Blueprint, new: &Blueprint) -> Self {
+        let mut changes = Vec::new();
+
+        diff_root(
+            "query",
+            Some(&old.schema.query),
+            Some(&new.schema.query),
+            &mut changes,
+        );
+        diff_root(
+            "mutation",
+            old.schema.mutation.as_ref(),
+            new.schema.mutation.as_ref(),
+            &mut changes,
+        );
+        diff_directives(
+            "schema",
+            &old.schema.directives,
+            &new.schema.directives,
+            &mut changes,
+        );
+
+        let old_defs = old
+            .definitions
+            .iter()
+            .map(|def| (def.name(), def))
+            .collect::<BTreeMap<_, _>>();
+        let new_defs = new
+            .definitions
+            .iter()
+            .map(|def| (def.name(), def))
+            .collect::<BTreeMap<_, _>>();
+
+        for (name, old_def) in old_defs.iter() {
+            match new_defs.get(name) {
+                Some(new_def) => diff_definition(old_def, new_def, &mut changes),
+                None => changes.push(Change::new(
+                    ChangeKind::TypeRemoved,
+                    Criticality::Breaking,
+                    *name,
+                    format!("Type `{}` was removed", name),
+                )),
+            }
+        }
+
+        for name in new_defs.keys().filter(|name| !old_defs.contains_key(*name)) {
+            changes.push(Change::new(
+                ChangeKind::TypeAdded,
+                Criticality::Safe,
+                *name,
+                format!("Type `{}` was added", name),
+            ));
+        }
+
+        Self { changes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn has_breaking_changes(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|change| change.criticality == Criticality::Breaking)
+    }
+
+    /// Returns changes with criticality equal to the given one.
+    pub fn filter(&self, criticality: Criticality) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(move |change| change.criticality == criticality)
+    }
+}
+
+fn diff_root(
+    operation: &str,
+    old: Option<&String>,
+    new: Option<&String>,
+    changes: &mut Vec<Change>,
+) {
+    if old == new {
+        return;
+    }
+
+    let criticality = if old.is_none() {
+        Criticality::Safe
+    } else {
+        Criticality::Breaking
+    };
+
+    changes.push(Change::new(
+        ChangeKind::RootTypeChanged,
+        criticality,
+        format!("schema.{}", operation),
+        format!(
+            "Root {} type changed from `{}` to `{}`",
+            operation,
+            old.map(String::as_str).unwrap_or("none"),
+            new.map(String::as_str).unwrap_or("none")
+        ),
+    ));
+}
+
+fn kind_of(def: &Definition) -> &'static str {
+    match def {
+        Definition::Interface(_) => "interface",
+        Definition::Object(_) => "object",
+        Definition::InputObject(_) => "input",
+        Definition::Scalar(_) => "scalar",
+        Definition::Enum(_) => "enum",
+        Definition::Union(_) => "union",
+    }
+}
+
+fn diff_definition(old: &Definition, new: &Definition, changes: &mut Vec<Change>) {
+    let name = old.name();
+    diff_directives(name, old.directives(), new.directives(), changes);
+
+    match (old, new) {
+        (Definition::Object(old), Definition::Object(new)) => {
+            diff_implements(name, &old.implements, &new.implements, changes);
+            diff_fields(name, &old.fields, &new.fields, changes);
+        }
+        (Definition::Interface(old), Definition::Interface(new)) => {
+            diff_implements(name, &old.implements, &new.implements, changes);
+            diff_fields(name, &old.fields, &new.fields, changes);
+        }
+        (Definition::InputObject(old), Definition::InputObject(new)) => {
+            diff_input_object(old, new, changes);
+        }
+        (Definition::Enum(old), Definition::Enum(new)) => diff_enum(old, new, changes),
+        (Definition::Union(old), Definition::Union(new)) => diff_union(old, new, changes),
+        (Definition::Scalar(_), Definition::Scalar(_)) => {}
+        (old, new) => changes.push(Change::new(
+            ChangeKind::TypeKindChanged,
+            Criticality::Breaking,
+            name,
+            format!(
+                "Type `{}` changed from {} to {}",
+                name,
+                kind_of(old),
+                kind_of(new)
+            ),
+        )),
+    }
+}
+
+fn diff_implements(
+    name: &str,
+    old: &BTreeSet<String>,
+    new: &BTreeSet<String>,
+    changes: &mut Vec<Change>,
+) {
+    for interface in old.difference(new) {
+        changes.push(Change::new(
+            ChangeKind::InterfaceRemoved,
+            Criticality::Breaking,
+            name,
+            format!("`{}` no longer implements interface `{}`", name, interface),
+        ));
+    }
+
+    for interface in new.difference(old) {
+        changes.push(Change::new(
+            ChangeKind::InterfaceAdded,
+            Criticality::Dangerous,
+            name,
+            format!("`{}` now implements interface `{}`", name, interface),
+        ));
+    }
+}
+
+fn diff_fields(
+    type_name: &str,
+    old: &[FieldDefinition],
+    new: &[FieldDefinition],
+    changes: &mut Vec<Change>,
+) {
+    for old_field in old {
+        let path = format!("{}.{}", type_name, old_field.name);
+        let Some(new_field) = new.iter().find(|field| field.name == old_field.name) else {
+            changes.push(Change::new(
+                ChangeKind::FieldRemoved,
+                Criticality::Breaking,
+                path.as_str(),
+                format!("Field `{}` was removed", path),
+            ));
+            continue;
+        };
+
+        if old_field.of_type != new_field.of_type {
+            let criticality = if is_safe_output_change(&old_field.of_type, &new_field.of_type) {
+                Criticality::Safe
+            } else {
+                Criticality::Breaking
+            };
+            changes.push(Change::new(
+                ChangeKind::FieldTypeChanged,
+                criticality,
+                path.as_str(),
+                format!(
+                    "Field `{}` changed type from `{:?}` to `{:?}`",
+                    path, old_field.of_type, new_field.of_type
+                ),
+            ));
+        }
+
+        diff_arguments(&path, &old_field.args, &new_field.args, changes);
+        diff_directives(&path, &old_field.directives, &new_field.directives, changes);
+    }
+
+    for new_field in new
+        .iter()
+        .filter(|field| !old.iter().any(|old_field| old_field.name == field.name))
+    {
+        let path = format!("{}.{}", type_name, new_field.name);
+        changes.push(Change::new(
+            ChangeKind::FieldAdded,
+            Criticality::Safe,
+            path.as_str(),
+            format!("Field `{}` was added", path),
+        ));
+    }
+}
+
+fn diff_arguments(
+    field_path: &str,
+    old: &[InputFieldDefinition],
+    new: &[InputFieldDefinition],
+    changes: &mut Vec<Change>,
+) {
+    for old_arg in old {
+        let path = format!("{}({}:)", field_path, old_arg.name);
+        let Some(new_arg) = new.iter().find(|arg| arg.name == old_arg.name) else {
+            changes.push(Change::new(
+                ChangeKind::ArgumentRemoved,
+                Criticality::Breaking,
+                path.as_str(),
+                format!("Argument `{}` was removed", path),
+            ));
+            continue;
+        };
+
+        if old_arg.of_type != new_arg.of_type {
+            let criticality = if is_safe_output_change(&new_arg.of_type, &old_arg.of_type) {
+                Criticality::Safe
+            } else {
+                Criticality::Breaking
+            };
+            changes.push(Change::new(
+                ChangeKind::ArgumentTypeChanged,
+                criticality,
+                path.as_str(),
+                format!(
+                    "Argument `{}` changed type from `{:?}` to `{:?}`",
+                    path, old_arg.of_type, new_arg.of_type
+                ),
+            ));
+        }
+
+        if old_arg.default_value != new_arg.default_value {
+            changes.push(Change::new(
+                ChangeKind::ArgumentDefaultChanged,
+                Criticality::Dangerous,
+                path.as_str(),
+                format!("Default value of argument `{}` was changed", path),
+            ));
+        }
+    }
+
+    for new_arg in new
+        .iter()
+        .filter(|arg| !old.iter().any(|old_arg| old_arg.name == arg.name))
+    {
+        let path = format!("{}({}:)", field_path, new_arg.name);
+        let criticality = if is_required_input(new_arg) {
+            Criticality::Breaking
+        } else {
+            Criticality::Safe
+        };
+        changes.push(Change::new(
+            ChangeKind::ArgumentAdded,
+            criticality,
+            path.as_str(),
+            format!("Argument `{}` was added", path),
+        ));
+    }
+}
+
+fn diff_input_object(
+    old: &InputObjectTypeDefinition,
+    new: &InputObjectTypeDefinition,
+    changes: &mut Vec<Change>,
+) {
+    for old_field in old.fields.iter() {
+        let path = format!("{}.{}", old.name, old_field.name);
+        let Some(new_field) = new.fields.iter().find(|field| field.name == old_field.name) else {
+            changes.push(Change::new(
+                ChangeKind::InputFieldRemoved,
+                Criticality::Breaking,
+                path.as_str(),
+                format!("Input field `{}` was removed", path),
+            ));
+            continue;
+        };
+
+        if old_field.of_type != new_field.of_type {
+            let criticality = if is_safe_output_change(&new_field.of_type, &old_field.of_type) {
+                Criticality::Safe
+            } else {
+                Criticality::Breaking
+            };
+            changes.push(Change::new(
+                ChangeKind::InputFieldTypeChanged,
+                criticality,
+                path.as_str(),
+                format!(
+                    "Input field `{}` changed type from `{:?}` to `{:?}`",
+                    path, old_field.of_type, new_field.of_type
+                ),
+            ));
+        }
+    }
+
+    for new_field in new.fields.iter().filter(|field| {
+        !old.fields
+            .iter()
+            .any(|old_field| old_field.name == field.name)
+    }) {
+        let path = format!("{}.{}", new.name, new_field.name);
+        let criticality = if is_required_input(new_field) {
+            Criticality::Breaking
+        } else {
+            Criticality::Dangerous
+        };
+        changes.push(Change::new(
+            ChangeKind::InputFieldAdded,
+            criticality,
+            path.as_str(),
+            format!("Input field `{}` was added", path),
+        ));
+    }
+}
+
+fn diff_enum(old: &EnumTypeDefinition, new: &EnumTypeDefinition, changes: &mut Vec<Change>) {
+    let old_values = old
+        .enum_values
+        .iter()
+        .map(|value| value.name.as_str())
+        .collect::<BTreeSet<_>>();
+    let new_values = new
+        .enum_values
+        .iter()
+        .map(|value| value.name.as_str())
+        .collect::<BTreeSet<_>>();
+
+    for value in old_values.difference(&new_values) {
+        let path = format!("{}.{}", old.name, value);
+        changes.push(Change::new(
+            ChangeKind::EnumValueRemoved,
+            Criticality::Breaking,
+            path.as_str(),
+            format!("Enum value `{}` was removed", path),
+        ));
+    }
+
+    for value in new_values.difference(&old_values) {
+        let path = format!("{}.{}", new.name, value);
+        changes.push(Change::new(
+            ChangeKind::EnumValueAdded,
+            Criticality::Dangerous,
+            path.as_str(),
+            format!("Enum value `{}` was added", path),
+        ));
+    }
+}
+
+fn diff_union(old: &UnionTypeDefinition, new: &UnionTypeDefinition, changes: &mut Vec<Change>) {
+    for member in old.types.difference(&new.types) {
+        changes.push(Change::new(
+            ChangeKind::UnionMemberRemoved,
+            Criticality::Breaking,
+            old.name.as_str(),
+            format!("`{}` was removed from union `{}`", member, old.name),
+        ));
+    }
+
+    for member in new.types.difference(&old.types) {
+        changes.push(Change::new(
+            ChangeKind::UnionMemberAdded,
+            Criticality::Dangerous,
+            new.name.as_str(),
+            format!("`{}` was added to union `{}`", member, new.name),
+        ));
+    }
+}
+
+fn diff_directives(path: &str, old: &[Directive], new: &[Directive], changes: &mut Vec<Change>) {
+    for old_directive in old {
+        match new
+            .iter()
+            .find(|directive| directive.name == old_directive.name)
+        {
+            Some(new_directive) => {
+                if old_directive.arguments != new_directive.arguments {
+                    changes.push(Change::new(
+                        ChangeKind::DirectiveChanged,
+                        Criticality::Dangerous,
+                        path,
+                        format!(
+                            "Arguments of directive `@{}` on `{}` were changed",
+                            old_directive.name, path
+                        ),
+                    ));
+                }
+            }
+            None => changes.push(Change::new(
+                ChangeKind::DirectiveRemoved,
+                Criticality::Dangerous,
+                path,
+                format!(
+                    "Directive `@{}` was removed from `{}`",
+                    old_directive.name, path
+                ),
+            )),
+        }
+    }
+
+    for new_directive in new.iter().filter(|directive| {
+        !old.iter()
+            .any(|old_directive| old_directive.name == directive.name)
+    }) {
+        changes.push(Change::new(
+            ChangeKind::DirectiveAdded,
+            Criticality::Dangerous,
+            path,
+            format!(
+                "Directive `@{}` was added to `{}`",
+                new_directive.name, path
+            ),
+        ));
+    }
+}
+
+fn is_required_input(input: &InputFieldDefinition) -> bool {
+    !input.of_type.is_nullable() && input.default_value.is_none()
+}
+
+/// Checks if a value of type `new` can always be read by a client that
+/// expects `old`. The same check with swapped arguments is used for inputs.
+fn is_safe_output_change(old: &Type, new: &Type) -> bool {
+    if old.is_nullable() && !new.is_nullable() {
+        return is_safe_output_change(old, &new.clone().into_nullable());
+    }
+
+    if old.is_nullable() != new.is_nullable() {
+        return false;
+    }
+
+    match (old, new) {
+        (Type::Named { name: old, .. }, Type::Named { name: new, .. }) => old == new,
+        (Type::List { of_type: old, .. }, Type::List { of_type: new, .. }) => {
+            is_safe_output_change(old, new)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tailcall_valid::Validator;
+
+    use super::*;
+    use crate::core::config::{Config, ConfigModule};
+
+    fn blueprint(sdl: &str) -> Blueprint {
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+        Blueprint::try_from(&ConfigModule::from(config)).unwrap()
+    }
+
+    fn diff(old: &str, new: &str) -> SchemaDiff {
+        SchemaDiff::new(&blueprint(old), &blueprint(new))
+    }
+
+    const BASE: &str = r#"
+        schema { query: Query }
+        enum Role { ADMIN USER }
+        type User { id: Int! name: String role: Role }
+        type Query {
+            user(id: Int!): User @http(url: "http://jsonplaceholder.typicode.com/users/{{.args.id}}")
+        }
+    "#;
+
+    #[test]
+    fn test_no_changes() {
+        let actual = diff(BASE, BASE);
+        assert!(actual.is_empty());
+    }
+
+    #[test]
+    fn test_field_removed_is_breaking() {
+        let new = BASE.replace("name: String ", "");
+        let actual = diff(BASE, &new);
+
+        assert!(actual.has_breaking_changes());
+        assert_eq!(actual.changes[0].kind, ChangeKind::FieldRemoved);
+        assert_eq!(actual.changes[0].path, "User.name");
+    }
+
+    #[test]
+    fn test_output_nullability() {
+        let tightened = BASE.replace("name: String ", "name: String! ");
+        let actual = diff(BASE, &tightened);
+        assert!(!actual.has_breaking_changes());
+        assert_eq!(actual.changes[0].kind, ChangeKind::FieldTypeChanged);
+
+        let actual = diff(&tightened, BASE);
+        assert!(actual.has_breaking_changes());
+    }
+
+    #[test]
+    fn test_argument_added() {
+        let required = BASE.replace("user(id: Int!)", "user(id: Int!, active: Boolean!)");
+        let actual = diff(BASE, &required);
+        assert!(actual.has_breaking_changes());
+        assert_eq!(actual.changes[0].kind, ChangeKind::ArgumentAdded);
+
+        let optional = BASE.replace("user(id: Int!)", "user(id: Int!, active: Boolean)");
+        let actual = diff(BASE, &optional);
+        assert!(!actual.has_breaking_changes());
+    }
+
+    #[test]
+    fn test_enum_value_changes() {
+        let removed = BASE.replace("ADMIN USER", "ADMIN");
+        let actual = diff(BASE, &removed);
+        assert!(actual.has_breaking_changes());
+        assert_eq!(actual.changes[0].kind, ChangeKind::EnumValueRemoved);
+
+        let actual = diff(&removed, BASE);
+        assert!(!actual.has_breaking_changes());
+        assert_eq!(actual.changes[0].criticality, Criticality::Dangerous);
+    }
+
+    #[test]
+    fn test_type_changes() {
+        let new = BASE.replace("id: Int! name", "id: String! name");
+        let actual = diff(BASE, &new);
+        assert!(actual.has_breaking_changes());
+        assert_eq!(actual.changes[0].kind, ChangeKind::FieldTypeChanged);
+    }
+}
@@ -43,6 +43,18 @@ pub enum BlueprintError {
     #[error("Invalid method format: {0}. Expected format is <package>.<service>.<method>")]
     InvalidGrpcMethodFormat(String),
 
+    #[error("Client-streaming method {0} can only be called with the GRPC protocol")]
+    GrpcClientStreamingProtocol(String),
+
+    #[error("Server-streaming method {0} can only be called with the GRPC or GRPC_WEB protocols")]
+    GrpcServerStreamingProtocol(String),
+
+    #[error("batchKey isn't supported for the streaming method {0}")]
+    GrpcStreamingBatchKey(String),
+
+    #[error("Bidirectional streaming method {0} isn't supported")]
+    GrpcBidiStreaming(String),
+
     #[error("Protobuf files were not specified in the config")]
     ProtobufFilesNotSpecifiedInConfig,
 
@@ -154,6 +166,24 @@ pub enum BlueprintError {
     #[error("Key is required for HTTP2")]
     KeyIsRequiredForHTTP2,
 
+    #[error("Certificate is required for HTTP3")]
+    CertificateIsRequiredForHTTP3,
+
+    #[error("Key is required for HTTP3")]
+    KeyIsRequiredForHTTP3,
+
+    #[error("Invalid listener address '{0}', expected '<hostname>:<port>' or 'unix:<path>'")]
+    InvalidListenerAddress(String),
+
+    #[error("Unix domain sockets only support HTTP1")]
+    UnixSocketRequiresHTTP1,
+
+    #[error("The admin token can't be empty")]
+    AdminTokenIsEmpty,
+
+    #[error("'{0}' is not a custom scalar")]
+    NotACustomScalar(String),
+
     #[error("Experimental headers must start with 'x-' or 'X-'. Got: '{0}'")]
     ExperimentalHeaderInvalidFormat(String),
 
@@ -94,8 +94,10 @@ fn to_type(def: &Definition) -> dynamic::Type {
                                         let ctx: ResolverContext = ctx.into();
                                         let ctx = &mut EvalContext::new(req_ctx, &ctx);
 
-                                        let value =
-                                            expr.eval(ctx).await.map_err(|err| err.extend())?;
+                                        let value = expr
+                                            .eval(ctx)
+                                            .await
+                                            .map_err(|err| err.extend().into_async_graphql())?;
 
                                         if let ConstValue::Null = value {
                                             Ok(FieldValue::NONE)
@@ -30,9 +30,10 @@ impl TryFrom<Vec<Link>> for Links {
                 .trace(&pos.to_string())
         })
         .and_then(|links| {
+            // scripts with an id can be imported as modules
             let script_links = links
                 .iter()
-                .filter(|l| l.type_of == LinkType::Script)
+                .filter(|l| l.type_of == LinkType::Script && l.id.is_none())
                 .collect::<Vec<&Link>>();
 
             if script_links.len() > 1 {
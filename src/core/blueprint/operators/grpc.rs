@@ -7,7 +7,7 @@ use tailcall_valid::{Valid, ValidationError, Validator};
 use super::apply_select;
 use crate::core::blueprint::BlueprintError;
 use crate::core::config::group_by::GroupBy;
-use crate::core::config::{Config, ConfigModule, Field, GraphQLOperationType, Grpc};
+use crate::core::config::{Config, ConfigModule, Field, GraphQLOperationType, Grpc, GrpcProtocol};
 use crate::core::grpc::protobuf::{ProtobufOperation, ProtobufSet};
 use crate::core::grpc::request_template::RequestTemplate;
 use crate::core::helpers;
@@ -84,6 +84,12 @@ fn validate_schema(
             // TODO: add validation for input schema - should compare result grpc.body to
             // schema
             let super_type = field_schema.field;
+            // the stream of messages of server-streaming methods is resolved as a list
+            let sub_type = if operation.method.is_server_streaming() {
+                JsonSchema::Arr(Box::new(sub_type))
+            } else {
+                sub_type
+            };
             // TODO: all of the fields in protobuf are optional actually
             // and if we want to mark some fields as required in GraphQL
             // JsonSchema won't match and the validation will fail
@@ -139,6 +145,27 @@ fn validate_group_by(
         })
 }
 
+fn validate_streaming(grpc: &Grpc, operation: &ProtobufOperation) -> Valid<(), BlueprintError> {
+    let method = &operation.method;
+    let name = method.full_name().to_string();
+    let is_streaming = method.is_client_streaming() || method.is_server_streaming();
+
+    // the messages of both streams can't be exchanged as a single list
+    if method.is_client_streaming() && method.is_server_streaming() {
+        Valid::fail(BlueprintError::GrpcBidiStreaming(name))
+    } else if method.is_client_streaming() && grpc.protocol != GrpcProtocol::Grpc {
+        Valid::fail(BlueprintError::GrpcClientStreamingProtocol(name))
+    } else if method.is_server_streaming()
+        && !matches!(grpc.protocol, GrpcProtocol::Grpc | GrpcProtocol::GrpcWeb)
+    {
+        Valid::fail(BlueprintError::GrpcServerStreamingProtocol(name))
+    } else if is_streaming && !grpc.batch_key.is_empty() {
+        Valid::fail(BlueprintError::GrpcStreamingBatchKey(name))
+    } else {
+        Valid::succeed(())
+    }
+}
+
 pub struct CompileGrpc<'a> {
     pub config_module: &'a ConfigModule,
     pub operation_type: &'a GraphQLOperationType,
@@ -206,16 +233,19 @@ pub fn compile_grpc(inputs: CompileGrpc) -> Valid<IR, BlueprintError> {
             }
         })
         .and_then(|(operation, url, headers, body)| {
-            let validation = if validate_with_schema {
-                let field_schema = json_schema_from_field(config_module, field);
-                if grpc.batch_key.is_empty() {
-                    validate_schema(field_schema, &operation, field.type_of.name()).unit()
+            let validation = validate_streaming(grpc, &operation).and_then(|_| {
+                if validate_with_schema {
+                    let field_schema = json_schema_from_field(config_module, field);
+                    if grpc.batch_key.is_empty() {
+                        validate_schema(field_schema, &operation, field.type_of.name()).unit()
+                    } else {
+                        validate_group_by(&field_schema, &operation, grpc.batch_key.clone()).unit()
+                    }
                 } else {
-                    validate_group_by(&field_schema, &operation, grpc.batch_key.clone()).unit()
+                    Valid::succeed(())
                 }
-            } else {
-                Valid::succeed(())
-            };
+            });
+            let operation = operation.with_max_stream_items(grpc.get_max_stream_items());
             validation.map(|_| (url, headers, operation, body))
         })
         .map(|(url, headers, operation, body)| {
@@ -225,6 +255,7 @@ pub fn compile_grpc(inputs: CompileGrpc) -> Valid<IR, BlueprintError> {
                 operation,
                 body,
                 operation_type: operation_type.clone(),
+                protocol: grpc.protocol,
             };
             let on_response = grpc.on_response_body.clone();
             let hook = WorkerHooks::try_new(None, on_response).ok();
@@ -250,10 +281,25 @@ pub fn compile_grpc(inputs: CompileGrpc) -> Valid<IR, BlueprintError> {
 mod tests {
     use std::convert::TryFrom;
 
-    use tailcall_valid::ValidationError;
+    use tailcall_fixtures::protobuf;
+    use tailcall_valid::{Valid, ValidationError};
 
-    use super::GrpcMethod;
+    use super::{validate_streaming, GrpcMethod};
     use crate::core::blueprint::BlueprintError;
+    use crate::core::config::{Grpc, GrpcProtocol};
+    use crate::core::grpc::protobuf::{ProtobufOperation, ProtobufSet};
+
+    fn streaming_operation(name: &str) -> ProtobufOperation {
+        let file = ProtobufSet::from_proto_file(
+            protox::compile([protobuf::STREAMING], [protobuf::SELF]).unwrap(),
+        )
+        .unwrap();
+        let method = GrpcMethod::try_from(name).unwrap();
+        file.find_service(&method)
+            .unwrap()
+            .find_operation(&method)
+            .unwrap()
+    }
 
     #[test]
     fn try_from_grpc_method() {
@@ -281,4 +327,44 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn validate_streaming_methods() {
+        let record = streaming_operation("streaming.Geometry.RecordPoints");
+        let list = streaming_operation("streaming.Geometry.ListPoints");
+        let echo = streaming_operation("streaming.Geometry.EchoPoints");
+        let grpc_web = Grpc { protocol: GrpcProtocol::GrpcWeb, ..Default::default() };
+        let connect = Grpc { protocol: GrpcProtocol::Connect, ..Default::default() };
+        let batched = Grpc { batch_key: vec!["id".to_string()], ..Default::default() };
+
+        assert_eq!(
+            validate_streaming(&Grpc::default(), &record),
+            Valid::succeed(())
+        );
+        assert_eq!(validate_streaming(&grpc_web, &list), Valid::succeed(()));
+        assert_eq!(
+            validate_streaming(&grpc_web, &record),
+            Valid::fail(BlueprintError::GrpcClientStreamingProtocol(
+                "streaming.Geometry.RecordPoints".to_string()
+            ))
+        );
+        assert_eq!(
+            validate_streaming(&connect, &list),
+            Valid::fail(BlueprintError::GrpcServerStreamingProtocol(
+                "streaming.Geometry.ListPoints".to_string()
+            ))
+        );
+        assert_eq!(
+            validate_streaming(&Grpc::default(), &echo),
+            Valid::fail(BlueprintError::GrpcBidiStreaming(
+                "streaming.Geometry.EchoPoints".to_string()
+            ))
+        );
+        assert_eq!(
+            validate_streaming(&batched, &list),
+            Valid::fail(BlueprintError::GrpcStreamingBatchKey(
+                "streaming.Geometry.ListPoints".to_string()
+            ))
+        );
+    }
 }
@@ -0,0 +1,37 @@
+use tailcall_valid::Valid;
+
+use crate::core::blueprint::{BlueprintError, FieldDefinition};
+use crate::core::config::{self, ConfigModule, Field};
+use crate::core::ir::model::IR;
+use crate::core::try_fold::TryFold;
+use crate::core::Mustache;
+
+/// Purges the tags listed in `@invalidate` after the resolver of the field
+/// succeeds
+pub fn update_invalidate<'a>() -> TryFold<
+    'a,
+    (&'a ConfigModule, &'a Field, &'a config::Type, &'a str),
+    FieldDefinition,
+    BlueprintError,
+> {
+    TryFold::<(&ConfigModule, &Field, &config::Type, &str), FieldDefinition, BlueprintError>::new(
+        |(_, field, _, name), mut b_field| {
+            let Some(invalidate) = field.invalidate.as_ref() else {
+                return Valid::succeed(b_field);
+            };
+
+            if b_field.resolver.is_none() {
+                return Valid::fail(BlueprintError::FieldHasNoResolver(name.to_string()));
+            }
+
+            let tags = invalidate
+                .tags
+                .iter()
+                .map(|tag| Mustache::parse(tag))
+                .collect::<Vec<_>>();
+            b_field.map_expr(|expr| IR::Invalidate(tags, Box::new(expr)));
+
+            Valid::succeed(b_field)
+        },
+    )
+}
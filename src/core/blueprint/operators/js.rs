@@ -1,16 +1,22 @@
 use tailcall_valid::{Valid, Validator};
 
 use crate::core::blueprint::BlueprintError;
-use crate::core::config::JS;
+use crate::core::config::{Content, PluginLink, JS};
 use crate::core::ir::model::{IO, IR};
 
 pub struct CompileJs<'a> {
     pub js: &'a JS,
     pub script: &'a Option<String>,
+    pub plugins: &'a Vec<Content<PluginLink>>,
 }
 
 pub fn compile_js(inputs: CompileJs) -> Valid<IR, BlueprintError> {
     let name = &inputs.js.name;
-    Valid::from_option(inputs.script.as_ref(), BlueprintError::ScriptIsRequired)
-        .map(|_| IR::IO(IO::Js { name: name.to_string() }))
+    // the function can be exported by the script or by any of the plugins
+    let has_functions = inputs.script.is_some() || !inputs.plugins.is_empty();
+    Valid::from_option(
+        has_functions.then_some(()),
+        BlueprintError::ScriptIsRequired,
+    )
+    .map(|_| IR::IO(IO::Js { name: name.to_string() }))
 }
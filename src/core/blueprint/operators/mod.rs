@@ -5,6 +5,7 @@ mod expr;
 mod graphql;
 mod grpc;
 mod http;
+mod invalidate;
 mod js;
 mod modify;
 mod protected;
@@ -18,6 +19,7 @@ pub use expr::*;
 pub use graphql::*;
 pub use grpc::*;
 pub use http::*;
+pub use invalidate::*;
 pub use js::*;
 pub use modify::*;
 pub use protected::*;
@@ -38,10 +38,12 @@ pub fn compile_resolver(
         }
         Resolver::Call(call) => compile_call(config_module, call, operation_type, object_name)
             .trace(config::Call::trace_name().as_str()),
-        Resolver::Js(js) => {
-            compile_js(super::CompileJs { js, script: &config_module.extensions().script })
-                .trace(config::JS::trace_name().as_str())
-        }
+        Resolver::Js(js) => compile_js(super::CompileJs {
+            js,
+            script: &config_module.extensions().script,
+            plugins: &config_module.extensions().plugins,
+        })
+        .trace(config::JS::trace_name().as_str()),
         Resolver::Expr(expr) => {
             compile_expr(super::CompileExpr { config_module, field, expr, validate: true })
                 .trace(config::Expr::trace_name().as_str())
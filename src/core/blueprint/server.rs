@@ -1,8 +1,11 @@
-use std::collections::{BTreeMap, BTreeSet, HashSet};
-use std::net::{AddrParseError, IpAddr};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Display;
+use std::net::{AddrParseError, IpAddr, SocketAddr};
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
 
+use async_graphql_value::ConstValue;
 use derive_setters::Setters;
 use http::header::{HeaderMap, HeaderName, HeaderValue};
 use rustls_pki_types::CertificateDer;
@@ -10,7 +13,10 @@ use tailcall_valid::{Valid, ValidationError, Validator};
 
 use super::BlueprintError;
 use crate::core::blueprint::Cors;
-use crate::core::config::{self, ConfigModule, HttpVersion, PrivateKey, Routes};
+use crate::core::config::{
+    self, Admin, Compression, ConfigModule, HttpVersion, Limits, ListenerRoute, PrivateKey,
+    ResponseCache, Routes, RuntimeConfig,
+};
 
 #[derive(Clone, Debug, Setters)]
 pub struct Server {
@@ -22,7 +28,9 @@ pub struct Server {
     pub enable_response_validation: bool,
     pub enable_batch_requests: bool,
     pub enable_showcase: bool,
+    pub enable_explain: bool,
     pub global_response_timeout: i64,
+    pub drain_timeout: Duration,
     pub worker: usize,
     pub port: u16,
     pub hostname: IpAddr,
@@ -31,16 +39,54 @@ pub struct Server {
     pub http: Http,
     pub pipeline_flush: bool,
     pub script: Option<Script>,
+    pub plugins: Vec<Plugin>,
     pub cors: Option<Cors>,
     pub experimental_headers: HashSet<HeaderName>,
     pub routes: Routes,
+    pub admin: Option<Admin>,
+    pub response_cache: Option<ResponseCache>,
+    pub compression: Option<Compression>,
+    pub limits: Limits,
+    /// Request headers used in the keys of `@cache` and of the response cache,
+    /// they are not forwarded to the upstream unless allowed.
+    pub cache_vary_headers: BTreeSet<String>,
+    /// Addresses the server listens on, defaults to `hostname` and `port`
+    /// serving all the routes.
+    pub listeners: Vec<Listener>,
 }
 
 /// Mimic of mini_v8::Script that's wasm compatible
 #[derive(Clone, Debug)]
 pub struct Script {
     pub source: String,
-    pub timeout: Option<Duration>,
+    /// Time a call can run without yielding.
+    pub timeout: Duration,
+    /// Time a call can take, including the time its promises are pending.
+    pub call_timeout: Duration,
+    /// Maximum size of the heap of every JS runtime, in bytes.
+    pub max_memory: Option<usize>,
+    /// Number of JS runtimes the calls are spread over.
+    pub workers: usize,
+    /// Sources of the ES modules the script can import, keyed by name.
+    pub modules: BTreeMap<String, String>,
+    /// Allow-listed settings exposed to the script as `tailcall.config`.
+    pub config: ConstValue,
+    /// Environment variables exposed to the script as `tailcall.env`.
+    pub env: BTreeSet<String>,
+}
+
+/// WebAssembly module whose exported functions are called like the functions
+/// of the script.
+#[derive(Clone, Debug)]
+pub struct Plugin {
+    pub id: Option<String>,
+    pub path: String,
+    /// Fuel available to a single call.
+    pub fuel: u64,
+    /// Maximum size of the linear memory, in bytes.
+    pub max_memory: usize,
+    /// Functions validating custom scalars, keyed by the name of the scalar.
+    pub scalars: BTreeMap<String, String>,
 }
 
 #[derive(Clone, Debug)]
@@ -50,6 +96,38 @@ pub enum Http {
         cert: Vec<CertificateDer<'static>>,
         key: PrivateKey,
     },
+    HTTP3 {
+        cert: Vec<CertificateDer<'static>>,
+        key: PrivateKey,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct Listener {
+    pub address: ListenerAddress,
+    pub http: Http,
+    pub routes: BTreeSet<ListenerRoute>,
+}
+
+impl Listener {
+    pub fn serves(&self, route: ListenerRoute) -> bool {
+        self.routes.contains(&route)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ListenerAddress {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl Display for ListenerAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListenerAddress::Tcp(addr) => write!(f, "{}", addr),
+            ListenerAddress::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
 }
 
 impl Default for Server {
@@ -78,6 +156,52 @@ impl Server {
     pub fn get_experimental_headers(&self) -> HashSet<HeaderName> {
         self.experimental_headers.clone()
     }
+
+    /// Plugin functions validating the custom scalars, keyed by the name of
+    /// the scalar. The functions of a plugin linked with an `id` are
+    /// referenced as `<id>.<function>` so that they're found in that plugin.
+    pub fn scalar_validators(&self) -> HashMap<&str, String> {
+        self.plugins
+            .iter()
+            .flat_map(|plugin| {
+                plugin.scalars.iter().map(|(scalar, function)| {
+                    let function = match &plugin.id {
+                        Some(id) => format!("{}.{}", id, function),
+                        None => function.clone(),
+                    };
+                    (scalar.as_str(), function)
+                })
+            })
+            .collect()
+    }
+
+    /// Value of the `Alt-Svc` header of the responses sent by the listener.
+    /// It advertises the HTTP/3 listeners serving at least the routes of the
+    /// listener, so the clients switching over keep reaching the same routes.
+    /// Unix domain sockets have no origin to advertise an alternative for.
+    pub fn alt_svc(&self, listener: &Listener) -> Option<HeaderValue> {
+        if !matches!(listener.address, ListenerAddress::Tcp(_)) {
+            return None;
+        }
+
+        let services = self
+            .listeners
+            .iter()
+            .filter(|http3| http3.routes.is_superset(&listener.routes))
+            .filter_map(|http3| match (&http3.http, &http3.address) {
+                (Http::HTTP3 { .. }, ListenerAddress::Tcp(addr)) => {
+                    Some(format!("h3=\":{}\"; ma=86400", addr.port()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        if services.is_empty() {
+            None
+        } else {
+            HeaderValue::from_str(&services.join(", ")).ok()
+        }
+    }
 }
 
 impl TryFrom<crate::core::config::ConfigModule> for Server {
@@ -86,25 +210,7 @@ impl TryFrom<crate::core::config::ConfigModule> for Server {
     fn try_from(config_module: config::ConfigModule) -> Result<Self, Self::Error> {
         let config_server = config_module.server.clone();
 
-        let http_server = match config_server.clone().get_version() {
-            HttpVersion::HTTP2 => {
-                if config_module.extensions().cert.is_empty() {
-                    return Valid::fail(BlueprintError::CertificateIsRequiredForHTTP2).to_result();
-                }
-
-                let cert = config_module.extensions().cert.clone();
-
-                let key = config_module
-                    .extensions()
-                    .keys
-                    .first()
-                    .ok_or_else(|| ValidationError::new(BlueprintError::KeyIsRequiredForHTTP2))?
-                    .clone();
-
-                Valid::succeed(Http::HTTP2 { cert, key })
-            }
-            _ => Valid::succeed(Http::HTTP1),
-        };
+        let http_server = to_http(config_server.clone().get_version(), &config_module);
 
         validate_hostname((config_server).get_hostname().to_lowercase())
             .fuse(http_server)
@@ -112,6 +218,7 @@ impl TryFrom<crate::core::config::ConfigModule> for Server {
                 (config_server).get_response_headers(),
             ))
             .fuse(to_script(&config_module))
+            .fuse(to_plugins(&config_module))
             .fuse(handle_experimental_headers(
                 (config_server).get_experimental_headers(),
             ))
@@ -121,8 +228,18 @@ impl TryFrom<crate::core::config::ConfigModule> for Server {
                     .as_ref()
                     .and_then(|headers| headers.get_cors()),
             ))
+            .fuse(validate_admin(config_server.admin.clone()))
             .map(
-                |(hostname, http, response_headers, script, experimental_headers, cors)| Server {
+                |(
+                    hostname,
+                    http,
+                    response_headers,
+                    script,
+                    plugins,
+                    experimental_headers,
+                    cors,
+                    admin,
+                )| Server {
                     enable_apollo_tracing: (config_server).enable_apollo_tracing(),
                     enable_cache_control_header: (config_server).enable_cache_control(),
                     enable_set_cookie_header: (config_server).enable_set_cookies(),
@@ -131,8 +248,10 @@ impl TryFrom<crate::core::config::ConfigModule> for Server {
                     enable_response_validation: (config_server).enable_http_validation(),
                     enable_batch_requests: (config_server).enable_batch_requests(),
                     enable_showcase: (config_server).enable_showcase(),
+                    enable_explain: (config_server).enable_explain(),
                     experimental_headers,
                     global_response_timeout: (config_server).get_global_response_timeout(),
+                    drain_timeout: Duration::from_secs(config_server.get_drain_timeout()),
                     http,
                     worker: (config_server).get_workers(),
                     port: (config_server).get_port(),
@@ -141,33 +260,221 @@ impl TryFrom<crate::core::config::ConfigModule> for Server {
                     pipeline_flush: (config_server).get_pipeline_flush(),
                     response_headers,
                     script,
+                    plugins,
                     cors,
                     routes: config_server.get_routes(),
+                    admin,
+                    response_cache: config_server.response_cache.clone(),
+                    compression: config_server.compression.clone(),
+                    limits: config_server.limits.clone().unwrap_or_default(),
+                    cache_vary_headers: cache_vary_headers(&config_module),
+                    listeners: vec![],
                 },
             )
+            .and_then(|server| {
+                to_listeners(&config_module, &server).map(|listeners| server.listeners(listeners))
+            })
             .to_result()
     }
 }
 
+fn to_http(version: HttpVersion, config_module: &ConfigModule) -> Valid<Http, BlueprintError> {
+    let cert = config_module.extensions().cert.clone();
+    let key = config_module.extensions().keys.first().cloned();
+
+    match version {
+        HttpVersion::HTTP1 => Valid::succeed(Http::HTTP1),
+        HttpVersion::HTTP2 => match key {
+            _ if cert.is_empty() => Valid::fail(BlueprintError::CertificateIsRequiredForHTTP2),
+            None => Valid::fail(BlueprintError::KeyIsRequiredForHTTP2),
+            Some(key) => Valid::succeed(Http::HTTP2 { cert, key }),
+        },
+        HttpVersion::HTTP3 => match key {
+            _ if cert.is_empty() => Valid::fail(BlueprintError::CertificateIsRequiredForHTTP3),
+            None => Valid::fail(BlueprintError::KeyIsRequiredForHTTP3),
+            Some(key) => Valid::succeed(Http::HTTP3 { cert, key }),
+        },
+    }
+}
+
+fn to_listeners(
+    config_module: &ConfigModule,
+    server: &Server,
+) -> Valid<Vec<Listener>, BlueprintError> {
+    let listeners = &config_module.server.listeners;
+    if listeners.is_empty() {
+        return Valid::succeed(vec![Listener {
+            address: ListenerAddress::Tcp((server.hostname, server.port).into()),
+            http: server.http.clone(),
+            routes: ListenerRoute::ALL.into(),
+        }]);
+    }
+
+    Valid::from_iter(listeners.iter(), |listener| {
+        let routes = if listener.routes().is_empty() {
+            ListenerRoute::ALL.into()
+        } else {
+            listener.routes().iter().copied().collect()
+        };
+        let version = listener.version().clone().unwrap_or_default();
+
+        to_listener_address(listener.address())
+            .zip(to_http(version, config_module))
+            .and_then(|(address, http)| match (&address, &http) {
+                (ListenerAddress::Unix(_), Http::HTTP2 { .. } | Http::HTTP3 { .. }) => {
+                    Valid::fail(BlueprintError::UnixSocketRequiresHTTP1)
+                }
+                _ => Valid::succeed(Listener { address, http, routes }),
+            })
+            .trace(listener.address())
+    })
+    .trace("listeners")
+    .trace("@server")
+    .trace("schema")
+}
+
+fn to_listener_address(address: &str) -> Valid<ListenerAddress, BlueprintError> {
+    if let Some(path) = address.strip_prefix("unix:") {
+        return Valid::succeed(ListenerAddress::Unix(PathBuf::from(path)));
+    }
+
+    let socket_addr = match address.strip_prefix("localhost:") {
+        Some(port) => format!("127.0.0.1:{}", port).parse(),
+        None => address.parse(),
+    };
+
+    match socket_addr {
+        Ok(addr) => Valid::succeed(ListenerAddress::Tcp(addr)),
+        Err(_) => Valid::fail(BlueprintError::InvalidListenerAddress(address.to_string())),
+    }
+}
+
+fn cache_vary_headers(config_module: &ConfigModule) -> BTreeSet<String> {
+    let caches = config_module.types.values().flat_map(|ty| {
+        ty.cache
+            .iter()
+            .chain(ty.fields.values().filter_map(|field| field.cache.as_ref()))
+    });
+
+    let mut headers = BTreeSet::new();
+    if let Some(response_cache) = config_module.server.response_cache.as_ref() {
+        headers.extend(
+            response_cache
+                .headers()
+                .iter()
+                .map(|name| name.to_lowercase()),
+        );
+    }
+    for cache in caches {
+        headers.extend(cache.vary_headers.iter().map(|name| name.to_lowercase()));
+        if cache.get_scope() == config::CacheScope::Private {
+            headers.insert(http::header::AUTHORIZATION.to_string());
+        }
+    }
+
+    headers
+}
+
 fn to_script(
     config_module: &crate::core::config::ConfigModule,
 ) -> Valid<Option<Script>, BlueprintError> {
+    let options = config_module.server.script.clone().unwrap_or_default();
     config_module.extensions().script.as_ref().map_or_else(
         || Valid::succeed(None),
         |script| {
             Valid::succeed(Some(Script {
                 source: script.clone(),
-                timeout: config_module
-                    .server
-                    .script
-                    .clone()
-                    .map_or_else(|| None, |script| script.timeout)
-                    .map(Duration::from_millis),
+                modules: config_module.extensions().script_modules.clone(),
+                timeout: Duration::from_millis(options.get_timeout()),
+                call_timeout: Duration::from_millis(options.get_call_timeout()),
+                max_memory: options.max_memory,
+                workers: options.get_workers(),
+                config: to_script_config(config_module),
+                env: options.env.iter().cloned().collect(),
             }))
         },
     )
 }
 
+/// Settings the scripts can read from `tailcall.config`. They are
+/// allow-listed as the config also holds secrets, e.g. the admin token, the
+/// variables and the telemetry credentials.
+const SCRIPT_CONFIG: [(&str, &[&str]); 2] = [
+    (
+        "server",
+        &[
+            "batchRequests",
+            "globalResponseTimeout",
+            "hostname",
+            "introspection",
+            "port",
+            "queryValidation",
+            "responseValidation",
+            "showcase",
+            "version",
+            "workers",
+        ],
+    ),
+    (
+        "upstream",
+        &[
+            "allowedHeaders",
+            "batch",
+            "connectTimeout",
+            "http2Only",
+            "httpCache",
+            "timeout",
+            "userAgent",
+        ],
+    ),
+];
+
+fn to_script_config(config_module: &ConfigModule) -> ConstValue {
+    let config = RuntimeConfig::from(config_module.config());
+    let Ok(serde_json::Value::Object(mut config)) = serde_json::to_value(config) else {
+        return ConstValue::Null;
+    };
+
+    let config = SCRIPT_CONFIG
+        .iter()
+        .map(|(section, keys)| {
+            let mut settings = match config.remove(*section) {
+                Some(serde_json::Value::Object(settings)) => settings,
+                _ => serde_json::Map::new(),
+            };
+            settings.retain(|key, _| keys.contains(&key.as_str()));
+            (section.to_string(), serde_json::Value::Object(settings))
+        })
+        .collect();
+
+    ConstValue::from_json(serde_json::Value::Object(config)).unwrap_or_default()
+}
+
+fn to_plugins(config_module: &ConfigModule) -> Valid<Vec<Plugin>, BlueprintError> {
+    let options = config_module.server.plugin.clone().unwrap_or_default();
+    Valid::from_iter(config_module.extensions().plugins.iter(), |plugin| {
+        Valid::from_iter(plugin.scalars.keys(), |name| {
+            let is_custom_scalar = config_module.find_type(name).is_some_and(|ty| ty.scalar());
+            if is_custom_scalar {
+                Valid::succeed(())
+            } else {
+                Valid::fail(BlueprintError::NotACustomScalar(name.clone()))
+            }
+        })
+        .trace("scalars")
+        .trace(&plugin.path)
+        .map(|_| Plugin {
+            id: plugin.id.clone(),
+            path: plugin.path.clone(),
+            fuel: options.get_fuel(),
+            max_memory: options.get_max_memory(),
+            scalars: plugin.scalars.clone(),
+        })
+    })
+    .trace("@link")
+    .trace("schema")
+}
+
 fn validate_cors(cors: Option<config::cors::Cors>) -> Valid<Option<Cors>, BlueprintError> {
     Valid::from(cors.map(|cors| cors.try_into()).transpose())
         .trace("cors")
@@ -191,6 +498,18 @@ fn validate_hostname(hostname: String) -> Valid<IpAddr, BlueprintError> {
     }
 }
 
+/// An empty token would let any request with an empty bearer token in.
+fn validate_admin(admin: Option<Admin>) -> Valid<Option<Admin>, BlueprintError> {
+    match admin {
+        Some(admin) if admin.token().is_empty() => Valid::fail(BlueprintError::AdminTokenIsEmpty)
+            .trace("token")
+            .trace("admin")
+            .trace("@server")
+            .trace("schema"),
+        admin => Valid::succeed(admin),
+    }
+}
+
 fn handle_response_headers(
     resp_headers: Vec<(String, String)>,
 ) -> Valid<HeaderMap, BlueprintError> {
@@ -236,11 +555,127 @@ fn handle_experimental_headers(
 
 #[cfg(test)]
 mod tests {
-    use crate::core::config::ConfigModule;
+    use rustls_pki_types::PrivateKeyDer;
+    use tailcall_valid::Validator;
+
+    use super::{Http, ListenerAddress};
+    use crate::core::config::{Config, ConfigModule, HttpVersion, Listener, ListenerRoute};
 
     #[test]
     fn test_try_from_default() {
         let actual = super::Server::try_from(ConfigModule::default());
         assert!(actual.is_ok())
     }
+
+    #[test]
+    fn test_script_config() {
+        let sdl = r#"
+            schema
+              @server(port: 8080, admin: {token: "secret"}, vars: [{key: "key", value: "secret"}])
+              @upstream(timeout: 10)
+              @telemetry(export: {apollo: {apiKey: "secret", graphRef: "graph@current"}}) {
+              query: Query
+            }
+
+            type Query {
+              foo: String @expr(body: "foo")
+            }
+        "#;
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+
+        let actual = super::to_script_config(&ConfigModule::from(config))
+            .into_json()
+            .unwrap();
+        let expected = serde_json::json!({
+            "server": { "port": 8080 },
+            "upstream": { "timeout": 10 },
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_listeners() {
+        let mut config = Config::default();
+        config.server.listeners = vec![
+            Listener::new("localhost:8000"),
+            Listener::new("unix:/tmp/tailcall.sock").with_routes(vec![ListenerRoute::GraphQL]),
+        ];
+
+        let server = super::Server::try_from(ConfigModule::from(config)).unwrap();
+        let addresses = server
+            .listeners
+            .iter()
+            .map(|listener| listener.address.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(addresses, vec!["127.0.0.1:8000", "unix:/tmp/tailcall.sock"]);
+        assert!(server.listeners[0].serves(ListenerRoute::Metrics));
+        assert!(!server.listeners[1].serves(ListenerRoute::Metrics));
+    }
+
+    #[test]
+    fn test_alt_svc() {
+        let mut server = super::Server::try_from(ConfigModule::default()).unwrap();
+        let listener = |address: &str, http: Http, routes: &[ListenerRoute]| super::Listener {
+            address: ListenerAddress::Tcp(address.parse().unwrap()),
+            http,
+            routes: routes.iter().copied().collect(),
+        };
+        let http3 = || Http::HTTP3 {
+            cert: vec![],
+            key: PrivateKeyDer::Pkcs8(vec![].into()).into(),
+        };
+        server.listeners = vec![
+            listener("127.0.0.1:8000", Http::HTTP1, &[ListenerRoute::GraphQL]),
+            listener("127.0.0.1:8001", Http::HTTP1, &[ListenerRoute::Metrics]),
+            listener("127.0.0.1:8443", http3(), &[ListenerRoute::GraphQL]),
+            listener(
+                "127.0.0.1:9443",
+                http3(),
+                &[ListenerRoute::GraphQL, ListenerRoute::Playground],
+            ),
+        ];
+
+        let alt_svc = |index: usize| server.alt_svc(&server.listeners[index]);
+        let expected = "h3=\":8443\"; ma=86400, h3=\":9443\"; ma=86400";
+        assert_eq!(alt_svc(0).unwrap(), expected);
+        assert_eq!(alt_svc(1), None);
+        assert_eq!(alt_svc(2).unwrap(), expected);
+        assert_eq!(alt_svc(3).unwrap(), "h3=\":9443\"; ma=86400");
+    }
+
+    #[test]
+    fn test_unix_listener_requires_http1() {
+        let mut config = Config::default();
+        config.server.listeners =
+            vec![Listener::new("unix:/tmp/tailcall.sock").with_version(HttpVersion::HTTP2)];
+
+        let actual = super::Server::try_from(ConfigModule::from(config));
+        assert!(actual.is_err())
+    }
+
+    #[test]
+    fn test_admin_requires_token() {
+        let sdl = r#"
+            schema @server(admin: {token: ""}) {
+              query: Query
+            }
+
+            type Query {
+              foo: String @expr(body: "foo")
+            }
+        "#;
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+
+        let actual = super::Server::try_from(ConfigModule::from(config));
+        assert!(actual.is_err())
+    }
+
+    #[test]
+    fn test_http3_requires_certificate() {
+        let mut config = Config::default();
+        config.server.version = Some(HttpVersion::HTTP3);
+
+        let actual = super::Server::try_from(ConfigModule::from(config));
+        assert!(actual.is_err())
+    }
 }
@@ -25,6 +25,7 @@ pub struct Upstream {
     pub user_agent: String,
     pub allowed_headers: BTreeSet<String>,
     pub http_cache: u64,
+    pub max_response_size: usize,
     pub batch: Option<Batch>,
     pub http2_only: bool,
     pub on_request: Option<String>,
@@ -78,6 +79,7 @@ impl TryFrom<&ConfigModule> for Upstream {
                 user_agent: (config_upstream).get_user_agent(),
                 allowed_headers,
                 http_cache: (config_upstream).get_http_cache_size(),
+                max_response_size: (config_upstream).get_max_response_size(),
                 batch,
                 http2_only: (config_upstream).get_http_2_only(),
                 on_request: (config_upstream).get_on_request(),
@@ -1,15 +1,30 @@
+use std::collections::HashSet;
 use std::hash::Hash;
 use std::num::NonZeroU64;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
+use serde::Serialize;
 use ttl_cache::TtlCache;
 
 use super::error::Result;
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// Cached value along with its tags, so that the tags are dropped with the
+/// entry when it's evicted.
+struct Entry<V> {
+    value: V,
+    tags: HashSet<String>,
+}
+
 pub struct InMemoryCache<K: Hash + Eq, V> {
-    data: Arc<RwLock<TtlCache<K, V>>>,
+    data: Arc<RwLock<TtlCache<K, Entry<V>>>>,
     hits: AtomicUsize,
     miss: AtomicUsize,
 }
@@ -31,7 +46,7 @@ impl<K: Hash + Eq, V: Clone> InMemoryCache<K, V> {
 }
 
 #[async_trait::async_trait]
-impl<K: Hash + Eq + Send + Sync, V: Clone + Send + Sync> crate::core::Cache
+impl<K: Hash + Eq + Clone + Send + Sync, V: Clone + Send + Sync> crate::core::Cache
     for InMemoryCache<K, V>
 {
     type Key = K;
@@ -39,12 +54,18 @@ impl<K: Hash + Eq + Send + Sync, V: Clone + Send + Sync> crate::core::Cache
     #[allow(clippy::too_many_arguments)]
     async fn set<'a>(&'a self, key: K, value: V, ttl: NonZeroU64) -> Result<()> {
         let ttl = Duration::from_millis(ttl.get());
-        self.data.write().unwrap().insert(key, value, ttl);
+        let entry = Entry { value, tags: HashSet::new() };
+        self.data.write().unwrap().insert(key, entry, ttl);
         Ok(())
     }
 
     async fn get<'a>(&'a self, key: &'a K) -> Result<Option<Self::Value>> {
-        let val = self.data.read().unwrap().get(key).cloned();
+        let val = self
+            .data
+            .read()
+            .unwrap()
+            .get(key)
+            .map(|entry| entry.value.clone());
         if val.is_some() {
             self.hits.fetch_add(1, Ordering::Relaxed);
         } else {
@@ -66,6 +87,43 @@ impl<K: Hash + Eq + Send + Sync, V: Clone + Send + Sync> crate::core::Cache
 
         None
     }
+
+    fn stats(&self) -> Option<CacheStats> {
+        Some(CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.miss.load(Ordering::Relaxed),
+        })
+    }
+
+    async fn clear<'a>(&'a self) -> Result<()> {
+        self.data.write().unwrap().clear();
+        Ok(())
+    }
+
+    async fn tag<'a>(&'a self, key: &'a K, tags: Vec<String>) -> Result<()> {
+        if let Some(entry) = self.data.write().unwrap().get_mut(key) {
+            entry.tags.extend(tags);
+        }
+        Ok(())
+    }
+
+    async fn purge_tags<'a>(&'a self, tags: &'a [String]) -> Result<usize> {
+        let mut data = self.data.write().unwrap();
+        let keys = data
+            .iter()
+            .filter(|(_, entry)| tags.iter().any(|tag| entry.tags.contains(tag)))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+
+        for key in keys.iter() {
+            data.remove(key);
+        }
+        Ok(keys.len())
+    }
+
+    async fn purge<'a>(&'a self, key: &'a K) -> Result<bool> {
+        Ok(self.data.write().unwrap().remove(key).is_some())
+    }
 }
 
 #[cfg(test)]
@@ -89,4 +147,51 @@ mod tests {
         tokio::time::sleep(Duration::from_millis(ttl.get())).await;
         assert_eq!(cache.get(&10).await.ok(), Some(None));
     }
+
+    #[tokio::test]
+    async fn test_purge_tags() {
+        let cache: crate::core::cache::InMemoryCache<u64, String> =
+            crate::core::cache::InMemoryCache::default();
+        let ttl = NonZeroU64::new(10000).unwrap();
+
+        cache.set(1, "first".into(), ttl).await.unwrap();
+        cache.set(2, "second".into(), ttl).await.unwrap();
+        cache.set(3, "third".into(), ttl).await.unwrap();
+        cache
+            .tag(&1, vec!["user:1".into(), "users".into()])
+            .await
+            .unwrap();
+        cache.tag(&2, vec!["users".into()]).await.unwrap();
+
+        assert_eq!(cache.purge_tags(&["user:1".into()]).await.unwrap(), 1);
+        assert_eq!(cache.get(&1).await.ok(), Some(None));
+        assert_eq!(cache.get(&2).await.ok(), Some(Some("second".into())));
+
+        // already removed entries are not counted twice
+        assert_eq!(cache.purge_tags(&["users".into()]).await.unwrap(), 1);
+        assert_eq!(cache.get(&2).await.ok(), Some(None));
+
+        assert!(cache.purge(&3).await.unwrap());
+        assert!(!cache.purge(&3).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_tags_of_replaced_entries() {
+        let cache: crate::core::cache::InMemoryCache<u64, String> =
+            crate::core::cache::InMemoryCache::default();
+        let ttl = NonZeroU64::new(10000).unwrap();
+
+        cache.set(1, "first".into(), ttl).await.unwrap();
+        cache.tag(&1, vec!["users".into()]).await.unwrap();
+        cache.purge(&1).await.unwrap();
+
+        // the tags of a purged entry don't apply to the entry cached next
+        cache.set(1, "second".into(), ttl).await.unwrap();
+        assert_eq!(cache.purge_tags(&["users".into()]).await.unwrap(), 0);
+        assert_eq!(cache.get(&1).await.ok(), Some(Some("second".into())));
+
+        // tagging an evicted entry is a no-op
+        cache.tag(&2, vec!["users".into()]).await.unwrap();
+        assert_eq!(cache.purge_tags(&["users".into()]).await.unwrap(), 0);
+    }
 }
@@ -16,8 +16,9 @@ use tailcall_valid::{Valid, Validator};
 use super::directive::Directive;
 use super::from_document::from_document;
 use super::{
-    AddField, Alias, Cache, Call, Discriminate, Expr, GraphQL, Grpc, Http, Link, Modify, Omit,
-    Protected, ResolverSet, Server, Telemetry, Upstream, JS,
+    AddField, Admin, Alias, Cache, Call, Discriminate, Expr, GraphQL, Grpc, Http, Invalidate, Link,
+    Lint, Mock, Modify, Omit, Protected, ResolverSet, Server, Telemetry, TelemetryExporter,
+    Upstream, JS,
 };
 use crate::core::config::npo::QueryPath;
 use crate::core::config::source::Source;
@@ -125,6 +126,9 @@ pub struct Type {
     /// Marks field as protected by auth providers
     pub protected: Option<Protected>,
     ///
+    /// Lint rules suppressed for the type
+    pub lint: Option<Lint>,
+    ///
     /// Apollo federation entity resolver.
     pub resolvers: ResolverSet,
     ///
@@ -216,6 +220,18 @@ pub struct Field {
     /// Used to overwrite the default discrimination strategy
     pub discriminate: Option<Discriminate>,
 
+    ///
+    /// Lint rules suppressed for the field
+    pub lint: Option<Lint>,
+
+    ///
+    /// Fixed value returned by the field in mock mode
+    pub mock: Option<Mock>,
+
+    ///
+    /// Cache tags purged after the field is resolved
+    pub invalidate: Option<Invalidate>,
+
     ///
     /// Resolver for the field
     pub resolvers: ResolverSet,
@@ -351,6 +367,17 @@ impl RuntimeConfig {
     }
 }
 
+impl From<&Config> for RuntimeConfig {
+    fn from(config: &Config) -> Self {
+        Self {
+            server: config.server.clone(),
+            upstream: config.upstream.clone(),
+            links: config.links.clone(),
+            telemetry: config.telemetry.clone(),
+        }
+    }
+}
+
 impl Config {
     pub fn with_runtime_config(self, runtime_config: RuntimeConfig) -> Self {
         Self {
@@ -391,6 +418,27 @@ impl Config {
         self.enums.get(name)
     }
 
+    /// Config without the secrets, the admin token and the telemetry
+    /// credentials are replaced with a placeholder.
+    pub fn redacted(mut self) -> Self {
+        const REDACTED: &str = "<redacted>";
+
+        self.server.admin = self
+            .server
+            .admin
+            .map(|admin| Admin::new(REDACTED).with_path(admin.path()));
+        match self.telemetry.export.as_mut() {
+            Some(TelemetryExporter::Apollo(apollo)) => apollo.api_key = REDACTED.to_string(),
+            Some(TelemetryExporter::Otlp(otlp)) => {
+                for header in otlp.headers.iter_mut() {
+                    header.value = REDACTED.to_string();
+                }
+            }
+            _ => {}
+        }
+        self
+    }
+
     /// Renders current config to graphQL string
     pub fn to_sdl(&self) -> String {
         crate::core::document::print(self.into())
@@ -647,6 +695,9 @@ impl Config {
             .add_directive(Omit::directive_definition(generated_types))
             .add_directive(Protected::directive_definition(generated_types))
             .add_directive(Discriminate::directive_definition(generated_types))
+            .add_directive(Lint::directive_definition(generated_types))
+            .add_directive(Mock::directive_definition(generated_types))
+            .add_directive(Invalidate::directive_definition(generated_types))
             .add_input(GraphQL::input_definition())
             .add_input(Grpc::input_definition())
             .add_input(Http::input_definition())
@@ -721,6 +772,27 @@ mod tests {
         assert_eq!(name, "graphQL");
     }
 
+    #[test]
+    fn test_redacted() {
+        let sdl = r#"
+            schema
+              @server(admin: {token: "secret"})
+              @telemetry(export: {otlp: {url: "http://localhost:4317", headers: [{key: "api-key", value: "secret"}]}}) {
+              query: Query
+            }
+
+            type Query {
+              foo: String @expr(body: "foo")
+            }
+        "#;
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+        assert!(config.to_sdl().contains("secret"));
+
+        let redacted = config.redacted();
+        assert_eq!(redacted.server.admin.as_ref().unwrap().path(), "/admin");
+        assert!(!redacted.to_sdl().contains("secret"));
+    }
+
     #[test]
     fn test_from_sdl_empty() {
         let actual = Config::from_sdl("type Foo {a: Int}").to_result().unwrap();
@@ -11,6 +11,7 @@ use crate::core::macros::MergeRight;
 use crate::core::merge_right::MergeRight;
 use crate::core::proto_reader::ProtoMetadata;
 use crate::core::rest::{EndpointSet, Unchecked};
+use crate::core::rpc;
 use crate::core::Transform;
 
 mod merge;
@@ -103,6 +104,15 @@ impl<A> Deref for Content<A> {
     }
 }
 
+/// A WebAssembly plugin linked with `@link(type: Plugin)`.
+#[derive(Clone, Debug, Default)]
+pub struct PluginLink {
+    pub path: String,
+    /// Functions of the plugin validating custom scalars, keyed by the name
+    /// of the scalar. They're read from `meta: {scalars: {..}}` of the link.
+    pub scalars: BTreeMap<String, String>,
+}
+
 #[derive(Debug)]
 pub struct PrivateKey(PrivateKeyDer<'static>);
 
@@ -135,6 +145,13 @@ pub struct Extensions {
     /// Contains the contents of the JS file
     pub script: Option<String>,
 
+    /// Contains the contents of the JS files linked with an id, they can be
+    /// imported as ES modules by that id
+    pub script_modules: BTreeMap<String, String>,
+
+    /// Contains the paths of the WebAssembly plugins
+    pub plugins: Vec<Content<PluginLink>>,
+
     /// Contains the certificate used on HTTP2 with TLS
     pub cert: Vec<CertificateDer<'static>>,
 
@@ -144,9 +161,15 @@ pub struct Extensions {
     /// Contains the endpoints
     pub endpoint_set: EndpointSet<Unchecked>,
 
+    /// Contains the operations exposed as gRPC methods
+    pub rpc_methods: Vec<rpc::Method>,
+
     pub htpasswd: Vec<Content<String>>,
 
     pub jwks: Vec<Content<JwkSet>>,
+
+    /// Contains the mock fixtures keyed by `Type.field`
+    pub mocks: Vec<Content<BTreeMap<String, serde_json::Value>>>,
 }
 
 impl Extensions {
@@ -101,6 +101,9 @@ impl Contravariant for Field {
                 default_value: self.default_value.or(other.default_value),
                 protected: self.protected.merge_right(other.protected),
                 discriminate: self.discriminate.merge_right(other.discriminate),
+                lint: self.lint.merge_right(other.lint),
+                mock: self.mock.merge_right(other.mock),
+                invalidate: self.invalidate.merge_right(other.invalidate),
                 resolvers: self.resolvers.merge_right(other.resolvers),
                 directives: self.directives.merge_right(other.directives),
             })
@@ -123,6 +126,9 @@ impl Covariant for Field {
                 default_value: self.default_value.or(other.default_value),
                 protected: self.protected.merge_right(other.protected),
                 discriminate: self.discriminate.merge_right(other.discriminate),
+                lint: self.lint.merge_right(other.lint),
+                mock: self.mock.merge_right(other.mock),
+                invalidate: self.invalidate.merge_right(other.invalidate),
                 resolvers: self.resolvers.merge_right(other.resolvers),
                 directives: self.directives.merge_right(other.directives),
             })
@@ -139,6 +145,7 @@ impl Contravariant for Type {
             implements: self.implements.merge_right(other.implements),
             cache: self.cache.merge_right(other.cache),
             protected: self.protected.merge_right(other.protected),
+            lint: self.lint.merge_right(other.lint),
             resolvers: self.resolvers.merge_right(other.resolvers),
             directives: self.directives.merge_right(other.directives),
         })
@@ -155,6 +162,7 @@ impl Covariant for Type {
             implements: self.implements.merge_right(other.implements),
             cache: self.cache.merge_right(other.cache),
             protected: self.protected.merge_right(other.protected),
+            lint: self.lint.merge_right(other.lint),
             resolvers: self.resolvers.merge_right(other.resolvers),
             directives: self.directives.merge_right(other.directives),
         })
@@ -24,4 +24,62 @@ pub struct Cache {
     /// Specifies the duration, in milliseconds, of how long the value has to be
     /// stored in the cache.
     pub max_age: NonZeroU64,
+
+    /// Tags attached to the cached value, so it can be purged with
+    /// `@invalidate` or the admin API. Tags are mustache templates rendered
+    /// before the field is resolved, e.g. `user:{{.args.id}}`, where `.value`
+    /// is the parent value, e.g. `posts:{{.value.id}}` on `User.posts`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Duration, in milliseconds, after the expiry during which the stale value
+    /// is still returned while it's refreshed in the background.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale_while_revalidate: Option<NonZeroU64>,
+
+    /// Duration, in milliseconds, after the expiry during which the stale value
+    /// is returned if the upstream fails.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale_if_error: Option<NonZeroU64>,
+
+    /// Mustache template used as the cache key instead of the upstream
+    /// request, e.g. `user:{{.args.id}}`. Useful to ignore parts of the request
+    /// that don't change the response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+
+    /// `PRIVATE` values are cached per user, based on the subject of the
+    /// verified JWT or else on the `Authorization` header, and mark the
+    /// response as private. @default `PUBLIC`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<CacheScope>,
+
+    /// Request headers whose values are part of the cache key.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vary_headers: Vec<String>,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Serialize,
+    schemars::JsonSchema,
+    MergeRight,
+)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CacheScope {
+    #[default]
+    Public,
+    Private,
+}
+
+impl Cache {
+    pub fn get_scope(&self) -> CacheScope {
+        self.scope.unwrap_or_default()
+    }
 }
@@ -5,6 +5,8 @@ use tailcall_macros::{DirectiveDefinition, InputDefinition};
 use crate::core::config::KeyValue;
 use crate::core::is_default;
 
+const DEFAULT_MAX_STREAM_ITEMS: usize = 1000;
+
 #[derive(
     Serialize,
     Deserialize,
@@ -74,4 +76,35 @@ pub struct Grpc {
     /// body before it's sent back to the client.
     #[serde(rename = "onResponseBody", default, skip_serializing_if = "is_default")]
     pub on_response_body: Option<String>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The protocol used to call the method. `GRPC` requires HTTP/2, while
+    /// `GRPC_WEB`, `CONNECT` (binary messages) and `CONNECT_JSON` (JSON
+    /// messages) work over HTTP/1.1 too. @default `GRPC`.
+    pub protocol: GrpcProtocol,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The maximum number of messages of the streams of the method. The list
+    /// argument of a client-streaming method is sent as a stream of messages,
+    /// and the stream of a server-streaming method is collected into a list,
+    /// the call fails when either holds more messages. @default `1000`.
+    pub max_stream_items: Option<usize>,
+}
+
+impl Grpc {
+    pub fn get_max_stream_items(&self) -> usize {
+        self.max_stream_items.unwrap_or(DEFAULT_MAX_STREAM_ITEMS)
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, schemars::JsonSchema,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GrpcProtocol {
+    #[default]
+    Grpc,
+    GrpcWeb,
+    Connect,
+    ConnectJson,
 }
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use tailcall_macros::{DirectiveDefinition, MergeRight};
+
+#[derive(
+    Clone,
+    Debug,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Eq,
+    Default,
+    schemars::JsonSchema,
+    MergeRight,
+    DirectiveDefinition,
+)]
+#[directive_definition(locations = "FieldDefinition")]
+#[serde(deny_unknown_fields)]
+/// The @invalidate directive purges the cached values tagged with any of the
+/// listed tags once the field, usually a mutation, is resolved successfully.
+pub struct Invalidate {
+    /// Tags of the cached values to purge. Tags are mustache templates that
+    /// can refer to the arguments of the field, e.g. `user:{{.args.id}}`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
@@ -27,7 +27,9 @@ pub enum LinkType {
     Protobuf,
 
     /// Points to a JS file. The imported JS file will be used by the `@js`
-    /// directive.
+    /// directive. Scripts linked with an `id` can be imported by that id as
+    /// ES modules. Files with a `.ts` extension are transpiled from
+    /// TypeScript when they are loaded.
     Script,
 
     /// Points to a Cert file. The imported Cert file will be used by the server
@@ -39,7 +41,8 @@ pub enum LinkType {
     Key,
 
     /// A trusted document that contains GraphQL operations (queries, mutations)
-    /// that can be exposed a REST API using the `@rest` directive.
+    /// that can be exposed a REST API using the `@rest` directive, or as gRPC
+    /// methods using the `@rpc` directive.
     Operation,
 
     /// Points to a Htpasswd file. The imported Htpasswd file will be used by
@@ -53,6 +56,15 @@ pub enum LinkType {
     /// Points to a reflection endpoint. The imported reflection endpoint will
     /// be used by the `@grpc` directive to resolve data from gRPC services.
     Grpc,
+
+    /// Points to a JSON file with mock fixtures keyed by `Type.field`. The
+    /// fixtures will be used by the server when it's started with `--mock`.
+    Mock,
+    /// Points to a WebAssembly plugin. Its exported functions can be used
+    /// wherever a script function can, e.g. by `@js` or the `onRequest` hooks.
+    /// Custom scalars are validated by the functions mapped to them in
+    /// `meta: {scalars: {..}}`.
+    Plugin,
 }
 
 /// The @link directive allows you to import external resources, such as
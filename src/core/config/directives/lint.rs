@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use tailcall_macros::{DirectiveDefinition, MergeRight};
+
+#[derive(
+    Clone,
+    Debug,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Eq,
+    Default,
+    schemars::JsonSchema,
+    MergeRight,
+    DirectiveDefinition,
+)]
+#[directive_definition(locations = "Object,FieldDefinition")]
+#[serde(deny_unknown_fields)]
+/// The @lint directive suppresses the listed lint rules reported by `tailcall
+/// check --lint` for the type or field it is applied to.
+pub struct Lint {
+    /// List of lint rule ids that should not be reported.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+}
+
+impl Lint {
+    pub fn allows(&self, rule: &str) -> bool {
+        self.allow.iter().any(|id| id == rule)
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tailcall_macros::DirectiveDefinition;
+
+use crate::core::merge_right::MergeRight;
+
+#[derive(
+    Serialize, Deserialize, Clone, Debug, PartialEq, Eq, schemars::JsonSchema, DirectiveDefinition,
+)]
+#[directive_definition(locations = "FieldDefinition")]
+#[serde(deny_unknown_fields)]
+/// The @mock directive sets a fixed value for the field that is returned
+/// instead of generated data when the server is started with `--mock`.
+pub struct Mock {
+    pub value: Value,
+}
+
+// It's a terminal implementation of MergeRight
+impl MergeRight for Mock {
+    fn merge_right(self, other: Self) -> Self {
+        other
+    }
+}
@@ -8,8 +8,11 @@ mod federation;
 mod graphql;
 mod grpc;
 mod http;
+mod invalidate;
 mod js;
 mod link;
+mod lint;
+mod mock;
 mod modify;
 mod omit;
 mod protected;
@@ -27,8 +30,11 @@ pub use federation::*;
 pub use graphql::*;
 pub use grpc::*;
 pub use http::*;
+pub use invalidate::*;
 pub use js::*;
 pub use link::*;
+pub use lint::*;
+pub use mock::*;
 pub use modify::*;
 pub use omit::*;
 pub use protected::*;
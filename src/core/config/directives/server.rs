@@ -34,6 +34,12 @@ pub struct Server {
     #[schemars(skip)]
     pub enable_jit: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `admin` enables the administration endpoints, e.g. the cache purge,
+    /// the runtime stats or the log level, under the given path. Requests
+    /// must send the configured token as a bearer token.
+    pub admin: Option<Admin>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `apolloTracing` exposes GraphQL query performance data, including
     /// execution time of queries and individual resolvers.
@@ -45,6 +51,16 @@ pub struct Server {
     /// debugging. Use judiciously. @default `false`.
     pub batch_requests: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `compression` compresses the responses with the gzip, brotli or zstd
+    /// encoding accepted by the client, as negotiated with `Accept-Encoding`.
+    pub compression: Option<Compression>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `drainTimeout` is the number of seconds the server waits for in-flight
+    /// requests to complete on `SIGTERM` before exiting. @default `30`.
+    pub drain_timeout: Option<u64>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `headers` contains key-value pairs that are included as default headers
     /// in server responses, allowing for consistent header management across
@@ -71,11 +87,34 @@ pub struct Server {
     #[serde(default, skip_serializing_if = "is_default")]
     pub enable_federation: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `explain` allows clients to get the execution plan of an operation,
+    /// including resolver timings, by sending the `explain: true` request
+    /// extension. It exposes upstream URLs, so keep it disabled in production.
+    /// @default `false`.
+    pub explain: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `limits` caps the size of the incoming requests, the requests over a
+    /// limit are rejected before being parsed.
+    pub limits: Option<Limits>,
+
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `listeners` binds the server to several addresses, including Unix
+    /// domain sockets, each serving a subset of the routes. When set,
+    /// `hostname`, `port` and `version` are ignored.
+    pub listeners: Vec<Listener>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `pipelineFlush` allows to control flushing behavior of the server
     /// pipeline.
     pub pipeline_flush: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `plugin` sets the sandbox limits of the WebAssembly plugins linked with
+    /// `@link(type: Plugin)`.
+    pub plugin: Option<PluginOptions>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `port` sets the Tailcall running port. @default `8000`.
     pub port: Option<u16>,
@@ -86,6 +125,13 @@ pub struct Server {
     /// @default `false`.
     pub query_validation: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// `responseCache` caches the whole response of query operations, keyed by
+    /// the query, the variables and the selected headers. The TTL is the
+    /// smallest of the `@cache` max ages and upstream `Cache-Control` max
+    /// ages, responses without one aren't cached.
+    pub response_cache: Option<ResponseCache>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// `responseValidation` Tailcall automatically validates responses from
     /// upstream services using inferred schema. @default `false`.
@@ -107,8 +153,8 @@ pub struct Server {
     pub vars: Vec<KeyValue>,
 
     #[serde(default, skip_serializing_if = "is_default")]
-    /// `version` sets the HTTP version for the server. Options are `HTTP1` and
-    /// `HTTP2`. @default `HTTP1`.
+    /// `version` sets the HTTP version for the server. Options are `HTTP1`,
+    /// `HTTP2` and `HTTP3`. @default `HTTP1`.
     pub version: Option<HttpVersion>,
 
     #[serde(default, skip_serializing_if = "is_default")]
@@ -158,15 +204,230 @@ impl Routes {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, MergeRight, JsonSchema, Getters)]
+pub struct Admin {
+    /// Path prefix of the administration endpoints. @default `/admin`.
+    #[serde(default = "default_admin_path")]
+    path: String,
+    /// Token expected in the `Authorization: Bearer <token>` header, it can't
+    /// be empty.
+    token: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, MergeRight, JsonSchema, Getters)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseCache {
+    /// Request headers that are part of the cache key.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    headers: Vec<String>,
+    /// Maximum number of cached responses. @default `1000`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_size: Option<usize>,
+}
+
+impl ResponseCache {
+    pub fn get_max_size(&self) -> usize {
+        self.max_size.unwrap_or(1000)
+    }
+}
+
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, MergeRight, JsonSchema, Getters,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Compression {
+    /// Smallest response body, in bytes, that gets compressed. @default
+    /// `1024`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min_size: Option<usize>,
+    /// Routes whose responses are compressed. @default all the routes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    routes: Vec<ListenerRoute>,
+}
+
+impl Compression {
+    pub fn get_min_size(&self) -> usize {
+        self.min_size.unwrap_or(1024)
+    }
+
+    /// Whether the responses of the route are compressed.
+    pub fn is_enabled(&self, route: ListenerRoute) -> bool {
+        self.routes.is_empty() || self.routes.contains(&route)
+    }
+}
+
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, MergeRight, JsonSchema, Getters,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Limits {
+    /// Maximum size of the request body, in bytes. Larger requests are
+    /// rejected with `413 Payload Too Large`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_body_size: Option<usize>,
+    /// Maximum number of operations in a batched request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_batch_length: Option<usize>,
+    /// Maximum size of the request headers, in bytes. Larger requests are
+    /// rejected with `431 Request Header Fields Too Large`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_header_size: Option<usize>,
+    /// Maximum nesting depth of the variables of an operation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_variables_depth: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, MergeRight, JsonSchema, Getters)]
+pub struct Listener {
+    /// Address to listen on, `<hostname>:<port>` or `unix:<path>` for a Unix
+    /// domain socket.
+    address: String,
+    /// HTTP version of the listener, Unix domain sockets only support
+    /// `HTTP1`. @default `HTTP1`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    version: Option<HttpVersion>,
+    /// Routes served by the listener. @default all the routes.
+    #[serde(default, skip_serializing_if = "is_default")]
+    routes: Vec<ListenerRoute>,
+}
+
+impl Listener {
+    pub fn new<T: Into<String>>(address: T) -> Self {
+        Self { address: address.into(), version: None, routes: vec![] }
+    }
+
+    pub fn with_version(self, version: HttpVersion) -> Self {
+        Self { version: Some(version), ..self }
+    }
+
+    pub fn with_routes(self, routes: Vec<ListenerRoute>) -> Self {
+        Self { routes, ..self }
+    }
+}
+
+/// Group of routes that a listener serves.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    MergeRight,
+    JsonSchema,
+)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ListenerRoute {
+    GraphQL,
+    Rest,
+    Metrics,
+    Status,
+    Playground,
+    Admin,
+    Grpc,
+    Showcase,
+}
+
+impl ListenerRoute {
+    pub const ALL: [ListenerRoute; 8] = [
+        ListenerRoute::GraphQL,
+        ListenerRoute::Rest,
+        ListenerRoute::Metrics,
+        ListenerRoute::Status,
+        ListenerRoute::Playground,
+        ListenerRoute::Admin,
+        ListenerRoute::Grpc,
+        ListenerRoute::Showcase,
+    ];
+}
+
+fn default_admin_path() -> String {
+    "/admin".into()
+}
+
+impl Admin {
+    pub fn new<T: Into<String>>(token: T) -> Self {
+        Self { path: default_admin_path(), token: token.into() }
+    }
+
+    pub fn with_path<T: Into<String>>(self, path: T) -> Self {
+        Self { path: path.into(), token: self.token }
+    }
+}
+
 fn merge_right_vars(mut left: Vec<KeyValue>, right: Vec<KeyValue>) -> Vec<KeyValue> {
     left = merge_key_value_vecs(&left, &right);
     left
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, schemars::JsonSchema, MergeRight)]
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, MergeRight, JsonSchema, Getters,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginOptions {
+    /// Fuel available to every plugin call, roughly the number of executed
+    /// WebAssembly instructions. @default `1_000_000_000`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fuel: Option<u64>,
+    /// Maximum size of the linear memory of a plugin, in bytes. @default
+    /// `67108864` (64 MiB).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_memory: Option<usize>,
+}
+
+impl PluginOptions {
+    pub fn get_fuel(&self) -> u64 {
+        self.fuel.unwrap_or(1_000_000_000)
+    }
+
+    pub fn get_max_memory(&self) -> usize {
+        self.max_memory.unwrap_or(64 * 1024 * 1024)
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, schemars::JsonSchema, MergeRight,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct ScriptOptions {
+    /// Time in milliseconds a function can run without yielding, e.g. by
+    /// awaiting a `fetch`, before it's interrupted. @default the
+    /// `callTimeout`.
+    #[serde(default, skip_serializing_if = "is_default")]
     pub timeout: Option<u64>,
+    /// Time in milliseconds a call can take overall, including the time its
+    /// promises are pending. @default `30000`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub call_timeout: Option<u64>,
+    /// Maximum size of the heap of every JS runtime, in bytes.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub max_memory: Option<usize>,
+    /// Number of JS runtimes the calls are spread over, independent of
+    /// `@server(workers)`. @default `1`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub workers: Option<usize>,
+    /// Environment variables the scripts can read from `tailcall.env`, none
+    /// are exposed by default.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+}
+
+impl ScriptOptions {
+    pub fn get_workers(&self) -> usize {
+        self.workers.unwrap_or(1).max(1)
+    }
+
+    pub fn get_call_timeout(&self) -> u64 {
+        self.call_timeout.unwrap_or(30000)
+    }
+
+    pub fn get_timeout(&self) -> u64 {
+        self.timeout.unwrap_or_else(|| self.get_call_timeout())
+    }
 }
 
 #[derive(
@@ -176,6 +437,11 @@ pub enum HttpVersion {
     #[default]
     HTTP1,
     HTTP2,
+    /// Served over QUIC, with an HTTP/2 listener on the same TCP port for the
+    /// clients that don't support it yet.
+    /// The TCP listeners serving the same routes advertise it to their
+    /// clients with the `Alt-Svc` header.
+    HTTP3,
 }
 
 impl Server {
@@ -191,6 +457,10 @@ impl Server {
         self.workers.unwrap_or(num_cpus::get())
     }
 
+    pub fn get_drain_timeout(&self) -> u64 {
+        self.drain_timeout.unwrap_or(30)
+    }
+
     pub fn get_port(&self) -> u16 {
         self.port.unwrap_or(8000)
     }
@@ -221,6 +491,9 @@ impl Server {
     pub fn enable_showcase(&self) -> bool {
         self.showcase.unwrap_or(false)
     }
+    pub fn enable_explain(&self) -> bool {
+        self.explain.unwrap_or(false)
+    }
 
     pub fn get_hostname(&self) -> String {
         self.hostname.clone().unwrap_or("127.0.0.1".to_string())
@@ -282,49 +555,66 @@ mod tests {
 
     #[test]
     fn script_options_merge_both() {
-        let a = server_with_script_options(ScriptOptions { timeout: Some(100) });
-        let b = server_with_script_options(ScriptOptions { timeout: Some(200) });
+        let a =
+            server_with_script_options(ScriptOptions { timeout: Some(100), ..Default::default() });
+        let b =
+            server_with_script_options(ScriptOptions { timeout: Some(200), ..Default::default() });
         let merged = a.merge_right(b);
-        let expected = ScriptOptions { timeout: Some(200) };
+        let expected = ScriptOptions { timeout: Some(200), ..Default::default() };
         assert_eq!(merged.script, Some(expected));
     }
 
     #[test]
     fn script_options_merge_first() {
-        let a = server_with_script_options(ScriptOptions { timeout: Some(100) });
-        let b = server_with_script_options(ScriptOptions { timeout: None });
+        let a =
+            server_with_script_options(ScriptOptions { timeout: Some(100), ..Default::default() });
+        let b = server_with_script_options(ScriptOptions::default());
         let merged = a.merge_right(b);
-        let expected = ScriptOptions { timeout: Some(100) };
+        let expected = ScriptOptions { timeout: Some(100), ..Default::default() };
         assert_eq!(merged.script, Some(expected));
     }
 
     #[test]
     fn script_options_merge_second() {
-        let a = server_with_script_options(ScriptOptions { timeout: None });
-        let b = server_with_script_options(ScriptOptions { timeout: Some(100) });
+        let a = server_with_script_options(ScriptOptions::default());
+        let b =
+            server_with_script_options(ScriptOptions { timeout: Some(100), ..Default::default() });
         let merged = a.merge_right(b);
-        let expected = ScriptOptions { timeout: Some(100) };
+        let expected = ScriptOptions { timeout: Some(100), ..Default::default() };
         assert_eq!(merged.script, Some(expected));
     }
 
     #[test]
     fn script_options_merge_second_default() {
-        let a = server_with_script_options(ScriptOptions { timeout: Some(100) });
+        let a =
+            server_with_script_options(ScriptOptions { timeout: Some(100), ..Default::default() });
         let b = Server::default();
         let merged = a.merge_right(b);
-        let expected = ScriptOptions { timeout: Some(100) };
+        let expected = ScriptOptions { timeout: Some(100), ..Default::default() };
         assert_eq!(merged.script, Some(expected));
     }
 
     #[test]
     fn script_options_merge_first_default() {
         let a = Server::default();
-        let b = server_with_script_options(ScriptOptions { timeout: Some(100) });
+        let b =
+            server_with_script_options(ScriptOptions { timeout: Some(100), ..Default::default() });
         let merged = a.merge_right(b);
-        let expected = ScriptOptions { timeout: Some(100) };
+        let expected = ScriptOptions { timeout: Some(100), ..Default::default() };
         assert_eq!(merged.script, Some(expected));
     }
 
+    #[test]
+    fn script_options_timeout_default() {
+        assert_eq!(ScriptOptions::default().get_timeout(), 30000);
+
+        let options = ScriptOptions { call_timeout: Some(100), ..Default::default() };
+        assert_eq!(options.get_timeout(), 100);
+
+        let options = ScriptOptions { timeout: Some(50), ..Default::default() };
+        assert_eq!(options.get_timeout(), 50);
+    }
+
     fn get_default_left_vec() -> Vec<KeyValue> {
         [
             KeyValue { key: "left".to_string(), value: "From Left".to_string() },
@@ -105,6 +105,11 @@ pub struct Upstream {
     /// sent while the connection is idle.
     pub keep_alive_while_idle: Option<bool>,
 
+    #[serde(default, skip_serializing_if = "is_default")]
+    /// The maximum size in bytes of a response body once it's decompressed,
+    /// the larger responses fail. @default `104857600` (100 MiB).
+    pub max_response_size: Option<usize>,
+
     #[serde(default, skip_serializing_if = "is_default")]
     /// The maximum number of idle connections that will be maintained per host.
     pub pool_max_idle_per_host: Option<usize>,
@@ -179,6 +184,9 @@ impl Upstream {
             .clone()
             .unwrap_or("Tailcall/1.0".to_string())
     }
+    pub fn get_max_response_size(&self) -> usize {
+        self.max_response_size.unwrap_or(100 << 20)
+    }
     pub fn get_http_cache_size(&self) -> u64 {
         self.http_cache.unwrap_or(0)
     }
@@ -14,8 +14,8 @@ use tailcall_valid::{Valid, ValidationError, Validator};
 use super::directive::{to_directive, Directive};
 use super::{Alias, Discriminate, Resolver, RuntimeConfig, Telemetry, FEDERATION_DIRECTIVES};
 use crate::core::config::{
-    self, Cache, Config, Enum, Link, Modify, Omit, Protected, RootSchema, Server, Union, Upstream,
-    Variant,
+    self, Cache, Config, Enum, Invalidate, Link, Lint, Mock, Modify, Omit, Protected, RootSchema,
+    Server, Union, Upstream, Variant,
 };
 use crate::core::directive::DirectiveCodec;
 
@@ -240,8 +240,9 @@ where
         .fuse(Protected::from_directives(directives.iter()))
         .fuse(to_add_fields_from_directives(directives))
         .fuse(to_federation_directives(directives))
+        .fuse(Lint::from_directives(directives.iter()))
         .map(
-            |(resolvers, cache, fields, protected, added_fields, unknown_directives)| {
+            |(resolvers, cache, fields, protected, added_fields, unknown_directives, lint)| {
                 let doc = description.to_owned().map(|pos| pos.node);
                 let implements = implements.iter().map(|pos| pos.node.to_string()).collect();
                 config::Type {
@@ -251,6 +252,7 @@ where
                     implements,
                     cache,
                     protected,
+                    lint,
                     resolvers,
                     directives: unknown_directives,
                 }
@@ -330,7 +332,12 @@ where
         .fuse(Omit::from_directives(directives.iter()))
         .fuse(Modify::from_directives(directives.iter()))
         .fuse(Protected::from_directives(directives.iter()))
-        .fuse(Discriminate::from_directives(directives.iter()))
+        .fuse(
+            Discriminate::from_directives(directives.iter())
+                .zip(Lint::from_directives(directives.iter()))
+                .zip(Mock::from_directives(directives.iter()))
+                .zip(Invalidate::from_directives(directives.iter())),
+        )
         .fuse(default_value)
         .fuse(to_federation_directives(directives))
         .map(
@@ -340,7 +347,7 @@ where
                 omit,
                 modify,
                 protected,
-                discriminate,
+                (((discriminate, lint), mock), invalidate),
                 default_value,
                 directives,
             )| config::Field {
@@ -352,6 +359,9 @@ where
                 cache,
                 protected,
                 discriminate,
+                lint,
+                mock,
+                invalidate,
                 default_value,
                 resolvers,
                 directives,
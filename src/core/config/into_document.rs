@@ -221,6 +221,9 @@ fn field_directives(field: &crate::core::config::Field) -> Vec<Positioned<ConstD
         .chain(field.omit.as_ref().map(|d| pos(d.to_directive())))
         .chain(field.cache.as_ref().map(|d| pos(d.to_directive())))
         .chain(field.protected.as_ref().map(|d| pos(d.to_directive())))
+        .chain(field.lint.as_ref().map(|d| pos(d.to_directive())))
+        .chain(field.mock.as_ref().map(|d| pos(d.to_directive())))
+        .chain(field.invalidate.as_ref().map(|d| pos(d.to_directive())))
         .chain(into_directives(&field.directives))
         .collect()
 }
@@ -242,6 +245,7 @@ fn type_directives(type_def: &crate::core::config::Type) -> Vec<Positioned<Const
                 .as_ref()
                 .map(|protected| pos(protected.to_directive())),
         )
+        .chain(type_def.lint.as_ref().map(|lint| pos(lint.to_directive())))
         .chain(
             type_def
                 .resolvers
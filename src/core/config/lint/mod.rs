@@ -0,0 +1,352 @@
+mod rules;
+mod sarif;
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+pub use rules::*;
+pub use sarif::Sarif;
+use serde::Serialize;
+
+use crate::core::config::Config;
+
+/// Severity with which a lint rule is reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "info" => Ok(Severity::Info),
+            "warning" | "warn" => Ok(Severity::Warning),
+            "error" => Ok(Severity::Error),
+            _ => Err(format!("Unknown severity `{}`", s)),
+        }
+    }
+}
+
+/// Position in the config that a diagnostic refers to.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub type_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field_name: Option<String>,
+}
+
+impl Location {
+    pub fn new(type_name: impl Into<String>) -> Self {
+        Self { type_name: type_name.into(), field_name: None }
+    }
+
+    pub fn field(type_name: impl Into<String>, field_name: impl Into<String>) -> Self {
+        Self {
+            type_name: type_name.into(),
+            field_name: Some(field_name.into()),
+        }
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.field_name {
+            Some(field_name) => write!(f, "{}.{}", self.type_name, field_name),
+            None => write!(f, "{}", self.type_name),
+        }
+    }
+}
+
+/// A problem detected by a single rule before severity is applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    pub location: Location,
+    pub message: String,
+}
+
+impl Finding {
+    pub fn new(location: Location, message: impl Into<String>) -> Self {
+        Self { location, message: message.into() }
+    }
+}
+
+/// A finding reported by the [Linter] with the rule and severity attached.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub location: Location,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{}] {} {}: {}",
+            self.severity, self.rule, self.location, self.message
+        )
+    }
+}
+
+/// A check that can be executed on the config.
+pub trait Rule: Send + Sync {
+    /// Unique id of the rule used for configuration and suppressions.
+    fn id(&self) -> &'static str;
+
+    /// Short human readable description of what the rule detects.
+    fn description(&self) -> &'static str;
+
+    /// Severity used unless it's overwritten in the [Linter].
+    fn severity(&self) -> Severity;
+
+    fn check(&self, config: &Config) -> Vec<Finding>;
+}
+
+/// Result of running the [Linter] on a config.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Report {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Report {
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+}
+
+/// Runs a set of lint [Rule]s on the config and collects [Diagnostic]s.
+pub struct Linter {
+    rules: Vec<Box<dyn Rule>>,
+    // `None` disables the rule
+    severities: HashMap<String, Option<Severity>>,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                Box::new(HttpBatchKey),
+                Box::new(MissingCache),
+                Box::new(UnusedType),
+                Box::new(UnknownTemplateArg),
+                Box::new(NPlusOne),
+            ],
+            severities: HashMap::new(),
+        }
+    }
+}
+
+impl Linter {
+    pub fn rules(&self) -> &[Box<dyn Rule>] {
+        &self.rules
+    }
+
+    /// Overrides the severity of the rule. Passing `None` disables the rule.
+    pub fn severity(mut self, rule: &str, severity: Option<Severity>) -> anyhow::Result<Self> {
+        if !self.rules.iter().any(|r| r.id() == rule) {
+            return Err(anyhow::anyhow!("Unknown lint rule `{}`", rule));
+        }
+
+        self.severities.insert(rule.to_string(), severity);
+        Ok(self)
+    }
+
+    pub fn lint(&self, config: &Config) -> Report {
+        let mut diagnostics = Vec::new();
+
+        for rule in self.rules.iter() {
+            let severity = match self.severities.get(rule.id()) {
+                Some(Some(severity)) => *severity,
+                Some(None) => continue,
+                None => rule.severity(),
+            };
+
+            diagnostics.extend(
+                rule.check(config)
+                    .into_iter()
+                    .filter(|finding| !is_suppressed(config, &finding.location, rule.id()))
+                    .map(|finding| Diagnostic {
+                        rule: rule.id(),
+                        severity,
+                        location: finding.location,
+                        message: finding.message,
+                    }),
+            );
+        }
+
+        diagnostics.sort_by(|a, b| {
+            b.severity
+                .cmp(&a.severity)
+                .then_with(|| a.location.to_string().cmp(&b.location.to_string()))
+        });
+
+        Report { diagnostics }
+    }
+}
+
+/// Checks if the rule is disabled with `@lint(allow: [...])` on the type or
+/// the field of the location.
+fn is_suppressed(config: &Config, location: &Location, rule: &str) -> bool {
+    let Some(type_of) = config.find_type(&location.type_name) else {
+        return false;
+    };
+
+    let type_allows = type_of.lint.as_ref().is_some_and(|lint| lint.allows(rule));
+    let field_allows = location
+        .field_name
+        .as_ref()
+        .and_then(|name| type_of.fields.get(name))
+        .and_then(|field| field.lint.as_ref())
+        .is_some_and(|lint| lint.allows(rule));
+
+    type_allows || field_allows
+}
+
+#[cfg(test)]
+mod tests {
+    use tailcall_valid::Validator;
+
+    use super::*;
+
+    fn lint(sdl: &str) -> Report {
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+        Linter::default().lint(&config)
+    }
+
+    fn rules(report: &Report) -> Vec<&'static str> {
+        report.diagnostics.iter().map(|d| d.rule).collect()
+    }
+
+    #[test]
+    fn test_http_batch_key() {
+        let report = lint(
+            r#"
+            schema { query: Query }
+            type Query {
+                posts: [Post] @http(url: "http://jsonplaceholder.typicode.com/posts") @cache(maxAge: 100)
+            }
+            type Post {
+                userId: Int!
+                user: User @http(url: "http://jsonplaceholder.typicode.com/users/{{.value.userId}}")
+            }
+            type User { id: Int }
+            "#,
+        );
+
+        assert!(rules(&report).contains(&"http-batch-key"));
+        assert!(rules(&report).contains(&"n-plus-one"));
+        assert!(!report.has_errors());
+    }
+
+    #[test]
+    fn test_suppression() {
+        let report = lint(
+            r#"
+            schema { query: Query }
+            type Query {
+                posts: [Post] @http(url: "http://jsonplaceholder.typicode.com/posts") @cache(maxAge: 100)
+            }
+            type Post {
+                userId: Int!
+                user: User
+                    @http(url: "http://jsonplaceholder.typicode.com/users/{{.value.userId}}")
+                    @lint(allow: ["http-batch-key", "n-plus-one"])
+            }
+            type User { id: Int }
+            "#,
+        );
+
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn test_missing_cache_and_unused_type() {
+        let report = lint(
+            r#"
+            schema { query: Query }
+            type Query {
+                users: [User] @http(url: "http://jsonplaceholder.typicode.com/users")
+            }
+            type User { id: Int }
+            type Unused { id: Int }
+            "#,
+        );
+
+        assert_eq!(rules(&report), vec!["unused-type", "missing-cache"]);
+        assert_eq!(report.diagnostics[0].location, Location::new("Unused"));
+    }
+
+    #[test]
+    fn test_unknown_template_arg() {
+        let report = lint(
+            r#"
+            schema { query: Query }
+            type Query {
+                user(id: Int!): User
+                    @http(
+                        url: "http://jsonplaceholder.typicode.com/users"
+                        headers: [{ key: "x-user", value: "{{.args.userId}}" }]
+                    )
+                    @cache(maxAge: 100)
+            }
+            type User { id: Int }
+            "#,
+        );
+
+        assert_eq!(rules(&report), vec!["unknown-template-arg"]);
+        assert!(report.has_errors());
+    }
+
+    #[test]
+    fn test_severity_override() {
+        let config = Config::from_sdl(
+            r#"
+            schema { query: Query }
+            type Query {
+                users: [User] @http(url: "http://jsonplaceholder.typicode.com/users")
+            }
+            type User { id: Int }
+            "#,
+        )
+        .to_result()
+        .unwrap();
+
+        let report = Linter::default()
+            .severity("missing-cache", Some(Severity::Error))
+            .unwrap()
+            .lint(&config);
+        assert!(report.has_errors());
+
+        let report = Linter::default()
+            .severity("missing-cache", None)
+            .unwrap()
+            .lint(&config);
+        assert!(report.is_empty());
+
+        assert!(Linter::default().severity("unknown", None).is_err());
+    }
+}
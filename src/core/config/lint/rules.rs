@@ -0,0 +1,303 @@
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+
+use super::{Finding, Location, Rule, Severity};
+use crate::core::config::{Config, Field, Resolver};
+use crate::core::http::Method;
+use crate::core::mustache::Mustache;
+
+/// Reports `@http` resolvers that are executed once per item of a list
+/// without a `batchKey`.
+pub struct HttpBatchKey;
+
+impl Rule for HttpBatchKey {
+    fn id(&self) -> &'static str {
+        "http-batch-key"
+    }
+
+    fn description(&self) -> &'static str {
+        "@http resolver inside a list is not batched with batchKey"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, config: &Config) -> Vec<Finding> {
+        let list_types = config
+            .types
+            .values()
+            .flat_map(|type_of| type_of.fields.values())
+            .filter(|field| field.type_of.is_list())
+            .map(|field| field.type_of.name().as_str())
+            .collect::<BTreeSet<_>>();
+
+        let mut findings = Vec::new();
+        for type_name in list_types {
+            let Some(type_of) = config.find_type(type_name) else {
+                continue;
+            };
+
+            for (field_name, field) in type_of.fields.iter() {
+                let unbatched = field.resolvers.iter().any(|resolver| {
+                    matches!(resolver, Resolver::Http(http) if http.batch_key.is_empty())
+                });
+
+                if unbatched {
+                    findings.push(Finding::new(
+                        Location::field(type_name, field_name),
+                        format!(
+                            "`{}` is resolved for every item of a list, consider adding `batchKey`",
+                            field_name
+                        ),
+                    ));
+                }
+            }
+        }
+
+        findings
+    }
+}
+
+/// Reports root query fields that make idempotent upstream calls without
+/// `@cache`.
+pub struct MissingCache;
+
+impl Rule for MissingCache {
+    fn id(&self) -> &'static str {
+        "missing-cache"
+    }
+
+    fn description(&self) -> &'static str {
+        "Idempotent root query field is not cached"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check(&self, config: &Config) -> Vec<Finding> {
+        let Some(query) = config.schema.query.as_ref() else {
+            return Vec::new();
+        };
+        let Some(type_of) = config.find_type(query) else {
+            return Vec::new();
+        };
+
+        if type_of.cache.is_some() {
+            return Vec::new();
+        }
+
+        type_of
+            .fields
+            .iter()
+            .filter(|(_, field)| field.cache.is_none())
+            .filter(|(_, field)| {
+                field.resolvers.iter().any(|resolver| {
+                    matches!(resolver, Resolver::Http(http) if http.method == Method::GET)
+                })
+            })
+            .map(|(field_name, _)| {
+                Finding::new(
+                    Location::field(query, field_name),
+                    format!(
+                        "`{}` issues a GET request on every query, consider adding @cache",
+                        field_name
+                    ),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Reports types that are not reachable from the root types and would be
+/// removed by tree shaking.
+pub struct UnusedType;
+
+impl Rule for UnusedType {
+    fn id(&self) -> &'static str {
+        "unused-type"
+    }
+
+    fn description(&self) -> &'static str {
+        "Type is not reachable from the schema root types"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, config: &Config) -> Vec<Finding> {
+        let mut unused = config
+            .unused_types()
+            .into_iter()
+            // entities are reachable through the federation `_entities` query
+            .filter(|name| {
+                config
+                    .find_type(name)
+                    .is_none_or(|type_of| !type_of.has_resolver())
+            })
+            .collect::<Vec<_>>();
+        unused.sort();
+
+        unused
+            .into_iter()
+            .map(|name| {
+                let message = format!("`{}` is not used and can be removed", name);
+                Finding::new(Location::new(name), message)
+            })
+            .collect()
+    }
+}
+
+/// Reports mustache templates that refer to arguments the field doesn't
+/// define.
+pub struct UnknownTemplateArg;
+
+impl UnknownTemplateArg {
+    fn templates(field: &Field) -> Vec<String> {
+        let mut templates = Vec::new();
+
+        for resolver in field.resolvers.iter() {
+            match resolver {
+                Resolver::Http(http) => {
+                    templates.push(http.url.clone());
+                    templates.extend(http.headers.iter().map(|header| header.value.clone()));
+                    templates.extend(http.query.iter().map(|query| query.value.clone()));
+                    if let Some(body) = &http.body {
+                        collect_strings(body, &mut templates);
+                    }
+                }
+                Resolver::Grpc(grpc) => {
+                    templates.extend(grpc.headers.iter().map(|header| header.value.clone()));
+                    if let Some(body) = &grpc.body {
+                        collect_strings(body, &mut templates);
+                    }
+                }
+                Resolver::Graphql(graphql) => {
+                    templates.extend(graphql.headers.iter().map(|header| header.value.clone()));
+                    templates.extend(graphql.args.iter().flatten().map(|arg| arg.value.clone()));
+                }
+                _ => {}
+            }
+        }
+
+        templates
+    }
+}
+
+impl Rule for UnknownTemplateArg {
+    fn id(&self) -> &'static str {
+        "unknown-template-arg"
+    }
+
+    fn description(&self) -> &'static str {
+        "Mustache template refers to an argument that doesn't exist"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, config: &Config) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for (type_name, type_of) in config.types.iter() {
+            for (field_name, field) in type_of.fields.iter() {
+                let unknown_args = Self::templates(field)
+                    .iter()
+                    .flat_map(|template| {
+                        Mustache::parse(template)
+                            .expression_segments()
+                            .into_iter()
+                            .filter(|parts| parts.len() > 1 && parts[0] == "args")
+                            .map(|parts| parts[1].clone())
+                            .collect::<Vec<_>>()
+                    })
+                    .filter(|arg| !field.args.contains_key(arg))
+                    .collect::<BTreeSet<_>>();
+
+                for arg in unknown_args {
+                    findings.push(Finding::new(
+                        Location::field(type_name, field_name),
+                        format!("`{{{{.args.{}}}}}` refers to an unknown argument", arg),
+                    ));
+                }
+            }
+        }
+
+        findings
+    }
+}
+
+fn collect_strings(value: &Value, strings: &mut Vec<String>) {
+    match value {
+        Value::String(string) => strings.push(string.clone()),
+        Value::Array(values) => values
+            .iter()
+            .for_each(|value| collect_strings(value, strings)),
+        Value::Object(map) => map
+            .values()
+            .for_each(|value| collect_strings(value, strings)),
+        _ => {}
+    }
+}
+
+/// Reports query paths that issue N + 1 requests to the upstream.
+pub struct NPlusOne;
+
+impl NPlusOne {
+    /// Finds the type that contains the last field of the path.
+    fn location(config: &Config, path: &[String]) -> Option<Location> {
+        let (first, rest) = path.split_first()?;
+
+        let (mut type_name, fields) = match first.strip_prefix("__entities") {
+            // entity paths are rendered as `__entities(representations: [{ __typename: "Type"}])`
+            Some(entity) => (entity.split('"').nth(1)?.to_string(), rest),
+            None => (config.schema.query.clone()?, path),
+        };
+
+        let Some((last, fields)) = fields.split_last() else {
+            return Some(Location::new(type_name));
+        };
+
+        for field in fields {
+            type_name = config
+                .find_type(&type_name)?
+                .fields
+                .get(field)?
+                .type_of
+                .name()
+                .clone();
+        }
+
+        Some(Location::field(type_name, last))
+    }
+}
+
+impl Rule for NPlusOne {
+    fn id(&self) -> &'static str {
+        "n-plus-one"
+    }
+
+    fn description(&self) -> &'static str {
+        "Query path issues N + 1 requests to the upstream"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, config: &Config) -> Vec<Finding> {
+        config
+            .n_plus_one()
+            .iter()
+            .filter_map(|path| {
+                let location = Self::location(config, path)?;
+                let message = format!("N + 1 detected at `{}`", path.join("."));
+                Some(Finding::new(location, message))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,89 @@
+use serde_json::{json, Value};
+
+use super::{Linter, Report, Severity};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Renders a lint [Report] in the [SARIF](https://sarifweb.azurewebsites.net)
+/// format understood by code scanning tools.
+pub struct Sarif<'a> {
+    linter: &'a Linter,
+    report: &'a Report,
+    artifacts: &'a [String],
+}
+
+impl<'a> Sarif<'a> {
+    pub fn new(linter: &'a Linter, report: &'a Report, artifacts: &'a [String]) -> Self {
+        Self { linter, report, artifacts }
+    }
+
+    fn level(severity: Severity) -> &'static str {
+        match severity {
+            Severity::Info => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        let rules = self
+            .linter
+            .rules()
+            .iter()
+            .map(|rule| {
+                json!({
+                    "id": rule.id(),
+                    "shortDescription": { "text": rule.description() },
+                    "defaultConfiguration": { "level": Self::level(rule.severity()) },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let results = self
+            .report
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let kind = if diagnostic.location.field_name.is_some() {
+                    "member"
+                } else {
+                    "type"
+                };
+                let mut location = json!({
+                    "logicalLocations": [{
+                        "fullyQualifiedName": diagnostic.location.to_string(),
+                        "kind": kind,
+                    }]
+                });
+
+                // the config can be merged from several files, so the file is
+                // only known when a single one was linted
+                if let [uri] = self.artifacts {
+                    location["physicalLocation"] = json!({ "artifactLocation": { "uri": uri } });
+                }
+
+                json!({
+                    "ruleId": diagnostic.rule,
+                    "level": Self::level(diagnostic.severity),
+                    "message": { "text": diagnostic.message },
+                    "locations": [location],
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "tailcall",
+                        "informationUri": "https://tailcall.run",
+                        "rules": rules,
+                    }
+                },
+                "results": results,
+            }]
+        })
+    }
+}
@@ -20,10 +20,12 @@ pub mod group_by;
 mod headers;
 mod into_document;
 mod key_values;
+pub mod lint;
 mod npo;
 pub mod reader;
 pub mod reader_context;
 mod resolver;
 mod source;
 pub mod transformer;
+mod typescript;
 mod url_query;
@@ -14,6 +14,12 @@ impl QueryPath {
     pub fn size(&self) -> usize {
         self.0.len()
     }
+
+    /// Iterates over every path as a list of field names starting from the
+    /// root type
+    pub fn iter(&self) -> impl Iterator<Item = &Vec<String>> {
+        self.0.iter()
+    }
 }
 
 impl<'a> From<Chunk<Chunk<Name<'a>>>> for QueryPath {
@@ -8,11 +8,12 @@ use rustls_pki_types::{
 use tailcall_valid::{Valid, ValidationError, Validator};
 use url::Url;
 
-use super::{ConfigModule, Content, Link, LinkType, PrivateKey};
+use super::{ConfigModule, Content, Link, LinkType, PluginLink, PrivateKey};
 use crate::core::config::{Config, ConfigReaderContext, Source};
 use crate::core::proto_reader::ProtoReader;
 use crate::core::resource_reader::{Cached, Resource, ResourceReader};
 use crate::core::rest::EndpointSet;
+use crate::core::rpc;
 use crate::core::runtime::TargetRuntime;
 use crate::core::variance::Invariant;
 
@@ -61,6 +62,7 @@ impl ConfigReader {
 
         let mut extensions = config_module.extensions().clone();
         let mut config_module = Valid::succeed(config_module);
+        let mut entry_linked = false;
 
         for link in links.iter() {
             let path = Self::resolve_path(&link.src, parent_dir);
@@ -90,8 +92,29 @@ impl ConfigReader {
                 }
                 LinkType::Script => {
                     let source = self.resource_reader.read_file(path).await?;
-                    let content = source.content;
-                    extensions.script = Some(content);
+                    let content = if source.path.ends_with(".ts") {
+                        Self::transpile(&source.path, source.content)?
+                    } else {
+                        source.content
+                    };
+                    match &link.id {
+                        Some(id) => {
+                            extensions
+                                .script_modules
+                                .insert(id.clone(), content.clone());
+                            // a script with an id is the entry point only if
+                            // every script has an id
+                            if !entry_linked {
+                                extensions.script = Some(content);
+                            }
+                        }
+                        None => {
+                            // the blueprint rejects several scripts without an
+                            // id, the last one is kept until then
+                            extensions.script = Some(content);
+                            entry_linked = true;
+                        }
+                    }
                 }
                 LinkType::Cert => {
                     let source = self.resource_reader.read_file(path).await?;
@@ -108,6 +131,9 @@ impl ConfigReader {
                     let content = source.content;
 
                     extensions.endpoint_set = EndpointSet::try_new(&content)?;
+                    extensions
+                        .rpc_methods
+                        .extend(rpc::Method::try_new(&content)?);
                 }
                 LinkType::Htpasswd => {
                     let source = self.resource_reader.read_file(path).await?;
@@ -128,6 +154,32 @@ impl ConfigReader {
                         content: serde_path_to_error::deserialize(de)?,
                     })
                 }
+                LinkType::Plugin => {
+                    // the module is compiled by the runtime that executes it
+                    let scalars = match link.meta.as_ref().and_then(|meta| meta.get("scalars")) {
+                        Some(scalars) => {
+                            serde_json::from_value(scalars.clone()).map_err(|err| {
+                                anyhow::anyhow!("Invalid scalars of plugin {}: {}", link.src, err)
+                            })?
+                        }
+                        None => Default::default(),
+                    };
+                    extensions.plugins.push(Content {
+                        id: link.id.clone(),
+                        content: PluginLink { path, scalars },
+                    });
+                }
+                LinkType::Mock => {
+                    let source = self.resource_reader.read_file(path).await?;
+                    let content = source.content;
+
+                    let de = &mut serde_json::Deserializer::from_str(&content);
+
+                    extensions.mocks.push(Content {
+                        id: link.id.clone(),
+                        content: serde_path_to_error::deserialize(de)?,
+                    })
+                }
                 LinkType::Grpc => {
                     let meta = self
                         .proto_reader
@@ -248,6 +300,20 @@ impl ConfigReader {
         self.ext_links(ConfigModule::from(config), parent_dir).await
     }
 
+    /// Strips the types of a TypeScript script, the runtime only executes JS.
+    fn transpile(path: &str, source: String) -> anyhow::Result<String> {
+        #[cfg(feature = "js")]
+        return crate::core::worker::transpile(path, &source);
+        #[cfg(not(feature = "js"))]
+        {
+            let _ = source;
+            Err(anyhow::anyhow!(
+                "Failed to load {}: TypeScript scripts require the `js` feature",
+                path
+            ))
+        }
+    }
+
     /// Checks if path is a URL or absolute path, returns directly if so.
     /// Otherwise, it joins file path with relative dir path.
     fn resolve_path(src: &str, root_dir: Option<&Path>) -> String {
@@ -275,8 +341,11 @@ mod reader_tests {
 
     use pretty_assertions::assert_eq;
 
+    use tailcall_valid::Validator;
+
+    use crate::core::blueprint::Blueprint;
     use crate::core::config::reader::ConfigReader;
-    use crate::core::config::{Config, Type};
+    use crate::core::config::{Config, ConfigModule, Type};
 
     fn start_mock_server() -> httpmock::MockServer {
         httpmock::MockServer::start()
@@ -372,6 +441,50 @@ mod reader_tests {
         );
     }
 
+    async fn read_scripts(links: &[(&str, Option<&str>)]) -> ConfigModule {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sdl = "schema".to_string();
+        for (name, id) in links {
+            std::fs::write(dir.path().join(name), format!("// {}", name)).unwrap();
+            let id = id.map(|id| format!(", id: \"{}\"", id)).unwrap_or_default();
+            sdl.push_str(&format!(" @link(type: Script, src: \"{}\"{})", name, id));
+        }
+        sdl.push_str(" { query: Query } type Query { foo: String @expr(body: \"foo\") }");
+
+        let config = Config::from_sdl(&sdl).to_result().unwrap();
+        let reader = ConfigReader::init(crate::core::runtime::test::init(None));
+        reader.resolve(config, Some(dir.path())).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_script_entry_point() {
+        let config =
+            read_scripts(&[("a.js", Some("a")), ("main.js", None), ("c.js", Some("c"))]).await;
+
+        let extensions = config.extensions();
+        assert_eq!(extensions.script.as_deref(), Some("// main.js"));
+        assert_eq!(
+            extensions.script_modules.keys().collect::<Vec<_>>(),
+            vec!["a", "c"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_script_entry_point_with_ids() {
+        // the last script is the entry point, as it was before the modules
+        let config = read_scripts(&[("a.js", Some("a")), ("b.js", Some("b"))]).await;
+        assert_eq!(config.extensions().script.as_deref(), Some("// b.js"));
+    }
+
+    #[tokio::test]
+    async fn test_several_entry_points() {
+        let config = read_scripts(&[("a.js", None), ("b.js", None)]).await;
+        let error = Blueprint::try_from(&config).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Only one script link is allowed"));
+    }
+
     #[test]
     fn test_relative_path() {
         let path_dir = Path::new("abc/xyz");
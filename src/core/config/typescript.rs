@@ -0,0 +1,279 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use super::{Config, Resolver};
+use crate::core::scalar::Scalar;
+
+/// Shapes of the values exchanged with the `onRequest` hooks, they mirror
+/// `Event` and `Command` of the workers, and the `tailcall` global. The other
+/// functions receive and return their values as JSON strings.
+const WORKER_TYPES: &str = r#"export type Awaitable<T> = T | Promise<T>
+
+/** JSON encoding of a `T`, the values of `@js` and `onResponseBody` are exchanged as strings */
+export type Json<T> = string
+
+export interface Uri {
+  path: string
+  query?: Record<string, string>
+  scheme?: "http" | "https"
+  host?: string | null
+  port?: number | null
+}
+
+export interface WorkerRequest {
+  uri: Uri
+  method: string
+  headers: Record<string, string>
+  body?: string | null
+}
+
+export interface WorkerResponse {
+  status: number
+  headers: Record<string, string>
+  body?: string | null
+}
+
+export type Event = {request: WorkerRequest} | {response: WorkerResponse}
+
+export type Command = {request: WorkerRequest} | {response: WorkerResponse}
+
+declare global {
+  const tailcall: {
+    readonly cache: {
+      get<T = unknown>(key: string): Promise<T | null>
+      set(key: string, value: unknown, ttl?: number): Promise<null>
+    }
+    readonly env: Readonly<Record<string, string | undefined>>
+    readonly config: unknown
+  }
+}
+"#;
+
+impl Config {
+    /// Generates the TypeScript definitions of the functions the scripts
+    /// are expected to export, along with the types of the config they
+    /// receive and return.
+    pub fn to_typescript(&self) -> String {
+        let mut out = String::from("// Generated by tailcall, do not edit.\n\n");
+        out.push_str(WORKER_TYPES);
+
+        for (name, ty) in &self.types {
+            if Scalar::is_predefined(name) {
+                continue;
+            }
+            out.push('\n');
+            write_doc(&mut out, "", ty.doc.as_deref());
+            if ty.scalar() {
+                let _ = writeln!(out, "export type {} = unknown", name);
+                continue;
+            }
+            let _ = writeln!(out, "export interface {} {{", name);
+            for (field_name, field) in &ty.fields {
+                write_doc(&mut out, "  ", field.doc.as_deref());
+                let optional = if field.type_of.is_nullable() { "?" } else { "" };
+                let _ = writeln!(
+                    out,
+                    "  {}{}: {}",
+                    field_name,
+                    optional,
+                    to_type(&field.type_of)
+                );
+            }
+            out.push_str("}\n");
+        }
+
+        for (name, union) in &self.unions {
+            out.push('\n');
+            write_doc(&mut out, "", union.doc.as_deref());
+            let types = union.types.iter().cloned().collect::<Vec<_>>();
+            let _ = writeln!(out, "export type {} = {}", name, types.join(" | "));
+        }
+
+        for (name, enum_) in &self.enums {
+            out.push('\n');
+            write_doc(&mut out, "", enum_.doc.as_deref());
+            let variants = enum_
+                .variants
+                .iter()
+                .map(|variant| format!("\"{}\"", variant.name))
+                .collect::<Vec<_>>();
+            let _ = writeln!(out, "export type {} = {}", name, variants.join(" | "));
+        }
+
+        out.push_str("\nexport interface Functions {\n");
+        for (name, signatures) in self.functions() {
+            for (signature, docs) in signatures {
+                let _ = writeln!(
+                    out,
+                    "  /** {} */",
+                    docs.into_iter().collect::<Vec<_>>().join(", ")
+                );
+                let _ = writeln!(out, "  {}{}", name, signature);
+            }
+        }
+        out.push_str("}\n");
+
+        out
+    }
+
+    /// Signatures of every function referenced by the config keyed by the
+    /// name of the function, with the places that reference them.
+    fn functions(&self) -> BTreeMap<String, BTreeMap<String, BTreeSet<String>>> {
+        let mut functions: BTreeMap<String, BTreeMap<String, BTreeSet<String>>> = BTreeMap::new();
+        let mut add = |name: &str, signature: String, doc: String| {
+            functions
+                .entry(name.to_string())
+                .or_default()
+                .entry(signature)
+                .or_default()
+                .insert(doc);
+        };
+        let on_request = "(event: Event): Awaitable<Command | undefined>".to_string();
+
+        if let Some(name) = &self.upstream.on_request {
+            add(
+                name,
+                on_request.clone(),
+                "`onRequest` of `@upstream`".to_string(),
+            );
+        }
+
+        for (type_name, ty) in &self.types {
+            for resolver in ty.resolvers.0.iter() {
+                if let Resolver::Js(js) = resolver {
+                    add(
+                        &js.name,
+                        format!(
+                            "(value: Json<{}>): Awaitable<Json<{}>>",
+                            type_name, type_name
+                        ),
+                        format!("`@js` of `{}`", type_name),
+                    );
+                }
+            }
+
+            for (field_name, field) in &ty.fields {
+                let path = format!("{}.{}", type_name, field_name);
+                let output = to_type(&field.type_of);
+                let on_response_body = format!("(body: string): Awaitable<Json<{}>>", output);
+                for resolver in field.resolvers.0.iter() {
+                    match resolver {
+                        Resolver::Js(js) => add(
+                            &js.name,
+                            format!("(value: Json<{}>): Awaitable<Json<{}>>", type_name, output),
+                            format!("`@js` of `{}`", path),
+                        ),
+                        Resolver::Http(http) => {
+                            if let Some(name) = &http.on_request {
+                                add(
+                                    name,
+                                    on_request.clone(),
+                                    format!("`onRequest` of `{}`", path),
+                                );
+                            }
+                            if let Some(name) = &http.on_response_body {
+                                add(
+                                    name,
+                                    on_response_body.clone(),
+                                    format!("`onResponseBody` of `{}`", path),
+                                );
+                            }
+                        }
+                        Resolver::Grpc(grpc) => {
+                            if let Some(name) = &grpc.on_response_body {
+                                add(
+                                    name,
+                                    on_response_body.clone(),
+                                    format!("`onResponseBody` of `{}`", path),
+                                );
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        functions
+    }
+}
+
+fn write_doc(out: &mut String, indent: &str, doc: Option<&str>) {
+    if let Some(doc) = doc {
+        let _ = writeln!(out, "{}/** {} */", indent, doc.trim().replace("*/", "*\\/"));
+    }
+}
+
+fn to_type(type_of: &crate::core::Type) -> String {
+    let (ty, non_null) = match type_of {
+        crate::core::Type::Named { name, non_null } => (to_named_type(name).to_string(), *non_null),
+        crate::core::Type::List { of_type, non_null } => {
+            (format!("Array<{}>", to_type(of_type)), *non_null)
+        }
+    };
+
+    if non_null || ty == "null" {
+        ty
+    } else {
+        format!("{} | null", ty)
+    }
+}
+
+fn to_named_type(name: &str) -> &str {
+    match name {
+        "Int" | "Float" | "Int8" | "Int16" | "Int32" | "UInt8" | "UInt16" | "UInt32" => "number",
+        "Boolean" => "boolean",
+        "JSON" => "unknown",
+        "Empty" => "null",
+        // the remaining scalars, including 64 and 128 bit integers, are strings
+        name if Scalar::is_predefined(name) => "string",
+        name => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::config::Source;
+
+    #[test]
+    fn test_to_typescript() {
+        let sdl = r#"
+            schema @upstream(onRequest: "auth") {
+              query: Query
+            }
+
+            type Query {
+              "All the users"
+              users: [User!]! @http(url: "http://localhost/users", onRequest: "auth")
+            }
+
+            type User {
+              id: Int!
+              name: String
+              role: Role
+              fullName: String! @js(name: "fullName")
+            }
+
+            enum Role {
+              ADMIN
+              USER
+            }
+        "#;
+        let config = Config::from_source(Source::GraphQL, sdl).unwrap();
+        let actual = config.to_typescript();
+
+        assert!(actual.contains("export type Event = {request: WorkerRequest}"));
+        assert!(actual.contains(
+            "export interface User {\n  fullName: string\n  id: number\n  name?: string | null\n  role?: Role | null\n}"
+        ));
+        assert!(actual.contains("/** All the users */\n  users: Array<User>"));
+        assert!(actual.contains("export type Role = \"ADMIN\" | \"USER\""));
+        assert!(actual.contains(
+            "  /** `@js` of `User.fullName` */\n  fullName(value: Json<User>): Awaitable<Json<string>>"
+        ));
+        assert!(actual.contains(
+            "  /** `onRequest` of `@upstream`, `onRequest` of `Query.users` */\n  auth(event: Event): Awaitable<Command | undefined>"
+        ));
+    }
+}
@@ -349,8 +349,13 @@ impl Context {
                 if let Some(graphql_type) = get_input_type(method.input_type())? {
                     let key = graphql_type.clone().into_field().to_string();
                     let type_of = graphql_type.into_object_type().to_string();
+                    let mut type_of = Type::from(type_of).into_required();
+                    // the messages of client-streaming methods are sent from a list
+                    if method.client_streaming() {
+                        type_of = type_of.into_list().into_required();
+                    }
                     let val = Arg {
-                        type_of: Type::from(type_of).into_required(),
+                        type_of,
                         /* Setting it not null by default. There's no way to infer this
                          * from proto file */
                         doc: None,
@@ -366,6 +371,10 @@ impl Context {
                     .into_object_type()
                     .to_string();
                 cfg_field.type_of = cfg_field.type_of.with_name(output_ty);
+                // the messages of server-streaming methods are collected into a list
+                if method.server_streaming() {
+                    cfg_field.type_of = cfg_field.type_of.into_list();
+                }
 
                 cfg_field.resolvers = Resolver::Grpc(Grpc {
                     url: url.to_string(),
@@ -376,6 +385,8 @@ impl Context {
                     dedupe: None,
                     select: None,
                     on_response_body: None,
+                    protocol: Default::default(),
+                    max_stream_items: None,
                 })
                 .into();
 
@@ -110,6 +110,8 @@ mod tests {
             dedupe: Default::default(),
             select: Default::default(),
             on_response_body: Default::default(),
+            protocol: Default::default(),
+            max_stream_items: None,
         };
 
         let http = Http::from(grpc);
@@ -130,6 +132,8 @@ mod tests {
             dedupe: Default::default(),
             select: Default::default(),
             on_response_body: Default::default(),
+            protocol: Default::default(),
+            max_stream_items: None,
         };
 
         let http = Http::from(grpc);
@@ -148,6 +152,8 @@ mod tests {
             dedupe: Default::default(),
             select: Default::default(),
             on_response_body: Default::default(),
+            protocol: Default::default(),
+            max_stream_items: None,
         };
 
         let http = Http::from(grpc);
@@ -183,6 +189,8 @@ mod tests {
             dedupe: Some(true),
             select: Some(Value::String("select_value".to_string())),
             on_response_body: Some("on_response_body_value".to_string()),
+            protocol: Default::default(),
+            max_stream_items: None,
         };
 
         let http = Http::from(grpc);
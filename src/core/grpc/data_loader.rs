@@ -9,6 +9,7 @@ use async_graphql_value::ConstValue;
 
 use super::data_loader_request::DataLoaderRequest;
 use super::protobuf::ProtobufOperation;
+use super::protocol;
 use super::request::execute_grpc_request;
 use crate::core::config::group_by::GroupBy;
 use crate::core::config::Batch;
@@ -38,7 +39,10 @@ impl GrpcDataLoader {
     ) -> anyhow::Result<HashMap<DataLoaderRequest, Response<async_graphql::Value>>> {
         let results = keys.iter().map(|key| async {
             let result = match key.to_request() {
-                Ok(req) => execute_grpc_request(&self.runtime, &self.operation, req).await,
+                Ok(req) => {
+                    let protocol = key.template.protocol;
+                    execute_grpc_request(&self.runtime, &self.operation, protocol, req).await
+                }
                 Err(error) => Err(error),
             };
 
@@ -69,6 +73,8 @@ impl GrpcDataLoader {
             .convert_multiple_inputs(inputs, group_by.key())?;
 
         let first_request = keys[0].clone();
+        let protocol = first_request.template.protocol;
+        let multiple_body = protocol::encode_request(protocol, &self.operation, multiple_body)?;
         let multiple_request = create_grpc_request(
             first_request.template.url,
             first_request.template.headers,
@@ -76,7 +82,8 @@ impl GrpcDataLoader {
         );
 
         let response =
-            execute_grpc_request(&self.runtime, &self.operation, multiple_request).await?;
+            execute_grpc_request(&self.runtime, &self.operation, protocol, multiple_request)
+                .await?;
 
         let path = &group_by.path();
         let response_body = response.body.group_by(path);
@@ -62,7 +62,7 @@ mod tests {
     use super::DataLoaderRequest;
     use crate::core::blueprint::GrpcMethod;
     use crate::core::config::reader::ConfigReader;
-    use crate::core::config::{Config, Field, Grpc, Link, LinkType, Resolver, Type};
+    use crate::core::config::{Config, Field, Grpc, GrpcProtocol, Link, LinkType, Resolver, Type};
     use crate::core::grpc::protobuf::{ProtobufOperation, ProtobufSet};
     use crate::core::grpc::request_template::RenderedRequestTemplate;
 
@@ -111,6 +111,7 @@ mod tests {
             headers: HeaderMap::new(),
             operation: get_protobuf_op().await,
             body: "{}".to_owned(),
+            protocol: GrpcProtocol::Grpc,
         };
 
         let dl_req_1 = DataLoaderRequest::new(tmpl.clone(), batch_headers.clone());
@@ -130,6 +131,7 @@ mod tests {
             )]),
             operation: get_protobuf_op().await,
             body: "{}".to_owned(),
+            protocol: GrpcProtocol::Grpc,
         };
         let tmpl_2 = tmpl_1.clone();
 
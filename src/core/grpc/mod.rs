@@ -1,6 +1,7 @@
 pub mod data_loader;
 pub mod data_loader_request;
 pub mod protobuf;
+pub mod protocol;
 pub mod request;
 pub mod request_template;
 
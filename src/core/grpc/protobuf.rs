@@ -39,6 +39,31 @@ fn message_to_bytes(message: DynamicMessage) -> Result<Vec<u8>> {
     Ok(buf)
 }
 
+/// Prefixes the message with the header of the Length-Prefixed Message
+/// framing.
+pub fn frame(message: &[u8]) -> Vec<u8> {
+    let mut buf: Vec<u8> = Vec::with_capacity(message.len() + 5);
+    buf.put_u8(0);
+    buf.put_u32(message.len() as u32);
+    buf.put_slice(message);
+
+    buf
+}
+
+/// Message of a Length-Prefixed Message frame.
+pub fn unframe(bytes: &[u8]) -> Result<&[u8]> {
+    if bytes.len() < 5 {
+        bail!("Empty message");
+    }
+    if bytes[0] != 0 {
+        bail!("Compressed messages are not supported");
+    }
+    let len = u32::from_be_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]) as usize;
+    bytes[5..]
+        .get(..len)
+        .context("Message is shorter than its declared length")
+}
+
 pub fn protobuf_value_as_str(value: &prost_reflect::Value) -> String {
     use prost_reflect::Value;
 
@@ -125,12 +150,49 @@ impl ProtobufService {
     }
 }
 
+/// Counts the messages of a stream of frames as its chunks arrive, the
+/// gRPC-Web frames of the trailers aren't messages.
+#[derive(Debug, Default)]
+struct FrameCounter {
+    header: Vec<u8>,
+    remaining: usize,
+    messages: usize,
+}
+
+impl FrameCounter {
+    /// Consumes the chunk and returns the number of messages so far.
+    fn push(&mut self, mut chunk: &[u8]) -> usize {
+        while !chunk.is_empty() {
+            if self.remaining > 0 {
+                let len = self.remaining.min(chunk.len());
+                self.remaining -= len;
+                chunk = &chunk[len..];
+                continue;
+            }
+
+            let len = (5 - self.header.len()).min(chunk.len());
+            self.header.extend_from_slice(&chunk[..len]);
+            chunk = &chunk[len..];
+            if let [flag, a, b, c, d] = self.header[..] {
+                self.remaining = u32::from_be_bytes([a, b, c, d]) as usize;
+                if flag & 0x80 == 0 {
+                    self.messages += 1;
+                }
+                self.header.clear();
+            }
+        }
+
+        self.messages
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ProtobufOperation {
     pub method: MethodDescriptor,
     pub input_type: MessageDescriptor,
     pub output_type: MessageDescriptor,
     serialize_options: SerializeOptions,
+    max_stream_items: usize,
 }
 
 impl Eq for ProtobufOperation {}
@@ -155,8 +217,15 @@ impl ProtobufOperation {
             input_type,
             output_type,
             serialize_options: SerializeOptions::default().skip_default_fields(false),
+            max_stream_items: usize::MAX,
         }
     }
+
+    /// Limits the number of messages of the streams of the method.
+    pub fn with_max_stream_items(self, max_stream_items: usize) -> Self {
+        Self { max_stream_items, ..self }
+    }
+
     pub fn name(&self) -> &str {
         self.method.name()
     }
@@ -165,10 +234,36 @@ impl ProtobufOperation {
         self.method.parent_service().name()
     }
 
+    /// Encodes the input as a framed message, the input of a client-streaming
+    /// method is a list whose items are encoded as a stream of messages.
     pub fn convert_input(&self, input: &str) -> Result<Vec<u8>> {
-        let message = to_message(&self.input_type, input)?;
+        if !self.method.is_client_streaming() {
+            let message = to_message(&self.input_type, input)?;
 
-        message_to_bytes(message)
+            return message_to_bytes(message);
+        }
+
+        let inputs = serde_json::from_str::<Vec<serde_json::Value>>(input).with_context(|| {
+            format!(
+                "Input of client-streaming method {} must be a list",
+                self.name()
+            )
+        })?;
+        self.check_stream_len(inputs.len())?;
+
+        let mut buf = Vec::new();
+        for input in inputs {
+            let message = DynamicMessage::deserialize(self.input_type.clone(), input)
+                .with_context(|| {
+                    format!(
+                        "Failed to parse input according to type {}",
+                        self.input_type.full_name()
+                    )
+                })?;
+            buf.extend(message_to_bytes(message)?);
+        }
+
+        Ok(buf)
     }
 
     pub fn convert_multiple_inputs<'a>(
@@ -210,7 +305,25 @@ impl ProtobufOperation {
         message_to_bytes(message).map(|result| (result, ids))
     }
 
+    /// Decodes the framed response, the stream of messages of a
+    /// server-streaming method is decoded as a list.
     pub fn convert_output<T: serde::de::DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        if self.method.is_server_streaming() {
+            let mut messages = Vec::new();
+            let mut rest = bytes;
+            while !rest.is_empty() {
+                let message = unframe(rest)?;
+                rest = &rest[5 + message.len()..];
+                self.check_stream_len(messages.len() + 1)?;
+                messages.push(self.decode_output(message)?.serialize_with_options(
+                    serde_json::value::Serializer,
+                    &self.serialize_options,
+                )?);
+            }
+
+            return Ok(serde_json::from_value(serde_json::Value::Array(messages))?);
+        }
+
         if bytes.len() < 5 {
             bail!("Empty response");
         }
@@ -218,13 +331,7 @@ impl ProtobufOperation {
         // see https://www.oreilly.com/library/view/grpc-up-and/9781492058328/ch04.html#:~:text=Length%2DPrefixed%20Message%20Framing
         // 1st byte - compression flag
         // 2-4th bytes - length of the message
-        let message =
-            DynamicMessage::decode(self.output_type.clone(), &bytes[5..]).with_context(|| {
-                format!(
-                    "Failed to parse response for type {}",
-                    self.output_type.full_name()
-                )
-            })?;
+        let message = self.decode_output(&bytes[5..])?;
 
         let mut serializer = serde_json::Serializer::new(vec![]);
         message.serialize_with_options(&mut serializer, &self.serialize_options)?;
@@ -232,6 +339,67 @@ impl ProtobufOperation {
         Ok(json)
     }
 
+    fn decode_output(&self, bytes: &[u8]) -> Result<DynamicMessage> {
+        DynamicMessage::decode(self.output_type.clone(), bytes).with_context(|| {
+            format!(
+                "Failed to parse response for type {}",
+                self.output_type.full_name()
+            )
+        })
+    }
+
+    /// Fails once the stream of a server-streaming method holds more messages
+    /// than the limit, it's passed the chunks of the response as they arrive
+    /// so that the stream is cut off before being buffered completely.
+    pub fn stream_limit(&self) -> impl FnMut(&[u8]) -> Result<()> + Send + '_ {
+        let mut counter = FrameCounter::default();
+        move |chunk| {
+            if self.method.is_server_streaming() {
+                self.check_stream_len(counter.push(chunk))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn check_stream_len(&self, len: usize) -> Result<()> {
+        if len > self.max_stream_items {
+            bail!(
+                "Stream of method {} exceeds the limit of {} messages",
+                self.name(),
+                self.max_stream_items
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Decodes a framed message of the input type into JSON, the fields are
+    /// keyed by their names in the proto file.
+    pub fn decode_input(&self, bytes: &[u8]) -> Result<serde_json::Value> {
+        let message = DynamicMessage::decode(self.input_type.clone(), unframe(bytes)?)
+            .with_context(|| {
+                format!(
+                    "Failed to parse request for type {}",
+                    self.input_type.full_name()
+                )
+            })?;
+
+        let options = self
+            .serialize_options
+            .clone()
+            .use_proto_field_name(true)
+            .stringify_64_bit_integers(false);
+        Ok(message.serialize_with_options(serde_json::value::Serializer, &options)?)
+    }
+
+    /// Encodes JSON as a framed message of the output type.
+    pub fn encode_output(&self, output: &str) -> Result<Vec<u8>> {
+        let message = to_message(&self.output_type, output)?;
+
+        message_to_bytes(message)
+    }
+
     pub fn find_message(&self, name: &str) -> Option<ProtobufMessage> {
         let message_descriptor = self.method.parent_pool().get_message_by_name(name)?;
 
@@ -537,6 +705,95 @@ pub mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn client_streaming_proto_file() -> Result<()> {
+        let grpc_method = GrpcMethod::try_from("streaming.Geometry.RecordPoints").unwrap();
+        let file = ProtobufSet::from_proto_file(get_proto_file(protobuf::STREAMING).await?)?;
+        let service = file.find_service(&grpc_method)?;
+        let operation = service.find_operation(&grpc_method)?;
+
+        let input = operation.convert_input(r#"[{ "x": 1, "y": 2 }, { "x": 3 }]"#)?;
+
+        assert_eq!(input, b"\0\0\0\0\x04\x08\x01\x10\x02\0\0\0\0\x02\x08\x03");
+
+        let error = operation.convert_input(r#"{ "x": 1 }"#).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Input of client-streaming method RecordPoints must be a list"
+        );
+
+        let error = operation
+            .with_max_stream_items(1)
+            .convert_input(r#"[{ "x": 1 }, { "x": 3 }]"#)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Stream of method RecordPoints exceeds the limit of 1 messages"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn server_streaming_proto_file() -> Result<()> {
+        let grpc_method = GrpcMethod::try_from("streaming.Geometry.ListPoints").unwrap();
+        let file = ProtobufSet::from_proto_file(get_proto_file(protobuf::STREAMING).await?)?;
+        let service = file.find_service(&grpc_method)?;
+        let operation = service.find_operation(&grpc_method)?;
+
+        let output = b"\0\0\0\0\x04\x08\x01\x10\x02\0\0\0\0\x02\x08\x03";
+
+        let parsed = operation.convert_output::<serde_json::Value>(output)?;
+
+        assert_eq!(
+            parsed,
+            json!([
+                { "x": 1, "y": 2 },
+                { "x": 3, "y": 0 },
+            ])
+        );
+
+        let parsed = operation.convert_output::<serde_json::Value>(b"")?;
+        assert_eq!(parsed, json!([]));
+
+        let error = operation
+            .with_max_stream_items(1)
+            .convert_output::<serde_json::Value>(output)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Stream of method ListPoints exceeds the limit of 1 messages"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stream_limit() -> Result<()> {
+        let grpc_method = GrpcMethod::try_from("streaming.Geometry.ListPoints").unwrap();
+        let file = ProtobufSet::from_proto_file(get_proto_file(protobuf::STREAMING).await?)?;
+        let service = file.find_service(&grpc_method)?;
+        let operation = service
+            .find_operation(&grpc_method)?
+            .with_max_stream_items(1);
+
+        // a message followed by the gRPC-Web trailers
+        let mut stream_limit = operation.stream_limit();
+        stream_limit(b"\0\0\0\0\x02\x08\x03\x80\0\0\0\0")?;
+
+        // the frames are split across the chunks
+        let mut stream_limit = operation.stream_limit();
+        stream_limit(b"\0\0\0\0\x04\x08\x01")?;
+        stream_limit(b"\x10\x02\0\0")?;
+        let error = stream_limit(b"\0\0\x02\x08\x03").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Stream of method ListPoints exceeds the limit of 1 messages"
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn map_proto_file() -> Result<()> {
         let grpc_method = GrpcMethod::try_from("map.MapService.GetMap").unwrap();
@@ -0,0 +1,301 @@
+//! Transports of the calls to gRPC methods besides native gRPC, i.e.
+//! gRPC-Web and the unary calls of the Connect protocol. Their requests are
+//! encoded from the framed messages built for gRPC, and their responses are
+//! decoded into the framed messages and status headers of gRPC, so that the
+//! rest of the pipeline doesn't depend on the protocol.
+
+use anyhow::{Context, Result};
+use base64::prelude::BASE64_STANDARD_NO_PAD;
+use base64::Engine;
+use http::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use http::HeaderMap;
+use hyper::body::Bytes;
+use prost::Message;
+use prost_reflect::prost_types::Any;
+use prost_reflect::DynamicMessage;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use tonic::{Code, Status};
+
+use super::protobuf::{frame, unframe, ProtobufOperation};
+use crate::core::config::GrpcProtocol;
+use crate::core::http::Response;
+
+/// Flag of the gRPC-Web frames holding the trailers.
+const TRAILERS_FLAG: u8 = 0x80;
+
+/// Headers identifying the protocol of the requests.
+pub fn headers(protocol: GrpcProtocol) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    let content_type = match protocol {
+        GrpcProtocol::Grpc => "application/grpc",
+        GrpcProtocol::GrpcWeb => "application/grpc-web+proto",
+        GrpcProtocol::Connect => "application/proto",
+        GrpcProtocol::ConnectJson => "application/json",
+    };
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+
+    match protocol {
+        GrpcProtocol::GrpcWeb => {
+            headers.insert("x-grpc-web", HeaderValue::from_static("1"));
+        }
+        GrpcProtocol::Connect | GrpcProtocol::ConnectJson => {
+            headers.insert("connect-protocol-version", HeaderValue::from_static("1"));
+        }
+        GrpcProtocol::Grpc => {}
+    }
+
+    headers
+}
+
+/// Encodes the framed message of a call into the body of the request.
+pub fn encode_request(
+    protocol: GrpcProtocol,
+    operation: &ProtobufOperation,
+    framed: Vec<u8>,
+) -> Result<Vec<u8>> {
+    match protocol {
+        GrpcProtocol::Grpc | GrpcProtocol::GrpcWeb => Ok(framed),
+        GrpcProtocol::Connect => Ok(unframe(&framed)?.to_vec()),
+        GrpcProtocol::ConnectJson => {
+            let message = DynamicMessage::decode(operation.input_type.clone(), unframe(&framed)?)?;
+            Ok(serde_json::to_vec(&message)?)
+        }
+    }
+}
+
+/// Decodes the response of a call into the framed message and the status
+/// headers of gRPC.
+pub fn decode_response(
+    protocol: GrpcProtocol,
+    operation: &ProtobufOperation,
+    mut response: Response<Bytes>,
+) -> Result<Response<Bytes>> {
+    match protocol {
+        GrpcProtocol::Grpc => Ok(response),
+        GrpcProtocol::GrpcWeb => {
+            let mut messages = Vec::new();
+            let mut rest = &response.body[..];
+            while !rest.is_empty() {
+                let header = rest.get(..5).context("Truncated gRPC-Web frame")?;
+                let len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+                let frame = rest.get(..5 + len).context("Truncated gRPC-Web frame")?;
+                if header[0] & TRAILERS_FLAG != 0 {
+                    for line in String::from_utf8_lossy(&frame[5..]).split("\r\n") {
+                        if let Some((name, value)) = line.split_once(':') {
+                            response.headers.insert(
+                                HeaderName::from_bytes(name.trim().to_lowercase().as_bytes())?,
+                                HeaderValue::from_str(value.trim())?,
+                            );
+                        }
+                    }
+                } else {
+                    messages.extend_from_slice(frame);
+                }
+                rest = &rest[5 + len..];
+            }
+            response.body = messages.into();
+            Ok(response)
+        }
+        GrpcProtocol::Connect | GrpcProtocol::ConnectJson if !response.status.is_success() => {
+            connect_error(response.status, &response.body).add_header(&mut response.headers)?;
+            // like gRPC, the failure is reported by the status headers
+            response.status = StatusCode::OK;
+            response.body = Bytes::new();
+            Ok(response)
+        }
+        GrpcProtocol::Connect => {
+            response.body = frame(&response.body).into();
+            Ok(response)
+        }
+        GrpcProtocol::ConnectJson => {
+            let mut deserializer = serde_json::Deserializer::from_slice(&response.body);
+            let message =
+                DynamicMessage::deserialize(operation.output_type.clone(), &mut deserializer)
+                    .with_context(|| {
+                        format!(
+                            "Failed to parse response for type {}",
+                            operation.output_type.full_name()
+                        )
+                    })?;
+            deserializer.end()?;
+            response.body = frame(&message.encode_to_vec()).into();
+            Ok(response)
+        }
+    }
+}
+
+/// Body of the failed responses of the Connect protocol.
+#[derive(Deserialize)]
+struct ConnectError {
+    code: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    details: Vec<ConnectErrorDetail>,
+}
+
+#[derive(Deserialize)]
+struct ConnectErrorDetail {
+    #[serde(rename = "type")]
+    type_name: String,
+    value: String,
+}
+
+fn connect_error(status: StatusCode, body: &[u8]) -> Status {
+    let Ok(error) = serde_json::from_slice::<ConnectError>(body) else {
+        let message = status.canonical_reason().unwrap_or_default();
+        return Status::new(http_code(status), message);
+    };
+
+    let code = connect_code(&error.code);
+    let details = error
+        .details
+        .iter()
+        .filter_map(|detail| {
+            let value = BASE64_STANDARD_NO_PAD
+                .decode(detail.value.trim_end_matches('='))
+                .ok()?;
+            Some(Any {
+                type_url: format!("type.googleapis.com/{}", detail.type_name),
+                value,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if details.is_empty() {
+        Status::new(code, error.message)
+    } else {
+        let details =
+            tonic_types::Status { code: code as i32, message: error.message.clone(), details };
+        Status::with_details(code, error.message, details.encode_to_vec().into())
+    }
+}
+
+/// Code of the errors of the Connect protocol.
+fn connect_code(code: &str) -> Code {
+    match code {
+        "canceled" => Code::Cancelled,
+        "invalid_argument" => Code::InvalidArgument,
+        "deadline_exceeded" => Code::DeadlineExceeded,
+        "not_found" => Code::NotFound,
+        "already_exists" => Code::AlreadyExists,
+        "permission_denied" => Code::PermissionDenied,
+        "resource_exhausted" => Code::ResourceExhausted,
+        "failed_precondition" => Code::FailedPrecondition,
+        "aborted" => Code::Aborted,
+        "out_of_range" => Code::OutOfRange,
+        "unimplemented" => Code::Unimplemented,
+        "internal" => Code::Internal,
+        "unavailable" => Code::Unavailable,
+        "data_loss" => Code::DataLoss,
+        "unauthenticated" => Code::Unauthenticated,
+        _ => Code::Unknown,
+    }
+}
+
+/// Code of the errors without a Connect body, e.g. sent by a proxy.
+fn http_code(status: StatusCode) -> Code {
+    match status.as_u16() {
+        400 => Code::Internal,
+        401 => Code::Unauthenticated,
+        403 => Code::PermissionDenied,
+        404 => Code::Unimplemented,
+        429 | 502 | 503 | 504 => Code::Unavailable,
+        _ => Code::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tailcall_fixtures::protobuf;
+
+    use super::*;
+    use crate::core::blueprint::GrpcMethod;
+    use crate::core::grpc::protobuf::ProtobufSet;
+
+    fn operation() -> ProtobufOperation {
+        let file = ProtobufSet::from_proto_file(
+            protox::compile([protobuf::GREETINGS], [protobuf::SELF]).unwrap(),
+        )
+        .unwrap();
+        let method = GrpcMethod::try_from("greetings.Greeter.SayHello").unwrap();
+        file.find_service(&method)
+            .unwrap()
+            .find_operation(&method)
+            .unwrap()
+    }
+
+    fn response(status: StatusCode, body: impl Into<Bytes>) -> Response<Bytes> {
+        Response { status, headers: HeaderMap::new(), body: body.into() }
+    }
+
+    #[test]
+    fn test_encode_request() {
+        let operation = operation();
+        let framed = operation.convert_input(r#"{"name": "test"}"#).unwrap();
+
+        let body = encode_request(GrpcProtocol::Connect, &operation, framed.clone()).unwrap();
+        assert_eq!(body, framed[5..]);
+
+        let body = encode_request(GrpcProtocol::ConnectJson, &operation, framed.clone()).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            json!({"name": "test"})
+        );
+
+        let body = encode_request(GrpcProtocol::GrpcWeb, &operation, framed.clone()).unwrap();
+        assert_eq!(body, framed);
+    }
+
+    #[test]
+    fn test_decode_grpc_web_response() {
+        let operation = operation();
+        let mut body = b"\0\0\0\0\x0e\n\x0ctest message".to_vec();
+        let trailers = b"grpc-status: 0\r\ngrpc-message: \r\n";
+        body.push(TRAILERS_FLAG);
+        body.extend((trailers.len() as u32).to_be_bytes());
+        body.extend(trailers);
+
+        let response = response(StatusCode::OK, body);
+        let response = decode_response(GrpcProtocol::GrpcWeb, &operation, response).unwrap();
+
+        assert_eq!(response.headers["grpc-status"], "0");
+        let output: serde_json::Value = operation.convert_output(&response.body).unwrap();
+        assert_eq!(output, json!({"message": "test message"}));
+    }
+
+    #[test]
+    fn test_decode_connect_response() {
+        let operation = operation();
+
+        let response = response(StatusCode::OK, &b"\n\x0ctest message"[..]);
+        let response = decode_response(GrpcProtocol::Connect, &operation, response).unwrap();
+        let output: serde_json::Value = operation.convert_output(&response.body).unwrap();
+        assert_eq!(output, json!({"message": "test message"}));
+
+        let response = self::response(StatusCode::OK, r#"{"message": "test message"}"#);
+        let response = decode_response(GrpcProtocol::ConnectJson, &operation, response).unwrap();
+        let output: serde_json::Value = operation.convert_output(&response.body).unwrap();
+        assert_eq!(output, json!({"message": "test message"}));
+    }
+
+    #[test]
+    fn test_decode_connect_error() {
+        let operation = operation();
+        let body = r#"{"code": "not_found", "message": "no such user"}"#;
+        let response = response(StatusCode::NOT_FOUND, body);
+        let response = decode_response(GrpcProtocol::ConnectJson, &operation, response).unwrap();
+
+        let status = Status::from_header_map(&response.headers).unwrap();
+        assert_eq!(status.code(), Code::NotFound);
+        assert_eq!(status.message(), "no such user");
+
+        let response = self::response(StatusCode::SERVICE_UNAVAILABLE, "");
+        let response = decode_response(GrpcProtocol::Connect, &operation, response).unwrap();
+        let status = Status::from_header_map(&response.headers).unwrap();
+        assert_eq!(status.code(), Code::Unavailable);
+    }
+}
@@ -4,6 +4,8 @@ use reqwest::Request;
 use url::Url;
 
 use super::protobuf::ProtobufOperation;
+use super::protocol;
+use crate::core::config::GrpcProtocol;
 use crate::core::http::Response;
 use crate::core::runtime::TargetRuntime;
 
@@ -20,9 +22,25 @@ pub fn create_grpc_request(url: Url, headers: HeaderMap, body: Vec<u8>) -> Reque
 pub async fn execute_grpc_request(
     runtime: &TargetRuntime,
     operation: &ProtobufOperation,
+    protocol: GrpcProtocol,
     request: Request,
 ) -> Result<Response<async_graphql::Value>> {
-    let response = runtime.http2_only.execute(request).await?;
+    let mut stream_limit = operation.stream_limit();
+    let response = match protocol {
+        GrpcProtocol::Grpc => {
+            runtime
+                .http2_only
+                .execute_inspected(request, &mut stream_limit)
+                .await?
+        }
+        _ => {
+            runtime
+                .http
+                .execute_inspected(request, &mut stream_limit)
+                .await?
+        }
+    };
+    let response = protocol::decode_response(protocol, operation, response)?;
 
     let grpc_status = response
         .headers
@@ -54,6 +72,7 @@ mod tests {
     use tonic::{Code, Status};
 
     use crate::core::blueprint::GrpcMethod;
+    use crate::core::config::GrpcProtocol;
     use crate::core::grpc::protobuf::{ProtobufOperation, ProtobufSet};
     use crate::core::grpc::request::execute_grpc_request;
     use crate::core::http::Response;
@@ -124,7 +143,7 @@ mod tests {
         let test_http = TestHttp { scenario: TestScenario::SuccessWithoutGrpcStatus };
         let (runtime, operation, request) = prepare_args(test_http).await?;
 
-        let result = execute_grpc_request(&runtime, &operation, request).await;
+        let result = execute_grpc_request(&runtime, &operation, GrpcProtocol::Grpc, request).await;
 
         assert!(
             result.is_ok(),
@@ -138,7 +157,7 @@ mod tests {
         let test_http = TestHttp { scenario: TestScenario::SuccessWithOkGrpcStatus };
         let (runtime, operation, request) = prepare_args(test_http).await?;
 
-        let result = execute_grpc_request(&runtime, &operation, request).await;
+        let result = execute_grpc_request(&runtime, &operation, GrpcProtocol::Grpc, request).await;
 
         assert!(
             result.is_ok(),
@@ -152,7 +171,7 @@ mod tests {
         let test_http = TestHttp { scenario: TestScenario::SuccessWithErrorGrpcStatus };
         let (runtime, operation, request) = prepare_args(test_http).await?;
 
-        let result = execute_grpc_request(&runtime, &operation, request).await;
+        let result = execute_grpc_request(&runtime, &operation, GrpcProtocol::Grpc, request).await;
 
         assert!(
             result.is_err(),
@@ -193,7 +212,7 @@ mod tests {
         let test_http = TestHttp { scenario: TestScenario::Error };
         let (runtime, operation, request) = prepare_args(test_http).await?;
 
-        let result = execute_grpc_request(&runtime, &operation, request).await;
+        let result = execute_grpc_request(&runtime, &operation, GrpcProtocol::Grpc, request).await;
 
         assert!(result.is_err(), "Expected error");
         assert_eq!(result.unwrap_err().to_string(), "Failed to execute request");
@@ -2,12 +2,13 @@ use std::hash::{Hash, Hasher};
 
 use anyhow::Result;
 use derive_setters::Setters;
-use http::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use http::header::{HeaderMap, HeaderValue};
 use tailcall_hasher::TailcallHasher;
 use url::Url;
 
+use super::protocol;
 use super::request::create_grpc_request;
-use crate::core::config::GraphQLOperationType;
+use crate::core::config::{GraphQLOperationType, GrpcProtocol};
 use crate::core::grpc::protobuf::ProtobufOperation;
 use crate::core::has_headers::HasHeaders;
 use crate::core::helpers::headers::MustacheHeaders;
@@ -15,8 +16,6 @@ use crate::core::ir::model::{CacheKey, IoId};
 use crate::core::mustache::Mustache;
 use crate::core::path::PathString;
 
-static GRPC_MIME_TYPE: HeaderValue = HeaderValue::from_static("application/grpc");
-
 #[derive(Setters, Debug, Clone)]
 pub struct RequestTemplate {
     pub url: Mustache,
@@ -24,6 +23,7 @@ pub struct RequestTemplate {
     pub body: Option<RequestBody>,
     pub operation: ProtobufOperation,
     pub operation_type: GraphQLOperationType,
+    pub protocol: GrpcProtocol,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Setters)]
@@ -48,6 +48,7 @@ pub struct RenderedRequestTemplate {
     pub headers: HeaderMap,
     pub body: String,
     pub operation: ProtobufOperation,
+    pub protocol: GrpcProtocol,
 }
 
 impl Hash for RenderedRequestTemplate {
@@ -65,9 +66,7 @@ impl RequestTemplate {
     }
 
     fn create_headers<C: PathString>(&self, ctx: &C) -> HeaderMap {
-        let mut header_map = HeaderMap::new();
-
-        header_map.insert(CONTENT_TYPE, GRPC_MIME_TYPE.to_owned());
+        let mut header_map = protocol::headers(self.protocol);
 
         for (k, v) in &self.headers {
             if let Ok(header_value) = HeaderValue::from_str(&v.render(ctx)) {
@@ -82,7 +81,13 @@ impl RequestTemplate {
         let url = self.create_url(ctx)?;
         let headers = self.render_headers(ctx);
         let body = self.render_body(ctx);
-        Ok(RenderedRequestTemplate { url, headers, body, operation: self.operation.clone() })
+        Ok(RenderedRequestTemplate {
+            url,
+            headers,
+            body,
+            operation: self.operation.clone(),
+            protocol: self.protocol,
+        })
     }
 
     fn render_body<C: PathString + HasHeaders>(&self, ctx: &C) -> String {
@@ -109,10 +114,16 @@ impl RequestTemplate {
 
 impl RenderedRequestTemplate {
     pub fn to_request(&self) -> Result<reqwest::Request> {
+        let body = protocol::encode_request(
+            self.protocol,
+            &self.operation,
+            self.operation.convert_input(self.body.as_str())?,
+        )?;
+
         Ok(create_grpc_request(
             self.url.clone(),
             self.headers.clone(),
-            self.operation.convert_input(self.body.as_str())?,
+            body,
         ))
     }
 }
@@ -141,7 +152,7 @@ mod tests {
     use crate::core::blueprint::GrpcMethod;
     use crate::core::config::reader::ConfigReader;
     use crate::core::config::{
-        Config, Field, GraphQLOperationType, Grpc, Link, LinkType, Resolver, Type,
+        Config, Field, GraphQLOperationType, Grpc, GrpcProtocol, Link, LinkType, Resolver, Type,
     };
     use crate::core::grpc::protobuf::{ProtobufOperation, ProtobufSet};
     use crate::core::ir::model::CacheKey;
@@ -227,6 +238,7 @@ mod tests {
             operation: get_protobuf_op().await,
             body: None,
             operation_type: GraphQLOperationType::Query,
+            protocol: GrpcProtocol::Grpc,
         };
         let ctx = Context::default();
         let rendered = tmpl.render(&ctx).unwrap();
@@ -264,6 +276,7 @@ mod tests {
                 value: Default::default(),
             }),
             operation_type: GraphQLOperationType::Query,
+            protocol: GrpcProtocol::Grpc,
         };
         let ctx = Context::default();
         let rendered = tmpl.render(&ctx).unwrap();
@@ -274,6 +287,23 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn request_with_connect_json_protocol() {
+        let tmpl = RequestTemplate {
+            protocol: GrpcProtocol::ConnectJson,
+            ..request_template_with_body(r#"{ "name": "test" }"#).await
+        };
+        let ctx = Context::default();
+        let rendered = tmpl.render(&ctx).unwrap();
+        let req = rendered.to_request().unwrap();
+
+        assert_eq!(req.headers()["content-type"], "application/json");
+        assert_eq!(req.headers()["connect-protocol-version"], "1");
+        if let Some(body) = req.body() {
+            assert_eq!(body.as_bytes(), Some(br#"{"name":"test"}"#.as_ref()))
+        }
+    }
+
     async fn request_template_with_body(body_str: &str) -> RequestTemplate {
         RequestTemplate {
             url: Mustache::parse("http://localhost:3000/"),
@@ -284,6 +314,7 @@ mod tests {
                 value: Default::default(),
             }),
             operation_type: GraphQLOperationType::Query,
+            protocol: GrpcProtocol::Grpc,
         }
     }
 
@@ -0,0 +1,437 @@
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use hyper::body::Bytes;
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+use tailcall_hasher::TailcallHasher;
+use tracing::level_filters::LevelFilter;
+
+use crate::core::app_context::AppContext;
+use crate::core::cache::CacheStats;
+use crate::core::config::Admin;
+use crate::core::ir::model::IoId;
+use crate::core::tracing::{log_level, set_log_level};
+use crate::core::Cache;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PurgeRequest {
+    /// Cache tags as rendered from `@cache(tags: ...)`
+    tags: Vec<String>,
+    /// Cache keys, as shown by the explain mode and the logs
+    keys: Vec<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct PurgeResponse {
+    purged: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ConfigResponse<'a> {
+    /// Hash of the blueprint the instance serves
+    hash: String,
+    config: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatsResponse {
+    cache: Option<CacheStats>,
+    response_cache: Option<CacheStats>,
+    data_loaders: DataLoaderStats,
+    cache_refreshes: usize,
+    operation_plans: usize,
+    connection_pool_config: ConnectionPoolConfig,
+}
+
+#[derive(Debug, Serialize)]
+struct DataLoaderStats {
+    http: usize,
+    graphql: usize,
+    grpc: usize,
+}
+
+/// Settings of the upstream connection pool, the HTTP client doesn't expose
+/// the state of its pool.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectionPoolConfig {
+    idle_timeout: u64,
+    max_idle_per_host: usize,
+    http2_only: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LogLevel {
+    level: String,
+}
+
+fn is_authorized(req: &Request<Body>, admin: &Admin) -> bool {
+    req.headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        // compared in constant time so the token can't be guessed from timings
+        .is_some_and(|token| bool::from(token.as_bytes().ct_eq(admin.token().as_bytes())))
+}
+
+fn json_response<T: Serialize>(status: StatusCode, body: &T) -> Result<Response<Body>> {
+    Ok(Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(serde_json::to_vec(body)?))?)
+}
+
+fn error_response(status: StatusCode, message: &str) -> Result<Response<Body>> {
+    json_response(status, &serde_json::json!({ "message": message }))
+}
+
+/// Reads the body within the body size limit of the server.
+async fn read_body(req: Request<Body>, app_ctx: &AppContext) -> Result<Option<Bytes>> {
+    let max_body_size = *app_ctx.blueprint.server.limits.max_body_size();
+    super::request_handler::read_body(req.into_body(), max_body_size).await
+}
+
+fn payload_too_large() -> Result<Response<Body>> {
+    error_response(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        "Request body exceeds the limit",
+    )
+}
+
+async fn purge_cache(req: Request<Body>, app_ctx: &AppContext) -> Result<Response<Body>> {
+    let Some(bytes) = read_body(req, app_ctx).await? else {
+        return payload_too_large();
+    };
+    let purge: PurgeRequest = match serde_json::from_slice(&bytes) {
+        Ok(purge) => purge,
+        Err(err) => return error_response(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let cache = &app_ctx.runtime.cache;
+    let mut purged = cache
+        .purge_tags(&purge.tags)
+        .await
+        .map_err(|e| anyhow!(e))?;
+    for key in purge.keys {
+        if cache.purge(&IoId::new(key)).await.map_err(|e| anyhow!(e))? {
+            purged += 1;
+        }
+    }
+
+    tracing::info!("Purged {} cached entries", purged);
+    json_response(StatusCode::OK, &PurgeResponse { purged })
+}
+
+fn config(app_ctx: &AppContext) -> Result<Response<Body>> {
+    let config = app_ctx.config.as_deref();
+    // the blueprint holds what the config resolves to, including the linked
+    // files and the secrets left out of the SDL above
+    let mut hasher = TailcallHasher::default();
+    format!("{:?}", app_ctx.blueprint).hash(&mut hasher);
+    let hash = format!("{:x}", hasher.finish());
+
+    json_response(StatusCode::OK, &ConfigResponse { hash, config })
+}
+
+fn stats(app_ctx: &AppContext) -> Result<Response<Body>> {
+    let upstream = &app_ctx.blueprint.upstream;
+    let stats = StatsResponse {
+        cache: app_ctx.runtime.cache.stats(),
+        response_cache: app_ctx
+            .response_cache
+            .as_ref()
+            .and_then(|cache| cache.stats()),
+        data_loaders: DataLoaderStats {
+            http: app_ctx.http_data_loaders.len(),
+            graphql: app_ctx.gql_data_loaders.len(),
+            grpc: app_ctx.grpc_data_loaders.len(),
+        },
+        cache_refreshes: app_ctx.cache_refreshes.len(),
+        operation_plans: app_ctx.operation_plans.len(),
+        connection_pool_config: ConnectionPoolConfig {
+            idle_timeout: upstream.pool_idle_timeout,
+            max_idle_per_host: upstream.pool_max_idle_per_host,
+            http2_only: upstream.http2_only,
+        },
+    };
+
+    json_response(StatusCode::OK, &stats)
+}
+
+async fn clear_caches(app_ctx: &AppContext) -> Result<Response<Body>> {
+    app_ctx
+        .runtime
+        .cache
+        .clear()
+        .await
+        .map_err(|e| anyhow!(e))?;
+    if let Some(response_cache) = app_ctx.response_cache.as_ref() {
+        response_cache.clear().await.map_err(|e| anyhow!(e))?;
+    }
+    app_ctx.const_execution_cache.clear();
+    app_ctx.operation_plans.clear();
+
+    tracing::info!("Cleared the caches");
+    json_response(
+        StatusCode::OK,
+        &serde_json::json!({ "message": "Caches cleared" }),
+    )
+}
+
+fn get_log_level() -> Result<Response<Body>> {
+    let level = log_level().to_string().to_lowercase();
+    json_response(StatusCode::OK, &LogLevel { level })
+}
+
+async fn update_log_level(req: Request<Body>, app_ctx: &AppContext) -> Result<Response<Body>> {
+    let Some(bytes) = read_body(req, app_ctx).await? else {
+        return payload_too_large();
+    };
+    let level = serde_json::from_slice::<LogLevel>(&bytes)
+        .map_err(|err| err.to_string())
+        .and_then(|body| LevelFilter::from_str(&body.level).map_err(|err| err.to_string()));
+    let level = match level {
+        Ok(level) => level,
+        Err(err) => return error_response(StatusCode::BAD_REQUEST, &err),
+    };
+
+    set_log_level(level);
+    tracing::info!("Log level changed to {}", level);
+    get_log_level()
+}
+
+/// Returns the route relative to the admin path if the path is under it.
+fn admin_route<'a>(path: &'a str, admin: &Admin) -> Option<&'a str> {
+    path.strip_prefix(admin.path().as_str())
+        .filter(|route| route.is_empty() || route.starts_with('/'))
+}
+
+pub fn is_admin_request(req: &Request<Body>, admin: &Admin) -> bool {
+    admin_route(req.uri().path(), admin).is_some()
+}
+
+/// Handles the requests under the admin path, returns `None` if the route
+/// doesn't exist.
+pub async fn handle_request(
+    req: Request<Body>,
+    app_ctx: Arc<AppContext>,
+    admin: &Admin,
+) -> Result<Option<Response<Body>>> {
+    let Some(route) = admin_route(req.uri().path(), admin) else {
+        return Ok(None);
+    };
+
+    if !is_authorized(&req, admin) {
+        return error_response(StatusCode::UNAUTHORIZED, "Invalid admin token").map(Some);
+    }
+
+    match (req.method(), route) {
+        (&Method::GET, "/config") => config(&app_ctx).map(Some),
+        (&Method::GET, "/stats") => stats(&app_ctx).map(Some),
+        (&Method::POST, "/cache/purge") => purge_cache(req, &app_ctx).await.map(Some),
+        (&Method::POST, "/cache/clear") => clear_caches(&app_ctx).await.map(Some),
+        (&Method::GET, "/log-level") => get_log_level().map(Some),
+        (&Method::PUT, "/log-level") => update_log_level(req, &app_ctx).await.map(Some),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use tailcall_valid::Validator;
+
+    use super::*;
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{Config, ConfigModule};
+    use crate::core::rest::EndpointSet;
+    use crate::core::runtime::test::init;
+
+    fn blueprint() -> Blueprint {
+        let sdl = r#"
+            schema {
+                query: Query
+            }
+
+            type Query {
+                foo: String @expr(body: "foo")
+            }
+        "#;
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+        Blueprint::try_from(&ConfigModule::from(config)).unwrap()
+    }
+
+    fn app_ctx() -> Arc<AppContext> {
+        Arc::new(AppContext::new(
+            blueprint(),
+            init(None),
+            EndpointSet::default(),
+        ))
+    }
+
+    fn admin_request(method: Method, route: &str, token: &str, body: &str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(format!("http://localhost/admin{}", route))
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    fn purge_request(token: &str, body: &str) -> Request<Body> {
+        admin_request(Method::POST, "/cache/purge", token, body)
+    }
+
+    async fn json_body(response: Response<Body>) -> serde_json::Value {
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_purge_cache() {
+        let app_ctx = app_ctx();
+        let admin = Admin::new("secret");
+        let ttl = NonZeroU64::new(10000).unwrap();
+        let cache = &app_ctx.runtime.cache;
+        cache.set(IoId::new(1), "first".into(), ttl).await.unwrap();
+        cache.set(IoId::new(2), "second".into(), ttl).await.unwrap();
+        cache
+            .tag(&IoId::new(1), vec!["user:1".to_string()])
+            .await
+            .unwrap();
+
+        let unauthorized = purge_request("wrong", r#"{"tags": ["user:1"]}"#);
+        let response = handle_request(unauthorized, app_ctx.clone(), &admin)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let request = purge_request("secret", r#"{"tags": ["user:1"], "keys": [2]}"#);
+        let response = handle_request(request, app_ctx.clone(), &admin)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body.as_ref(), br#"{"purged":2}"#);
+        assert_eq!(cache.get(&IoId::new(1)).await.unwrap(), None);
+        assert_eq!(cache.get(&IoId::new(2)).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_body_limit() {
+        let mut blueprint = blueprint();
+        blueprint.server.limits =
+            serde_json::from_value(serde_json::json!({"maxBodySize": 8})).unwrap();
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+        let admin = Admin::new("secret");
+
+        let request = purge_request("secret", r#"{"tags": ["user:1"]}"#);
+        let response = handle_request(request, app_ctx, &admin)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_stats_and_clear_caches() {
+        let app_ctx = app_ctx();
+        let admin = Admin::new("secret");
+        let ttl = NonZeroU64::new(10000).unwrap();
+        let cache = &app_ctx.runtime.cache;
+        cache.set(IoId::new(1), "first".into(), ttl).await.unwrap();
+        cache.get(&IoId::new(1)).await.unwrap();
+        cache.get(&IoId::new(2)).await.unwrap();
+
+        let request = admin_request(Method::GET, "/stats", "secret", "");
+        let response = handle_request(request, app_ctx.clone(), &admin)
+            .await
+            .unwrap()
+            .unwrap();
+        let stats = json_body(response).await;
+        assert_eq!(stats["cache"], serde_json::json!({"hits": 1, "misses": 1}));
+
+        let request = admin_request(Method::POST, "/cache/clear", "secret", "");
+        let response = handle_request(request, app_ctx.clone(), &admin)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(cache.get(&IoId::new(1)).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_config_hash() {
+        let admin = Admin::new("secret");
+        let mut hashes = vec![];
+        for port in [8000, 8001, 8000] {
+            let mut blueprint = blueprint();
+            blueprint.server.port = port;
+            let app_ctx = Arc::new(AppContext::new(
+                blueprint,
+                init(None),
+                EndpointSet::default(),
+            ));
+
+            let request = admin_request(Method::GET, "/config", "secret", "");
+            let response = handle_request(request, app_ctx, &admin)
+                .await
+                .unwrap()
+                .unwrap();
+            hashes.push(json_body(response).await["hash"].clone());
+        }
+        assert_ne!(hashes[0], hashes[1]);
+        assert_eq!(hashes[0], hashes[2]);
+    }
+
+    /// Restores the log level when the test ends, even if it fails.
+    struct RestoreLogLevel(LevelFilter);
+
+    impl Drop for RestoreLogLevel {
+        fn drop(&mut self) {
+            set_log_level(self.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_log_level() {
+        let app_ctx = app_ctx();
+        let admin = Admin::new("secret");
+        let _restore = RestoreLogLevel(log_level());
+
+        let request = admin_request(
+            Method::PUT,
+            "/log-level",
+            "secret",
+            r#"{"level": "verbose"}"#,
+        );
+        let response = handle_request(request, app_ctx.clone(), &admin)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let request = admin_request(Method::PUT, "/log-level", "secret", r#"{"level": "debug"}"#);
+        let response = handle_request(request, app_ctx.clone(), &admin)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(json_body(response).await["level"], "debug");
+        assert_eq!(log_level(), LevelFilter::DEBUG);
+    }
+}
@@ -5,10 +5,11 @@ use http::HeaderValue;
 pub use method::Method;
 pub use query_encoder::QueryEncoder;
 pub use request_context::RequestContext;
-pub use request_handler::{handle_request, API_URL_PREFIX};
+pub use request_handler::{handle_listener_request, handle_request, request_route, API_URL_PREFIX};
 pub use request_template::RequestTemplate;
 pub use response::*;
 
+mod admin;
 mod cache;
 mod data_loader;
 mod data_loader_request;
@@ -4,6 +4,7 @@ use std::sync::{Arc, Mutex};
 
 use async_graphql_value::ConstValue;
 use cache_control::{Cachability, CacheControl};
+use dashmap::DashSet;
 use derive_setters::Setters;
 use http::header::{HeaderMap, HeaderName, HeaderValue};
 
@@ -27,6 +28,8 @@ pub struct RequestContext {
     // A subset of all the headers received in the GraphQL Request that will be sent to the
     // upstream.
     pub allowed_headers: HeaderMap,
+    // Headers of the GraphQL Request that are part of the cache keys.
+    pub vary_headers: HeaderMap,
     pub http_data_loaders: Arc<Vec<DataLoader<DataLoaderRequest, HttpDataLoader>>>,
     pub gql_data_loaders: Arc<Vec<DataLoader<DataLoaderRequest, GraphqlDataLoader>>>,
     pub grpc_data_loaders: Arc<Vec<DataLoader<grpc::DataLoaderRequest, GrpcDataLoader>>>,
@@ -35,6 +38,14 @@ pub struct RequestContext {
     pub runtime: TargetRuntime,
     pub cache: DedupeResult<IoId, ConstValue, Error>,
     pub dedupe_handler: Arc<DedupeResult<IoId, ConstValue, Error>>,
+    /// Keys of the cached values that are being refreshed in the background
+    pub cache_refreshes: Arc<DashSet<IoId>>,
+    /// Set when a stale cached value was used to build the response
+    #[setters(skip)]
+    pub is_stale: Arc<Mutex<bool>>,
+    /// Subject of the JWT verified for the request
+    #[setters(skip)]
+    pub jwt_subject: Arc<Mutex<Option<String>>>,
 }
 
 impl RequestContext {
@@ -52,9 +63,53 @@ impl RequestContext {
             runtime: target_runtime,
             cache: DedupeResult::new(true),
             dedupe_handler: Arc::new(DedupeResult::new(false)),
+            cache_refreshes: Arc::new(DashSet::new()),
+            is_stale: Arc::new(Mutex::new(false)),
+            jwt_subject: Arc::new(Mutex::new(None)),
             allowed_headers: HeaderMap::new(),
+            vary_headers: HeaderMap::new(),
         }
     }
+
+    /// Creates a context for work that outlives the request, e.g. a background
+    /// cache refresh. Headers set by the upstream responses are not forwarded.
+    pub fn detach(&self) -> RequestContext {
+        RequestContext {
+            server: self.server.clone(),
+            upstream: self.upstream.clone(),
+            x_response_headers: Arc::new(Mutex::new(HeaderMap::new())),
+            cookie_headers: None,
+            allowed_headers: self.allowed_headers.clone(),
+            vary_headers: self.vary_headers.clone(),
+            http_data_loaders: self.http_data_loaders.clone(),
+            gql_data_loaders: self.gql_data_loaders.clone(),
+            grpc_data_loaders: self.grpc_data_loaders.clone(),
+            min_max_age: Arc::new(Mutex::new(None)),
+            cache_public: Arc::new(Mutex::new(None)),
+            runtime: self.runtime.clone(),
+            cache: DedupeResult::new(true),
+            dedupe_handler: self.dedupe_handler.clone(),
+            cache_refreshes: self.cache_refreshes.clone(),
+            is_stale: Arc::new(Mutex::new(false)),
+            jwt_subject: self.jwt_subject.clone(),
+        }
+    }
+
+    pub fn set_stale(&self) {
+        *self.is_stale.lock().unwrap() = true;
+    }
+
+    pub fn is_stale(&self) -> bool {
+        *self.is_stale.lock().unwrap()
+    }
+
+    pub fn set_jwt_subject(&self, subject: String) {
+        *self.jwt_subject.lock().unwrap() = Some(subject);
+    }
+
+    pub fn jwt_subject(&self) -> Option<String> {
+        self.jwt_subject.lock().unwrap().clone()
+    }
     fn set_min_max_age_conc(&self, min_max_age: i32) {
         *self.min_max_age.lock().unwrap() = Some(min_max_age);
     }
@@ -193,6 +248,7 @@ impl From<&AppContext> for RequestContext {
             x_response_headers: Arc::new(Mutex::new(HeaderMap::new())),
             cookie_headers,
             allowed_headers: HeaderMap::new(),
+            vary_headers: HeaderMap::new(),
             http_data_loaders: app_ctx.http_data_loaders.clone(),
             gql_data_loaders: app_ctx.gql_data_loaders.clone(),
             grpc_data_loaders: app_ctx.grpc_data_loaders.clone(),
@@ -201,6 +257,9 @@ impl From<&AppContext> for RequestContext {
             runtime: app_ctx.runtime.clone(),
             cache: DedupeResult::new(true),
             dedupe_handler: app_ctx.dedupe_handler.clone(),
+            cache_refreshes: app_ctx.cache_refreshes.clone(),
+            is_stale: Arc::new(Mutex::new(false)),
+            jwt_subject: Arc::new(Mutex::new(None)),
         }
     }
 }
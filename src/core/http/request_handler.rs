@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use async_graphql::ServerError;
+use hyper::body::{Bytes, HttpBody};
 use hyper::header::{self, HeaderValue, CONTENT_TYPE};
 use hyper::http::request::Parts;
 use hyper::http::Method;
@@ -17,14 +18,18 @@ use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use super::request_context::RequestContext;
 use super::telemetry::{get_response_status_code, RequestCounter};
-use super::{showcase, telemetry, TAILCALL_HTTPS_ORIGIN, TAILCALL_HTTP_ORIGIN};
+use super::{admin, showcase, telemetry, TAILCALL_HTTPS_ORIGIN, TAILCALL_HTTP_ORIGIN};
 use crate::core::app_context::AppContext;
-use crate::core::async_graphql_hyper::{GraphQLRequestLike, GraphQLResponse};
+use crate::core::async_graphql_hyper::{variables_depth, GraphQLRequestLike, GraphQLResponse};
 use crate::core::blueprint::telemetry::TelemetryExporter;
-use crate::core::config::{PrometheusExporter, PrometheusFormat};
+use crate::core::blueprint::Listener;
+use crate::core::config::{Limits, ListenerRoute, PrometheusExporter, PrometheusFormat};
 use crate::core::jit::JITExecutor;
+use crate::core::rest::OPENAPI_PATH;
+use crate::core::rpc::{self, Gateway};
 
 pub const API_URL_PREFIX: &str = "/api";
+const SHOWCASE_PATH: &str = "/showcase/graphql";
 
 fn prometheus_metrics(prometheus_exporter: &PrometheusExporter) -> Result<Response<Body>> {
     let metric_families = prometheus::default_registry().gather();
@@ -54,10 +59,72 @@ fn not_found() -> Result<Response<Body>> {
         .body(Body::empty())?)
 }
 
+fn payload_too_large() -> Result<Response<Body>> {
+    Ok(Response::builder()
+        .status(StatusCode::PAYLOAD_TOO_LARGE)
+        .body(Body::empty())?)
+}
+
+/// Rejects the request if its headers or declared body exceed the limits.
+fn check_limits(req: &Request<Body>, limits: &Limits) -> Option<Result<Response<Body>>> {
+    if let Some(max_header_size) = limits.max_header_size() {
+        let header_size: usize = req
+            .headers()
+            .iter()
+            .map(|(name, value)| name.as_str().len() + value.len())
+            .sum();
+        if header_size > *max_header_size {
+            return Some(
+                Response::builder()
+                    .status(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE)
+                    .body(Body::empty())
+                    .map_err(Into::into),
+            );
+        }
+    }
+
+    if let Some(max_body_size) = limits.max_body_size() {
+        let content_length = req
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<usize>().ok());
+        if content_length.is_some_and(|length| length > *max_body_size) {
+            return Some(payload_too_large());
+        }
+    }
+
+    None
+}
+
+/// Reads the body, `None` if it's larger than `max_size`. The body is read in
+/// chunks so that oversized requests without a `Content-Length` are cut off
+/// without being buffered completely.
+pub(super) async fn read_body(mut body: Body, max_size: Option<usize>) -> Result<Option<Bytes>> {
+    let Some(max_size) = max_size else {
+        return Ok(Some(hyper::body::to_bytes(body).await?));
+    };
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if bytes.len() + chunk.len() > max_size {
+            return Ok(None);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(Some(Bytes::from(bytes)))
+}
+
 fn create_request_context(req: &Request<Body>, app_ctx: &AppContext) -> RequestContext {
     let allowed_headers =
         create_allowed_headers(req.headers(), &app_ctx.blueprint.upstream.allowed_headers);
-    RequestContext::from(app_ctx).allowed_headers(allowed_headers)
+    let vary_headers =
+        create_allowed_headers(req.headers(), &app_ctx.blueprint.server.cache_vary_headers);
+    RequestContext::from(app_ctx)
+        .allowed_headers(allowed_headers)
+        .vary_headers(vary_headers)
 }
 
 pub fn update_response_headers(
@@ -90,7 +157,18 @@ pub async fn graphql_request<T: DeserializeOwned + GraphQLRequestLike>(
     req_counter.set_http_route("/graphql");
     let req_ctx = Arc::new(create_request_context(&req, app_ctx));
     let (req, body) = req.into_parts();
-    let bytes = hyper::body::to_bytes(body).await?;
+    let bytes = if req.method == Method::GET {
+        match query_string_to_body(req.uri.query()) {
+            Ok(bytes) => Bytes::from(bytes),
+            Err(err) => return graphql_error(format!("Invalid GET request: {}", err)),
+        }
+    } else {
+        let max_body_size = *app_ctx.blueprint.server.limits.max_body_size();
+        match read_body(body, max_body_size).await? {
+            Some(bytes) => bytes,
+            None => return payload_too_large(),
+        }
+    };
     let bytes = if req.headers.get("content-type")
         == Some(&HeaderValue::from_str("application/graphql")?)
     {
@@ -104,9 +182,31 @@ pub async fn graphql_request<T: DeserializeOwned + GraphQLRequestLike>(
     } else {
         bytes
     };
+    let limits = &app_ctx.blueprint.server.limits;
+    if let Some(max_variables_depth) = limits.max_variables_depth() {
+        if variables_depth(&bytes) > *max_variables_depth {
+            return graphql_error(format!(
+                "Variables depth exceeds the limit of {}",
+                max_variables_depth
+            ));
+        }
+    }
     let graphql_request = serde_json::from_slice::<T>(&bytes);
     match graphql_request {
-        Ok(request) => {
+        Ok(mut request) => {
+            if let Some(max_batch_length) = limits.max_batch_length() {
+                if request.batch_len() > *max_batch_length {
+                    return graphql_error(format!(
+                        "Batch length exceeds the limit of {}",
+                        max_batch_length
+                    ));
+                }
+            }
+
+            // GET requests must not have side effects
+            if req.method == Method::GET && !request.is_query() {
+                return graphql_error("Only queries are allowed in GET requests".to_string());
+            }
             let resp = execute_query(app_ctx, &req_ctx, request, req).await?;
             Ok(resp)
         }
@@ -126,6 +226,40 @@ pub async fn graphql_request<T: DeserializeOwned + GraphQLRequestLike>(
     }
 }
 
+fn graphql_error(message: String) -> Result<Response<Body>> {
+    let mut response = async_graphql::Response::default();
+    response.errors = vec![ServerError::new(message, None)];
+
+    GraphQLResponse::from(response).into_response()
+}
+
+/// Converts the query string of a GET request into the body of a POST request.
+/// `variables` and `extensions` are expected to be JSON encoded.
+fn query_string_to_body(query: Option<&str>) -> Result<Vec<u8>> {
+    let mut body = serde_json::Map::new();
+    for (key, value) in url::form_urlencoded::parse(query.unwrap_or_default().as_bytes()) {
+        let value = match key.as_ref() {
+            "variables" | "extensions" => serde_json::from_str(&value)?,
+            _ => serde_json::Value::String(value.into_owned()),
+        };
+        body.insert(key.into_owned(), value);
+    }
+
+    Ok(serde_json::to_vec(&body)?)
+}
+
+fn is_not_modified(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+        })
+}
+
 async fn execute_query<T: DeserializeOwned + GraphQLRequestLike>(
     app_ctx: &Arc<AppContext>,
     req_ctx: &Arc<RequestContext>,
@@ -134,15 +268,33 @@ async fn execute_query<T: DeserializeOwned + GraphQLRequestLike>(
 ) -> anyhow::Result<Response<Body>> {
     let operation_id = request.operation_id(&req.headers);
     let exec = JITExecutor::new(app_ctx.clone(), req_ctx.clone(), operation_id);
-    let mut response = request
-        .execute_with_jit(exec)
-        .await
-        .set_cache_control(
-            app_ctx.blueprint.server.enable_cache_control_header,
-            req_ctx.get_min_max_age().unwrap_or(0),
-            req_ctx.is_cache_public().unwrap_or(true),
-        )
-        .into_response()?;
+    let response = request.execute_with_jit(exec).await.set_cache_control(
+        app_ctx.blueprint.server.enable_cache_control_header,
+        req_ctx.get_min_max_age().unwrap_or(0),
+        req_ctx.is_cache_public().unwrap_or(true),
+    );
+
+    let etag = app_ctx
+        .blueprint
+        .server
+        .response_cache
+        .is_some()
+        .then(|| response.etag());
+
+    let mut response = match etag.as_ref() {
+        Some(etag) if req.method == Method::GET && is_not_modified(&req.headers, etag) => {
+            Response::builder()
+                .status(StatusCode::NOT_MODIFIED)
+                .body(Body::empty())?
+        }
+        _ => response.into_response()?,
+    };
+
+    if let Some(etag) = etag {
+        response
+            .headers_mut()
+            .insert(header::ETAG, HeaderValue::from_str(&etag)?);
+    }
 
     update_response_headers(&mut response, req_ctx, app_ctx);
     Ok(response)
@@ -264,8 +416,18 @@ async fn handle_rest_apis(
             { HTTP_ROUTE } = http_route
         );
         return async {
-            let graphql_request = p_request.into_request(request).await?;
-            let mut response = graphql_request
+            let options = p_request.response;
+            let body = if p_request.body.is_some() {
+                let max_body_size = *app_ctx.blueprint.server.limits.max_body_size();
+                match read_body(request.into_body(), max_body_size).await? {
+                    Some(body) => body,
+                    None => return payload_too_large(),
+                }
+            } else {
+                Bytes::new()
+            };
+            let graphql_request = p_request.into_request(&body)?;
+            let response = graphql_request
                 .data(req_ctx.clone())
                 .execute(&app_ctx.schema)
                 .await
@@ -273,27 +435,89 @@ async fn handle_rest_apis(
                     app_ctx.blueprint.server.enable_cache_control_header,
                     req_ctx.get_min_max_age().unwrap_or(0),
                     req_ctx.is_cache_public().unwrap_or(true),
-                )
-                .into_rest_response()?;
+                );
+            let mut response = options.to_response(response)?;
             update_response_headers(&mut response, &req_ctx, &app_ctx);
+            options.extend_headers(response.headers_mut());
             Ok(response)
         }
         .instrument(span)
         .await;
     }
 
+    if request.method() == Method::GET && request.uri().path() == OPENAPI_PATH {
+        let document = app_ctx.endpoints.to_openapi(&app_ctx.blueprint);
+        return Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_vec(&document)?))?);
+    }
+
     not_found()
 }
 
+async fn handle_rpc(
+    request: Request<Body>,
+    app_ctx: Arc<AppContext>,
+    gateway: &Gateway,
+    req_counter: &mut RequestCounter,
+) -> Result<Response<Body>> {
+    let path = request.uri().path().to_string();
+    req_counter.set_http_route(&path);
+    let req_ctx = Arc::new(create_request_context(&request, app_ctx.as_ref()));
+    let span = tracing::info_span!(
+        "gRPC",
+        otel.name = format!("gRPC {}", path),
+        otel.kind = ?SpanKind::Server,
+        { HTTP_REQUEST_METHOD } = %request.method(),
+        { HTTP_ROUTE } = path.as_str()
+    );
+    async {
+        let max_body_size = *app_ctx.blueprint.server.limits.max_body_size();
+        let Some(body) = read_body(request.into_body(), max_body_size).await? else {
+            let status = tonic::Status::resource_exhausted("Request body exceeds the limit");
+            return rpc::status_response(&status);
+        };
+        let (graphql_request, operation) = match gateway.to_request(&path, &body) {
+            Ok(call) => call,
+            Err(status) => return rpc::status_response(&status),
+        };
+        let response = graphql_request
+            .data(req_ctx.clone())
+            .execute(&app_ctx.schema)
+            .await;
+        let mut response = rpc::to_response(operation, response)?;
+        update_response_headers(&mut response, &req_ctx, &app_ctx);
+        Ok(response)
+    }
+    .instrument(span)
+    .await
+}
+
 async fn handle_request_inner<T: DeserializeOwned + GraphQLRequestLike>(
     req: Request<Body>,
     app_ctx: Arc<AppContext>,
     req_counter: &mut RequestCounter,
 ) -> Result<Response<Body>> {
+    if let Some(gateway) = app_ctx.rpc.clone() {
+        if gateway.is_grpc_request(&req) {
+            return handle_rpc(req, app_ctx, &gateway, req_counter).await;
+        }
+    }
+
     if req.uri().path().starts_with(API_URL_PREFIX) {
         return handle_rest_apis(req, app_ctx, req_counter).await;
     }
 
+    if let Some(admin) = app_ctx.blueprint.server.admin.clone() {
+        if admin::is_admin_request(&req, &admin) {
+            return match admin::handle_request(req, app_ctx, &admin).await? {
+                Some(response) => Ok(response),
+                None => not_found(),
+            };
+        }
+    }
+
     let health_check_endpoint = app_ctx.blueprint.server.routes.status();
     let graphql_endpoint = app_ctx.blueprint.server.routes.graphql();
 
@@ -304,9 +528,14 @@ async fn handle_request_inner<T: DeserializeOwned + GraphQLRequestLike>(
         Method::POST if req.uri().path() == graphql_endpoint => {
             graphql_request::<T>(req, &app_ctx, req_counter).await
         }
+        Method::GET
+            if app_ctx.blueprint.server.response_cache.is_some()
+                && req.uri().path() == graphql_endpoint =>
+        {
+            graphql_request::<T>(req, &app_ctx, req_counter).await
+        }
         Method::POST
-            if app_ctx.blueprint.server.enable_showcase
-                && req.uri().path() == "/showcase/graphql" =>
+            if app_ctx.blueprint.server.enable_showcase && req.uri().path() == SHOWCASE_PATH =>
         {
             let app_ctx =
                 match showcase::create_app_ctx::<T>(&req, app_ctx.runtime.clone(), false).await? {
@@ -317,10 +546,18 @@ async fn handle_request_inner<T: DeserializeOwned + GraphQLRequestLike>(
             graphql_request::<T>(req, &Arc::new(app_ctx), req_counter).await
         }
         Method::GET if req.uri().path() == health_check_endpoint => {
+            let (status, message) = if app_ctx.is_draining() {
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    r#"{"message": "draining"}"#,
+                )
+            } else {
+                (StatusCode::OK, r#"{"message": "ready"}"#)
+            };
             let status_response = Response::builder()
-                .status(StatusCode::OK)
+                .status(status)
                 .header(CONTENT_TYPE, "application/json")
-                .body(Body::from(r#"{"message": "ready"}"#))?;
+                .body(Body::from(message))?;
             Ok(status_response)
         }
         Method::GET => {
@@ -354,7 +591,9 @@ pub async fn handle_request<T: DeserializeOwned + GraphQLRequestLike>(
     telemetry::propagate_context(&req);
     let mut req_counter = RequestCounter::new(&app_ctx.blueprint.telemetry, &req);
 
-    let response = if app_ctx.blueprint.server.cors.is_some() {
+    let response = if let Some(response) = check_limits(&req, &app_ctx.blueprint.server.limits) {
+        response
+    } else if app_ctx.blueprint.server.cors.is_some() {
         handle_request_with_cors::<T>(req, app_ctx, &mut req_counter).await
     } else if let Some(origin) = req.headers().get(&header::ORIGIN) {
         if origin == TAILCALL_HTTPS_ORIGIN || origin == TAILCALL_HTTP_ORIGIN {
@@ -375,14 +614,72 @@ pub async fn handle_request<T: DeserializeOwned + GraphQLRequestLike>(
     response
 }
 
+/// Group of routes the request belongs to, classified by its path, `None` if
+/// it doesn't belong to any. Only the GraphQL requests of the Tailcall origin
+/// belong to the playground.
+pub fn request_route(req: &Request<Body>, app_ctx: &AppContext) -> Option<ListenerRoute> {
+    let server = &app_ctx.blueprint.server;
+    let path = req.uri().path();
+    let is_tailcall_origin = req
+        .headers()
+        .get(&header::ORIGIN)
+        .is_some_and(|origin| origin == TAILCALL_HTTPS_ORIGIN || origin == TAILCALL_HTTP_ORIGIN);
+    let is_metrics = matches!(
+        app_ctx.blueprint.telemetry.export.as_ref(),
+        Some(TelemetryExporter::Prometheus(prometheus)) if prometheus.path == path
+    );
+
+    if app_ctx
+        .rpc
+        .as_ref()
+        .is_some_and(|gateway| gateway.is_grpc_request(req))
+    {
+        Some(ListenerRoute::Grpc)
+    } else if path.starts_with(API_URL_PREFIX) {
+        Some(ListenerRoute::Rest)
+    } else if server
+        .admin
+        .as_ref()
+        .is_some_and(|admin| admin::is_admin_request(req, admin))
+    {
+        Some(ListenerRoute::Admin)
+    } else if path == server.routes.graphql() && is_tailcall_origin {
+        Some(ListenerRoute::Playground)
+    } else if path == server.routes.graphql() {
+        Some(ListenerRoute::GraphQL)
+    } else if path == server.routes.status() {
+        Some(ListenerRoute::Status)
+    } else if is_metrics {
+        Some(ListenerRoute::Metrics)
+    } else if server.enable_showcase && path == SHOWCASE_PATH {
+        Some(ListenerRoute::Showcase)
+    } else {
+        None
+    }
+}
+
+/// Handles the request if its route is served by the listener that received
+/// it, responds with `404` otherwise.
+pub async fn handle_listener_request<T: DeserializeOwned + GraphQLRequestLike>(
+    req: Request<Body>,
+    app_ctx: Arc<AppContext>,
+    listener: &Listener,
+) -> Result<Response<Body>> {
+    match request_route(&req, &app_ctx) {
+        Some(route) if listener.serves(route) => handle_request::<T>(req, app_ctx).await,
+        _ => not_found(),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use tailcall_valid::Validator;
 
     use super::*;
     use crate::core::async_graphql_hyper::GraphQLRequest;
+    use crate::core::auth::basic::tests::HTPASSWD_TEST;
     use crate::core::blueprint::Blueprint;
-    use crate::core::config::{Config, ConfigModule, Routes};
+    use crate::core::config::{Config, ConfigModule, Content, Extensions, Routes};
     use crate::core::rest::EndpointSet;
     use crate::core::runtime::test::init;
 
@@ -403,15 +700,110 @@ mod test {
             .uri("http://localhost:8000/health".to_string())
             .body(Body::empty())?;
 
-        let resp = handle_request::<GraphQLRequest>(req, app_ctx).await?;
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx.clone()).await?;
 
         assert_eq!(resp.status(), StatusCode::OK);
         let body = hyper::body::to_bytes(resp.into_body()).await?;
         assert_eq!(body, r#"{"message": "ready"}"#);
 
+        app_ctx.start_draining();
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("http://localhost:8000/health".to_string())
+            .body(Body::empty())?;
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx).await?;
+
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_listener_routes() -> anyhow::Result<()> {
+        let sdl = tokio::fs::read_to_string(tailcall_fixtures::configs::JSONPLACEHOLDER).await?;
+        let config = Config::from_sdl(&sdl).to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let mut listener = blueprint.server.listeners[0].clone();
+        listener.routes = BTreeSet::from([ListenerRoute::GraphQL]);
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("http://localhost:8000/status".to_string())
+            .body(Body::empty())?;
+        let resp = handle_listener_request::<GraphQLRequest>(req, app_ctx, &listener).await?;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_request_route() -> anyhow::Result<()> {
+        let sdl = tokio::fs::read_to_string(tailcall_fixtures::configs::JSONPLACEHOLDER).await?;
+        let config = Config::from_sdl(&sdl).to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let app_ctx = AppContext::new(blueprint, init(None), EndpointSet::default());
+        let route = |path: &str, origin: Option<&HeaderValue>| {
+            let mut builder = Request::builder().uri(format!("http://localhost:8000{}", path));
+            if let Some(origin) = origin {
+                builder = builder.header(header::ORIGIN, origin.clone());
+            }
+            request_route(&builder.body(Body::empty()).unwrap(), &app_ctx)
+        };
+
+        assert_eq!(route("/graphql", None), Some(ListenerRoute::GraphQL));
+        assert_eq!(
+            route("/graphql", Some(&TAILCALL_HTTPS_ORIGIN)),
+            Some(ListenerRoute::Playground)
+        );
+        assert_eq!(
+            route("/status", Some(&TAILCALL_HTTPS_ORIGIN)),
+            Some(ListenerRoute::Status)
+        );
+        assert_eq!(
+            route("/api/users", Some(&TAILCALL_HTTPS_ORIGIN)),
+            Some(ListenerRoute::Rest)
+        );
+        assert_eq!(route("/showcase/graphql", None), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_string_to_body() {
+        let query = "query=%7B%20users%20%7B%20id%20%7D%20%7D&variables=%7B%22id%22%3A1%7D&operationName=Users";
+        let body: serde_json::Value =
+            serde_json::from_slice(&query_string_to_body(Some(query)).unwrap()).unwrap();
+
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "query": "{ users { id } }",
+                "variables": {"id": 1},
+                "operationName": "Users"
+            })
+        );
+        assert!(query_string_to_body(Some("variables=%7B")).is_err());
+    }
+
+    #[test]
+    fn test_is_not_modified() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_not_modified(&headers, "\"abc\""));
+
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_static("\"xyz\", W/\"abc\""),
+        );
+        assert!(is_not_modified(&headers, "\"abc\""));
+        assert!(!is_not_modified(&headers, "\"def\""));
+    }
+
     #[tokio::test]
     async fn test_graphql_endpoint() -> anyhow::Result<()> {
         let sdl = tokio::fs::read_to_string(tailcall_fixtures::configs::JSONPLACEHOLDER).await?;
@@ -442,6 +834,151 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_response_cache_protected() -> anyhow::Result<()> {
+        let sdl = r#"
+            schema @server(responseCache: {}) {
+                query: Query
+            }
+
+            type Query {
+                public: String @expr(body: "public") @cache(maxAge: 60000)
+                secret: String @expr(body: "secret") @cache(maxAge: 60000) @protected
+            }
+        "#;
+        let config = Config::from_sdl(sdl).to_result()?;
+        let htpasswd = Content { id: None, content: HTPASSWD_TEST.to_owned() };
+        let extensions = Extensions { htpasswd: vec![htpasswd], ..Default::default() };
+        let blueprint = Blueprint::try_from(&ConfigModule::new(config, extensions))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+        let request = |authorization: Option<&'static str>| {
+            let mut builder = Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost:8000/graphql")
+                .header("Content-Type", "application/json");
+            if let Some(authorization) = authorization {
+                builder = builder.header("Authorization", authorization);
+            }
+            builder.body(Body::from(r#"{"query": "{ public secret }"}"#))
+        };
+
+        // testuser1:password123
+        let authorized = request(Some("Basic dGVzdHVzZXIxOnBhc3N3b3JkMTIz"))?;
+        let resp = handle_request::<GraphQLRequest>(authorized, app_ctx.clone()).await?;
+        let body = hyper::body::to_bytes(resp.into_body()).await?;
+        assert!(String::from_utf8(body.to_vec())?.contains(r#""secret":"secret""#));
+
+        let resp = handle_request::<GraphQLRequest>(request(None)?, app_ctx).await?;
+        let body = String::from_utf8(hyper::body::to_bytes(resp.into_body()).await?.to_vec())?;
+        assert!(!body.contains(r#""secret":"secret""#));
+        assert!(body.contains("Authentication Failure"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_response_cache_key() -> anyhow::Result<()> {
+        let sdl = r#"
+            schema @server(responseCache: {}) @upstream(allowedHeaders: ["x-user"]) {
+                query: Query
+            }
+
+            type Query {
+                user: String @expr(body: "{{.headers.x-user}}") @cache(maxAge: 60000)
+                echo(input: String): String @expr(body: "{{.args.input}}") @cache(maxAge: 60000)
+            }
+        "#;
+        let config = Config::from_sdl(sdl).to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+        let request = |query: &str, user: &str| {
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost:8000/graphql")
+                .header("Content-Type", "application/json")
+                .header("x-user", user)
+                .body(Body::from(
+                    serde_json::json!({ "query": query }).to_string(),
+                ))
+        };
+        let execute = |request: Request<Body>| {
+            let app_ctx = app_ctx.clone();
+            async move {
+                let resp = handle_request::<GraphQLRequest>(request, app_ctx).await?;
+                let body = hyper::body::to_bytes(resp.into_body()).await?;
+                anyhow::Ok(String::from_utf8(body.to_vec())?)
+            }
+        };
+
+        let body = execute(request("{ user }", "a")?).await?;
+        assert!(body.contains(r#""user":"a""#));
+        let body = execute(request("{ user }", "b")?).await?;
+        assert!(body.contains(r#""user":"b""#));
+
+        let body = execute(request(r#"{ echo(input: "a  b") }"#, "a")?).await?;
+        assert!(body.contains(r#""echo":"a  b""#));
+        let body = execute(request(r#"{ echo(input: "a b") }"#, "a")?).await?;
+        assert!(body.contains(r#""echo":"a b""#));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_limits() -> anyhow::Result<()> {
+        let sdl = tokio::fs::read_to_string(tailcall_fixtures::configs::JSONPLACEHOLDER).await?;
+        let config = Config::from_sdl(&sdl).to_result()?;
+        let mut blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        blueprint.server.limits = serde_json::from_value(serde_json::json!({
+            "maxBodySize": 64,
+            "maxHeaderSize": 128,
+            "maxVariablesDepth": 2
+        }))?;
+        let app_ctx = Arc::new(AppContext::new(
+            blueprint,
+            init(None),
+            EndpointSet::default(),
+        ));
+        let request = |body: &str| {
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost:8000/graphql")
+                .header("Content-Type", "application/json")
+                .body(Body::from(body.to_string()))
+        };
+
+        let query = r#"{"query": "{ __typename }"}"#;
+        let resp = handle_request::<GraphQLRequest>(request(query)?, app_ctx.clone()).await?;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let query = format!(
+            r#"{{"query": "{{ __typename }}", "x": "{}"}}"#,
+            "a".repeat(64)
+        );
+        let resp = handle_request::<GraphQLRequest>(request(&query)?, app_ctx.clone()).await?;
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let query = r#"{"query": "{ a }", "variables": {"a": {"b": {}}}}"#;
+        let resp = handle_request::<GraphQLRequest>(request(query)?, app_ctx.clone()).await?;
+        let body = hyper::body::to_bytes(resp.into_body()).await?;
+        assert!(String::from_utf8(body.to_vec())?.contains("Variables depth exceeds"));
+
+        let mut req = request(r#"{"query": "{ __typename }"}"#)?;
+        req.headers_mut()
+            .insert("x-large", HeaderValue::from_str(&"a".repeat(128))?);
+        let resp = handle_request::<GraphQLRequest>(req, app_ctx).await?;
+        assert_eq!(resp.status(), StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE);
+
+        Ok(())
+    }
+
     #[test]
     fn test_create_allowed_headers() {
         use std::collections::BTreeSet;
@@ -462,4 +999,46 @@ mod test {
         assert_eq!(new_headers.get("x-foo").unwrap(), "bar");
         assert_eq!(new_headers.get("x-bar").unwrap(), "foo");
     }
+
+    #[tokio::test]
+    async fn test_rest_body_limit() -> anyhow::Result<()> {
+        let sdl = r#"
+            schema @server(limits: {maxBodySize: 16}) {
+                query: Query
+            }
+
+            type Query {
+                echo(input: JSON): JSON @expr(body: "{{.args.input}}")
+            }
+        "#;
+        let config = Config::from_sdl(sdl).to_result()?;
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config))?;
+        let runtime = init(None);
+        let operations = r#"
+            query ($input: JSON) @rest(method: POST, path: "/echo", body: $input) {
+                echo(input: $input)
+            }
+        "#;
+        let endpoints = EndpointSet::try_new(operations)?
+            .into_checked(&blueprint, runtime.clone())
+            .await?;
+        let app_ctx = Arc::new(AppContext::new(blueprint, runtime, endpoints));
+        let request = |body: &'static str| {
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost:8000/api/echo")
+                .body(Body::from(body))
+        };
+
+        let resp =
+            handle_request::<GraphQLRequest>(request(r#"{"a": 1}"#)?, app_ctx.clone()).await?;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp =
+            handle_request::<GraphQLRequest>(request(r#"{"a": "over the limit"}"#)?, app_ctx)
+                .await?;
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        Ok(())
+    }
 }
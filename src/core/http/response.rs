@@ -48,6 +48,18 @@ impl FromValue for ConstValue {
     }
 }
 
+/// Response with an error status, it's the context of the error returned for
+/// it so that the callers that don't fail on error statuses, e.g. the `fetch`
+/// of the scripts, can still read it. It's displayed as its body.
+#[derive(Clone, Debug)]
+pub struct ErrorResponse(pub Response<Bytes>);
+
+impl std::fmt::Display for ErrorResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&String::from_utf8_lossy(&self.0.body))
+    }
+}
+
 impl Response<Bytes> {
     /// Handle error responses with body extraction for better error messages
     /// This is a common pattern used across different HTTP clients
@@ -56,20 +68,41 @@ impl Response<Bytes> {
     ) -> anyhow::Result<Self> {
         // Check if it's an error status
         if let Err(err) = response.error_for_status_ref() {
+            let message = err.without_url().to_string();
             // Get the body content first (this is the key step)
-            let body_text = response.text().await?;
-            // Create an error with the status code and add body content as context
+            let response = Self::from_reqwest(response).await?;
+            // Create an error with the status code and add the response as context
             let err = Error::HTTP {
-                message: err.without_url().to_string(),
-                body: body_text.clone(),
+                message,
+                body: String::from_utf8_lossy(&response.body).into_owned(),
             };
-            return Err(anyhow::Error::new(err).context(body_text));
+            return Err(anyhow::Error::new(err).context(ErrorResponse(response)));
         }
 
         // If not an error status, proceed normally
         Self::from_reqwest(response).await
     }
 
+    /// Same as [Self::from_reqwest_with_error_handling], the chunks of the
+    /// body are passed to `inspect` as they arrive.
+    pub async fn from_reqwest_inspected(
+        mut response: reqwest::Response,
+        inspect: &mut (dyn for<'a> FnMut(&'a [u8]) -> Result<()> + Send),
+    ) -> Result<Self> {
+        if response.error_for_status_ref().is_err() {
+            return Self::from_reqwest_with_error_handling(response).await;
+        }
+
+        let status = response.status();
+        let headers = response.headers().to_owned();
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            inspect(&chunk)?;
+            body.extend_from_slice(&chunk);
+        }
+        Ok(Response { status, headers, body: Bytes::from(body) })
+    }
+
     pub async fn from_reqwest(resp: reqwest::Response) -> Result<Self> {
         let status = resp.status();
         let headers = resp.headers().to_owned();
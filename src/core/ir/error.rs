@@ -93,6 +93,11 @@ impl ErrorExtensions for Error {
                 e.set("grpcStatusDetails", grpc_status_details.clone());
             }
 
+            if let Error::Worker(worker::Error::LimitExceeded(name, limit)) = self {
+                e.set("function", name);
+                e.set("reason", limit.reason());
+            }
+
             if let Error::HTTP { message: _, body } = self {
                 if let Ok(ConstValue::Object(map)) = serde_json::from_str::<ConstValue>(body) {
                     e.extend(map);
@@ -1,13 +1,13 @@
 use std::collections::HashMap;
 use std::future::Future;
-use std::ops::Deref;
 
 use async_graphql_value::ConstValue;
 use futures_util::future::join_all;
 use indexmap::IndexMap;
 
+use super::eval_cache::eval_cache;
 use super::eval_io::eval_io;
-use super::model::{Cache, CacheKey, Map, IR};
+use super::model::{Map, IR};
 use super::{Error, EvalContext, ResolverContextLike, TypedValue};
 use crate::core::auth::verify::{AuthVerifier, Verify};
 use crate::core::json::{JsonLike, JsonObjectLike};
@@ -44,25 +44,7 @@ impl IR {
                     expr.eval(ctx).await
                 }
                 IR::IO(io) => eval_io(io, ctx).await,
-                IR::Cache(Cache { max_age, io }) => {
-                    let io = io.deref();
-                    let key = io.cache_key(ctx);
-                    if let Some(key) = key {
-                        if let Some(val) = ctx.request_ctx.runtime.cache.get(&key).await? {
-                            Ok(val)
-                        } else {
-                            let val = eval_io(io, ctx).await?;
-                            ctx.request_ctx
-                                .runtime
-                                .cache
-                                .set(key, val.clone(), max_age.to_owned())
-                                .await?;
-                            Ok(val)
-                        }
-                    } else {
-                        eval_io(io, ctx).await
-                    }
-                }
+                IR::Cache(cache) => eval_cache(cache, ctx).await,
                 IR::Map(Map { input, map }) => {
                     fn recursive_map_enum(
                         val: Result<ConstValue, Error>,
@@ -158,6 +140,13 @@ impl IR {
 
                     Ok(ConstValue::List(entities))
                 }
+                IR::Invalidate(tags, expr) => {
+                    let val = expr.eval(ctx).await?;
+                    let tags = tags.iter().map(|tag| tag.render(&*ctx)).collect::<Vec<_>>();
+                    ctx.request_ctx.runtime.cache.purge_tags(&tags).await?;
+
+                    Ok(val)
+                }
                 IR::Service(sdl) => {
                     let mut obj = IndexMap::new();
 
@@ -0,0 +1,417 @@
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroU64;
+
+use async_graphql_value::{ConstValue, Name};
+use http::header::AUTHORIZATION;
+use indexmap::IndexMap;
+use tailcall_hasher::TailcallHasher;
+
+use super::eval_io::eval_io;
+use super::model::{Cache, CacheKey, IoId};
+use super::{Error, EvalContext, ResolverContextLike};
+
+/// Fields of the entries of the caches with stale windows. The time the value
+/// was fetched is stored with it, so that the windows follow from the age of
+/// the entry and expire together with it.
+const VALUE: &str = "value";
+const STORED_AT: &str = "storedAt";
+
+/// Milliseconds since the epoch.
+fn now() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+fn to_entry(val: ConstValue, stored_at: u64) -> ConstValue {
+    ConstValue::Object(IndexMap::from([
+        (Name::new(VALUE), val),
+        (Name::new(STORED_AT), ConstValue::from(stored_at)),
+    ]))
+}
+
+fn from_entry(entry: ConstValue) -> Option<(ConstValue, u64)> {
+    let ConstValue::Object(mut entry) = entry else {
+        return None;
+    };
+    let stored_at = match entry.get(STORED_AT)? {
+        ConstValue::Number(stored_at) => stored_at.as_u64()?,
+        _ => return None,
+    };
+
+    Some((entry.swap_remove(VALUE)?, stored_at))
+}
+
+/// Checks if an entry of the given age is within `max_age` plus the window.
+fn is_within(cache: &Cache, window: Option<NonZeroU64>, age: u64) -> bool {
+    window.is_some_and(|window| age < cache.max_age.get().saturating_add(window.get()))
+}
+
+pub async fn eval_cache<Ctx>(
+    cache: &Cache,
+    ctx: &mut EvalContext<'_, Ctx>,
+) -> Result<ConstValue, Error>
+where
+    Ctx: ResolverContextLike + Sync,
+{
+    let io = cache.io.as_ref();
+    let Some(key) = cache_key(cache, ctx) else {
+        return eval_io(io, ctx).await;
+    };
+
+    if cache.key.private {
+        ctx.request_ctx.set_cache_public_false();
+    }
+
+    let runtime_cache = &ctx.request_ctx.runtime.cache;
+    let cached = runtime_cache.get(&key).await?;
+    if !cache.stale.is_enabled() {
+        if let Some(cached) = cached {
+            return Ok(cached);
+        }
+    }
+
+    let Some((cached, stored_at)) = cached.and_then(from_entry) else {
+        let val = eval_io(io, ctx).await?;
+        store(cache, key, val.clone(), ctx).await?;
+        return Ok(val);
+    };
+
+    let age = now().saturating_sub(stored_at);
+    if age < cache.max_age.get() {
+        return Ok(cached);
+    }
+
+    if is_within(cache, cache.stale.while_revalidate, age) {
+        ctx.request_ctx.set_stale();
+        revalidate(cache, key, ctx);
+        return Ok(cached);
+    }
+
+    match eval_io(io, ctx).await {
+        Ok(val) => {
+            store(cache, key, val.clone(), ctx).await?;
+            Ok(val)
+        }
+        Err(err) => {
+            if is_within(cache, cache.stale.if_error, age) {
+                tracing::warn!("Using stale cached value: {}", err);
+                ctx.request_ctx.set_stale();
+                Ok(cached)
+            } else {
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Key of the cached value, derived from the upstream request unless a key
+/// template is set.
+fn cache_key<Ctx: ResolverContextLike + Sync>(
+    cache: &Cache,
+    ctx: &EvalContext<'_, Ctx>,
+) -> Option<IoId> {
+    let options = &cache.key;
+    let key = match &options.template {
+        Some(template) => {
+            let mut hasher = TailcallHasher::default();
+            template.render(ctx).hash(&mut hasher);
+            IoId::new(hasher.finish())
+        }
+        None => cache.io.cache_key(ctx)?,
+    };
+
+    if !options.private && options.vary_headers.is_empty() {
+        return Some(key);
+    }
+
+    let headers = &ctx.request_ctx.vary_headers;
+    let mut hasher = TailcallHasher::default();
+    key.hash(&mut hasher);
+    for name in &options.vary_headers {
+        headers
+            .get(name)
+            .map(|value| value.as_bytes())
+            .hash(&mut hasher);
+    }
+    if options.private {
+        // the subject keeps the entries of a user across their tokens
+        match ctx.request_ctx.jwt_subject() {
+            Some(subject) => ("sub", subject.as_bytes()).hash(&mut hasher),
+            None => headers
+                .get(AUTHORIZATION)
+                .map(|value| ("authorization", value.as_bytes()))
+                .hash(&mut hasher),
+        }
+    }
+
+    Some(IoId::new(hasher.finish()))
+}
+
+async fn store<Ctx: ResolverContextLike + Sync>(
+    cache: &Cache,
+    key: IoId,
+    val: ConstValue,
+    ctx: &EvalContext<'_, Ctx>,
+) -> Result<(), Error> {
+    let runtime_cache = &ctx.request_ctx.runtime.cache;
+    let (val, ttl) = if cache.stale.is_enabled() {
+        // the entry is kept for as long as any window is open
+        let window = [cache.stale.while_revalidate, cache.stale.if_error]
+            .into_iter()
+            .flatten()
+            .max()
+            .map_or(0, NonZeroU64::get);
+        let ttl = cache.max_age.saturating_add(window);
+        (to_entry(val, now()), ttl)
+    } else {
+        (val, cache.max_age)
+    };
+
+    runtime_cache.set(key.clone(), val, ttl).await?;
+    // the tags are attached to the entry, it must be cached first
+    if !cache.tags.is_empty() {
+        let tags = cache.tags.iter().map(|tag| tag.render(ctx)).collect();
+        runtime_cache.tag(&key, tags).await?;
+    }
+    Ok(())
+}
+
+/// Refreshes the cached value in the background. Concurrent refreshes of the
+/// same key are coalesced into one.
+fn revalidate<Ctx: ResolverContextLike>(cache: &Cache, key: IoId, ctx: &EvalContext<'_, Ctx>) {
+    let refreshes = ctx.request_ctx.cache_refreshes.clone();
+    if !refreshes.insert(key.clone()) {
+        return;
+    }
+
+    let cache = cache.clone();
+    let req_ctx = ctx.request_ctx.detach();
+    let resolver_ctx = ctx.detach();
+    let task = async move {
+        let mut ctx = EvalContext::new(&req_ctx, &resolver_ctx);
+        let result = match eval_io(cache.io.as_ref(), &mut ctx).await {
+            Ok(val) => store(&cache, key.clone(), val, &ctx).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            tracing::warn!("Failed to refresh the cached value: {}", err);
+        }
+        refreshes.remove(&key);
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    tokio::spawn(task);
+    #[cfg(target_arch = "wasm32")]
+    async_std::task::spawn_local(task);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::core::http::RequestContext;
+    use crate::core::ir::model::{CacheKeyOptions, Stale, IO};
+    use crate::core::ir::{DetachedResolverContext, EmptyResolverContext};
+    use crate::core::{Mustache, WorkerIO};
+
+    fn cache(private: bool) -> Cache {
+        Cache {
+            max_age: NonZeroU64::new(1000).unwrap(),
+            tags: vec![],
+            stale: Stale::default(),
+            key: CacheKeyOptions {
+                template: Some(Mustache::parse("users")),
+                private,
+                vary_headers: vec![],
+            },
+            io: Box::new(IO::Js { name: "users".to_string() }),
+        }
+    }
+
+    fn key(cache: &Cache, authorization: &str) -> Option<IoId> {
+        key_with_subject(cache, authorization, None)
+    }
+
+    fn key_with_subject(cache: &Cache, authorization: &str, sub: Option<&str>) -> Option<IoId> {
+        let mut req_ctx = RequestContext::new(crate::core::runtime::test::init(None));
+        req_ctx
+            .vary_headers
+            .insert(AUTHORIZATION, authorization.parse().unwrap());
+        if let Some(sub) = sub {
+            req_ctx.set_jwt_subject(sub.to_string());
+        }
+        let ctx = EvalContext::new(&req_ctx, &EmptyResolverContext {});
+
+        cache_key(cache, &ctx)
+    }
+
+    #[test]
+    fn test_private_cache_key() {
+        let public = cache(false);
+        assert_eq!(key(&public, "Bearer a"), key(&public, "Bearer b"));
+
+        let private = cache(true);
+        assert_ne!(key(&private, "Bearer a"), key(&private, "Bearer b"));
+        assert_eq!(key(&private, "Bearer a"), key(&private, "Bearer a"));
+        assert_ne!(key(&private, "Bearer a"), key(&public, "Bearer a"));
+    }
+
+    #[test]
+    fn test_private_cache_key_with_jwt() {
+        let private = cache(true);
+        let key = |authorization, sub| key_with_subject(&private, authorization, Some(sub));
+        assert_eq!(key("Bearer a", "alice"), key("Bearer b", "alice"));
+        assert_ne!(key("Bearer a", "alice"), key("Bearer a", "bob"));
+        assert_ne!(
+            key("Bearer a", "alice"),
+            key_with_subject(&private, "Bearer a", None)
+        );
+    }
+
+    #[test]
+    fn test_entry() {
+        let entry = to_entry(ConstValue::from("users"), 42);
+        assert_eq!(from_entry(entry), Some((ConstValue::from("users"), 42)));
+        assert_eq!(from_entry(ConstValue::from("users")), None);
+    }
+
+    /// Counts the calls and returns the count, or fails when `fail` is set.
+    /// The calls take a while so that concurrent ones overlap.
+    #[derive(Default)]
+    struct Upstream {
+        calls: AtomicUsize,
+        fail: AtomicBool,
+    }
+
+    #[async_trait::async_trait]
+    impl WorkerIO<ConstValue, ConstValue> for Upstream {
+        async fn call(
+            &self,
+            _: &str,
+            _: ConstValue,
+        ) -> Result<Option<ConstValue>, crate::core::worker::Error> {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let calls = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if self.fail.load(Ordering::SeqCst) {
+                Err(crate::core::worker::Error::Communication)
+            } else {
+                Ok(Some(ConstValue::from(calls)))
+            }
+        }
+    }
+
+    struct Setup {
+        upstream: Arc<Upstream>,
+        req_ctx: RequestContext,
+    }
+
+    impl Setup {
+        fn new() -> Self {
+            let upstream = Arc::new(Upstream::default());
+            let mut runtime = crate::core::runtime::test::init(None);
+            runtime.worker = Some(upstream.clone());
+            Self { upstream, req_ctx: RequestContext::new(runtime) }
+        }
+
+        /// Evaluates the cache in a new request sharing the refreshes in
+        /// progress, returns the value and whether it was stale.
+        async fn eval(&self, cache: &Cache) -> (Result<ConstValue, Error>, bool) {
+            let req_ctx = self.req_ctx.detach();
+            let resolver_ctx = DetachedResolverContext {
+                value: Some(ConstValue::Null),
+                args: None,
+                field: None,
+                is_query: true,
+            };
+            let mut ctx = EvalContext::new(&req_ctx, &resolver_ctx);
+            let val = eval_cache(cache, &mut ctx).await;
+            (val, req_ctx.is_stale())
+        }
+
+        fn calls(&self) -> usize {
+            self.upstream.calls.load(Ordering::SeqCst)
+        }
+
+        async fn wait_for_calls(&self, calls: usize) {
+            while self.calls() < calls {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }
+    }
+
+    fn stale_cache(stale: Stale) -> Cache {
+        Cache { max_age: NonZeroU64::new(50).unwrap(), stale, ..cache(false) }
+    }
+
+    fn window() -> Option<NonZeroU64> {
+        NonZeroU64::new(60_000)
+    }
+
+    #[tokio::test]
+    async fn test_stale_while_revalidate() {
+        let setup = Setup::new();
+        let cache = stale_cache(Stale { while_revalidate: window(), if_error: None });
+
+        let (val, is_stale) = setup.eval(&cache).await;
+        assert_eq!((val.unwrap(), is_stale), (ConstValue::from(1), false));
+        let (val, is_stale) = setup.eval(&cache).await;
+        assert_eq!((val.unwrap(), is_stale), (ConstValue::from(1), false));
+        assert_eq!(setup.calls(), 1);
+
+        // the expired value is served while it's refreshed once in the background
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let (first, second) = tokio::join!(setup.eval(&cache), setup.eval(&cache));
+        assert_eq!((first.0.unwrap(), first.1), (ConstValue::from(1), true));
+        assert_eq!((second.0.unwrap(), second.1), (ConstValue::from(1), true));
+
+        setup.wait_for_calls(2).await;
+        // the refreshed value is stored after the call returns
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let (val, is_stale) = setup.eval(&cache).await;
+        assert_eq!((val.unwrap(), is_stale), (ConstValue::from(2), false));
+        assert_eq!(setup.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stale_if_error() {
+        let setup = Setup::new();
+        let cache = stale_cache(Stale { while_revalidate: None, if_error: window() });
+
+        let (val, _) = setup.eval(&cache).await;
+        assert_eq!(val.unwrap(), ConstValue::from(1));
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        setup.upstream.fail.store(true, Ordering::SeqCst);
+        let (val, is_stale) = setup.eval(&cache).await;
+        assert_eq!((val.unwrap(), is_stale), (ConstValue::from(1), true));
+
+        // the upstream is called again while it fails
+        let (val, _) = setup.eval(&cache).await;
+        assert_eq!(val.unwrap(), ConstValue::from(1));
+        assert_eq!(setup.calls(), 3);
+
+        setup.upstream.fail.store(false, Ordering::SeqCst);
+        let (val, is_stale) = setup.eval(&cache).await;
+        assert_eq!((val.unwrap(), is_stale), (ConstValue::from(4), false));
+    }
+
+    #[tokio::test]
+    async fn test_failed_refresh() {
+        let setup = Setup::new();
+        let cache = stale_cache(Stale { while_revalidate: window(), if_error: None });
+
+        setup.eval(&cache).await.0.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        setup.upstream.fail.store(true, Ordering::SeqCst);
+        // served stale, the refresh fails in the background
+        let (val, is_stale) = setup.eval(&cache).await;
+        assert_eq!((val.unwrap(), is_stale), (ConstValue::from(1), true));
+        setup.wait_for_calls(2).await;
+
+        // the value is still served, there is no error window to stop using it
+        let (val, is_stale) = setup.eval(&cache).await;
+        assert_eq!((val.unwrap(), is_stale), (ConstValue::from(1), true));
+    }
+}
@@ -5,7 +5,10 @@ use std::sync::Arc;
 use async_graphql::{ServerError, Value};
 use http::header::HeaderMap;
 
-use super::{GraphQLOperationContext, RelatedFields, ResolverContextLike, SelectionField};
+use super::{
+    DetachedResolverContext, GraphQLOperationContext, RelatedFields, ResolverContextLike,
+    SelectionField,
+};
 use crate::core::document::print_directives;
 use crate::core::http::RequestContext;
 
@@ -56,6 +59,21 @@ impl<'a, Ctx: ResolverContextLike> EvalContext<'a, Ctx> {
         self.graphql_ctx.value()
     }
 
+    /// Copies the current value and arguments, including the overridden ones.
+    pub fn detach(&self) -> DetachedResolverContext {
+        let args = match self.path_arg::<&str>(&[]).map(Cow::into_owned) {
+            Some(Value::Object(args)) => Some(args),
+            _ => None,
+        };
+
+        DetachedResolverContext {
+            value: self.path_value::<&str>(&[]).map(Cow::into_owned),
+            args,
+            field: self.graphql_ctx.field(),
+            is_query: self.is_query(),
+        }
+    }
+
     pub fn path_arg<T: AsRef<str>>(&self, path: &[T]) -> Option<Cow<'a, Value>> {
         // TODO: add unit tests for this
         if let Some(args) = self.graphql_ctx_args.as_ref() {
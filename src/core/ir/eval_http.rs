@@ -7,6 +7,7 @@ use tailcall_valid::Validator;
 use super::model::DataLoaderId;
 use super::request::DynamicRequest;
 use super::{EvalContext, ResolverContextLike};
+use crate::core::config::GrpcProtocol;
 use crate::core::data_loader::{DataLoader, Loader};
 use crate::core::grpc::protobuf::ProtobufOperation;
 use crate::core::grpc::request::execute_grpc_request;
@@ -229,8 +230,9 @@ pub async fn execute_raw_grpc_request<Ctx: ResolverContextLike>(
     ctx: &EvalContext<'_, Ctx>,
     req: Request,
     operation: &ProtobufOperation,
+    protocol: GrpcProtocol,
 ) -> Result<Response<async_graphql::Value>, Error> {
-    execute_grpc_request(&ctx.request_ctx.runtime, operation, req)
+    execute_grpc_request(&ctx.request_ctx.runtime, operation, protocol, req)
         .await
         .map_err(Error::from)
 }
@@ -89,7 +89,8 @@ where
                 execute_grpc_request_with_dl(ctx, rendered, data_loader).await?
             } else {
                 let req = rendered.to_request()?;
-                execute_raw_grpc_request(ctx, req, &req_template.operation).await?
+                execute_raw_grpc_request(ctx, req, &req_template.operation, req_template.protocol)
+                    .await?
             };
 
             let res = match (worker.as_ref(), hook.as_ref()) {
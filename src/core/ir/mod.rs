@@ -1,6 +1,7 @@
 mod discriminator;
 mod error;
 mod eval;
+mod eval_cache;
 mod eval_context;
 mod eval_http;
 mod eval_io;
@@ -16,7 +17,8 @@ pub use error::*;
 pub use eval_context::EvalContext;
 pub(crate) use request::DynamicRequest;
 pub use resolver_context_like::{
-    EmptyResolverContext, ResolverContext, ResolverContextLike, SelectionField,
+    DetachedResolverContext, EmptyResolverContext, ResolverContext, ResolverContextLike,
+    SelectionField,
 };
 
 /// Contains all the nested fields that are resolved with current parent
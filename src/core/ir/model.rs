@@ -11,7 +11,7 @@ use crate::core::blueprint::{Auth, DynamicValue};
 use crate::core::config::group_by::GroupBy;
 use crate::core::graphql::{self};
 use crate::core::worker_hooks::WorkerHooks;
-use crate::core::{grpc, http};
+use crate::core::{grpc, http, Mustache};
 
 #[derive(Clone, Debug, Display)]
 pub enum IR {
@@ -32,6 +32,8 @@ pub enum IR {
     Entity(HashMap<String, IR>),
     /// Apollo Federation _service resolver
     Service(String),
+    /// Purges the cache tags after the inner IR succeeds
+    Invalidate(Vec<Mustache>, Box<IR>),
 }
 
 #[derive(Clone, Debug)]
@@ -114,17 +116,60 @@ pub trait CacheKey<Ctx> {
 #[derive(Clone, Debug)]
 pub struct Cache {
     pub max_age: NonZeroU64,
+    pub tags: Vec<Mustache>,
+    pub stale: Stale,
+    pub key: CacheKeyOptions,
     pub io: Box<IO>,
 }
 
+/// Customizes the key of the cached value, which by default is derived from
+/// the upstream request.
+#[derive(Clone, Debug, Default)]
+pub struct CacheKeyOptions {
+    /// Replaces the key derived from the upstream request
+    pub template: Option<Mustache>,
+    /// Values are cached per JWT subject, or per `Authorization` header
+    pub private: bool,
+    /// Request headers that are part of the key
+    pub vary_headers: Vec<String>,
+}
+
+/// Windows after `max_age` during which an expired value can still be used.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Stale {
+    /// The stale value is returned and refreshed in the background.
+    pub while_revalidate: Option<NonZeroU64>,
+    /// The stale value is returned if the upstream fails.
+    pub if_error: Option<NonZeroU64>,
+}
+
+impl Stale {
+    pub fn is_enabled(&self) -> bool {
+        self.while_revalidate.is_some() || self.if_error.is_some()
+    }
+}
+
 impl Cache {
     ///
     /// Wraps an expression with the cache primitive.
     /// Performance DFS on the cache on the expression and identifies all the IO
     /// nodes. Then wraps each IO node with the cache primitive.
-    pub fn wrap(max_age: NonZeroU64, expr: IR) -> IR {
+    #[allow(clippy::too_many_arguments)]
+    pub fn wrap(
+        max_age: NonZeroU64,
+        tags: Vec<Mustache>,
+        stale: Stale,
+        key: CacheKeyOptions,
+        expr: IR,
+    ) -> IR {
         expr.modify(&mut move |expr| match expr {
-            IR::IO(io) => Some(IR::Cache(Cache { max_age, io: Box::new(io.to_owned()) })),
+            IR::IO(io) => Some(IR::Cache(Cache {
+                max_age,
+                tags: tags.clone(),
+                stale,
+                key: key.clone(),
+                io: Box::new(io.to_owned()),
+            })),
             _ => None,
         })
     }
@@ -136,9 +181,10 @@ impl IR {
         match self {
             IR::IO(io) => io_modifier(io),
             IR::Cache(cache) => io_modifier(&mut cache.io),
-            IR::Discriminate(_, ir) | IR::Protect(_, ir) | IR::Path(ir, _) => {
-                ir.modify_io(io_modifier)
-            }
+            IR::Discriminate(_, ir)
+            | IR::Protect(_, ir)
+            | IR::Path(ir, _)
+            | IR::Invalidate(_, ir) => ir.modify_io(io_modifier),
             IR::Pipe(ir1, ir2) => {
                 ir1.modify_io(io_modifier);
                 ir2.modify_io(io_modifier);
@@ -178,10 +224,10 @@ impl IR {
                     IR::ContextPath(path) => IR::ContextPath(path),
                     IR::Dynamic(_) => expr,
                     IR::IO(_) => expr,
-                    IR::Cache(Cache { io, max_age }) => {
-                        let expr = *IR::IO(*io).modify_box(modifier);
+                    IR::Cache(cache) => {
+                        let expr = *IR::IO(*cache.io).modify_box(modifier);
                         match expr {
-                            IR::IO(io) => IR::Cache(Cache { io: Box::new(io), max_age }),
+                            IR::IO(io) => IR::Cache(Cache { io: Box::new(io), ..cache }),
                             expr => expr,
                         }
                     }
@@ -199,6 +245,7 @@ impl IR {
                             .collect(),
                     ),
                     IR::Service(sdl) => IR::Service(sdl),
+                    IR::Invalidate(tags, expr) => IR::Invalidate(tags, expr.modify_box(modifier)),
                     IR::Merge(vec) => {
                         IR::Merge(vec.into_iter().map(|ir| ir.modify(modifier)).collect())
                     }
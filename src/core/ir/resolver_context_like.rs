@@ -36,6 +36,39 @@ impl ResolverContextLike for EmptyResolverContext {
     fn add_error(&self, _: ServerError) {}
 }
 
+/// Owned copy of a resolver context that can outlive the request, e.g. for a
+/// background cache refresh.
+#[derive(Clone)]
+pub struct DetachedResolverContext {
+    pub value: Option<Value>,
+    pub args: Option<IndexMap<Name, Value>>,
+    pub field: Option<SelectionField>,
+    pub is_query: bool,
+}
+
+impl ResolverContextLike for DetachedResolverContext {
+    fn value(&self) -> Option<&Value> {
+        self.value.as_ref()
+    }
+
+    fn args(&self) -> Option<&IndexMap<Name, Value>> {
+        self.args.as_ref()
+    }
+
+    fn field(&self) -> Option<SelectionField> {
+        self.field.clone()
+    }
+
+    fn is_query(&self) -> bool {
+        self.is_query
+    }
+
+    // there is no response to attach the error to
+    fn add_error(&self, error: ServerError) {
+        tracing::warn!("{}", error.message)
+    }
+}
+
 #[derive(Clone)]
 pub struct ResolverContext<'a> {
     inner: Arc<async_graphql::dynamic::ResolverContext<'a>>,
@@ -69,7 +102,7 @@ impl ResolverContextLike for ResolverContext<'_> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct SelectionField {
     name: String,
     args: Vec<(String, String)>,
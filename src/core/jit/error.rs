@@ -26,6 +26,12 @@ pub enum ResolveInputError {
         arg_name: String,
         field_name: String,
     },
+    #[error("Argument `{arg_name}` for field `{field_name}` is not a valid `{type_of}`")]
+    ScalarIsInvalid {
+        arg_name: String,
+        field_name: String,
+        type_of: String,
+    },
 }
 
 #[derive(Error, Debug, Clone)]
@@ -1,4 +1,6 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_graphql_value::{ConstValue, Value};
 use futures_util::future::join_all;
@@ -7,15 +9,23 @@ use tailcall_valid::Validator;
 use super::context::Context;
 use super::exec::{Executor, IRExecutor};
 use super::graphql_error::GraphQLError;
-use super::{transform, AnyResponse, BuildError, Error, OperationPlan, Request, Response, Result};
+use super::{
+    transform, AnyResponse, BuildError, Error, Explain, OperationPlan, Request, ResolveInputError,
+    Response, Result, Timings, EXPLAIN_EXTENSION,
+};
 use crate::core::app_context::AppContext;
+use crate::core::blueprint::Index;
 use crate::core::http::RequestContext;
 use crate::core::ir::model::IR;
 use crate::core::ir::{self, EmptyResolverContext, EvalContext};
 use crate::core::jit::synth::Synth;
 use crate::core::jit::transform::InputResolver;
 use crate::core::json::{JsonLike, JsonLikeList};
-use crate::core::Transform;
+use crate::core::{Transform, Type};
+
+/// Name of the response extension that is set when a stale cached value was
+/// used in the response.
+pub const STALE_EXTENSION: &str = "stale";
 
 /// A specialized executor that executes with async_graphql::Value
 pub struct ConstValueExecutor {
@@ -89,14 +99,42 @@ impl ConstValueExecutor {
             }
         };
 
-        let exec = ConstValueExec::new(&plan, req_ctx);
+        if let Err(err) = validate_scalars(app_ctx, &plan).await {
+            let resp: Response<ConstValue> = Response::default();
+            return resp
+                .with_errors(vec![GraphQLError::new(err.to_string(), None)])
+                .into();
+        }
+
+        let timings =
+            (req_ctx.server.enable_explain && request.is_explain()).then(Timings::default);
+        // must outlive the response that borrows from it
+        let explain;
+
+        let exec = ConstValueExec::new(&plan, req_ctx, timings.as_ref());
         // PERF: remove this particular clone?
         let vars = request.variables.clone();
         let exe = Executor::new(&plan, exec);
         let store = exe.store().await;
         let synth = Synth::new(&plan, store, vars);
 
-        let resp: Response<serde_json_borrow::Value> = exe.execute(&synth).await;
+        let mut resp: Response<serde_json_borrow::Value> = exe.execute(&synth).await;
+
+        if req_ctx.is_stale() {
+            resp.extensions.push((
+                STALE_EXTENSION.to_string(),
+                serde_json_borrow::Value::Bool(true),
+            ));
+        }
+
+        if let Some(timings) = timings.as_ref() {
+            explain =
+                serde_json::to_value(Explain::with_timings(&plan, timings)).unwrap_or_default();
+            resp.extensions.push((
+                EXPLAIN_EXTENSION.to_string(),
+                serde_json_borrow::Value::from(&explain),
+            ));
+        }
 
         if is_introspection_query {
             let async_req = async_graphql::Request::from(request).only_introspection();
@@ -109,14 +147,103 @@ impl ConstValueExecutor {
     }
 }
 
+/// Validates the arguments whose custom scalar is validated by a plugin. The
+/// plugin function receives the value and returns `true` when it's valid.
+async fn validate_scalars(app_ctx: &AppContext, plan: &OperationPlan<ConstValue>) -> Result<()> {
+    let validators = app_ctx.blueprint.server.scalar_validators();
+    let Some(worker) = app_ctx.runtime.worker.as_ref() else {
+        return Ok(());
+    };
+    if validators.is_empty() {
+        return Ok(());
+    }
+
+    let mut scalars = vec![];
+    for field in plan.iter_dfs() {
+        for arg in field.args.iter() {
+            let values = arg.value.as_ref().map_or_else(Vec::new, |value| {
+                scalar_values(&plan.index, &validators, &arg.type_of, value)
+            });
+            scalars.extend(
+                values
+                    .into_iter()
+                    .map(|(type_of, value)| (field, arg, type_of, value)),
+            );
+        }
+    }
+
+    let validators = &validators;
+    join_all(
+        scalars
+            .into_iter()
+            .map(|(field, arg, type_of, value)| async move {
+                let output = worker
+                    .call(&validators[type_of], value.clone())
+                    .await
+                    .map_err(ir::Error::from)?;
+                if output == Some(ConstValue::Boolean(true)) {
+                    Ok(())
+                } else {
+                    Err(Error::from(BuildError::from(
+                        ResolveInputError::ScalarIsInvalid {
+                            arg_name: arg.name.clone(),
+                            field_name: field.name.clone(),
+                            type_of: type_of.to_string(),
+                        },
+                    )))
+                }
+            }),
+    )
+    .await
+    .into_iter()
+    .collect()
+}
+
+/// Collects the values of the custom scalars with a validator in the value
+/// of an argument, along with the name of their scalar.
+fn scalar_values<'a>(
+    index: &'a Index,
+    validators: &HashMap<&str, String>,
+    type_of: &'a Type,
+    value: &'a ConstValue,
+) -> Vec<(&'a str, &'a ConstValue)> {
+    match (type_of, value) {
+        (_, ConstValue::Null) => vec![],
+        (Type::List { of_type, .. }, ConstValue::List(items)) => items
+            .iter()
+            .flat_map(|item| scalar_values(index, validators, of_type, item))
+            .collect(),
+        (Type::List { of_type, .. }, value) => scalar_values(index, validators, of_type, value),
+        (Type::Named { name, .. }, value) if validators.contains_key(name.as_str()) => {
+            vec![(name, value)]
+        }
+        (Type::Named { name, .. }, ConstValue::Object(object)) => index
+            .get_input_type_definition(name)
+            .into_iter()
+            .flat_map(|definition| definition.fields.iter())
+            .filter_map(|field| {
+                let value = object.get(field.name.as_str())?;
+                Some(scalar_values(index, validators, &field.of_type, value))
+            })
+            .flatten()
+            .collect(),
+        _ => vec![],
+    }
+}
+
 struct ConstValueExec<'a> {
     plan: &'a OperationPlan<ConstValue>,
     req_context: &'a RequestContext,
+    timings: Option<&'a Timings>,
 }
 
 impl<'a> ConstValueExec<'a> {
-    pub fn new(plan: &'a OperationPlan<ConstValue>, req_context: &'a RequestContext) -> Self {
-        Self { req_context, plan }
+    pub fn new(
+        plan: &'a OperationPlan<ConstValue>,
+        req_context: &'a RequestContext,
+        timings: Option<&'a Timings>,
+    ) -> Self {
+        Self { req_context, plan, timings }
     }
 
     async fn call(
@@ -136,7 +263,13 @@ impl<'a> ConstValueExec<'a> {
         let req_context = &self.req_context;
         let mut eval_ctx = EvalContext::new(req_context, ctx);
 
-        Ok(ir.eval(&mut eval_ctx).await?)
+        let start = Instant::now();
+        let result = ir.eval(&mut eval_ctx).await;
+        if let Some(timings) = self.timings {
+            timings.record(&ctx.field().id, start);
+        }
+
+        Ok(result?)
     }
 }
 
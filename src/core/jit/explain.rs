@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_graphql::parser::types::OperationType;
+use serde::Serialize;
+
+use super::{Field, FieldId, OperationPlan};
+use crate::core::ir::model::{IO, IR};
+
+/// Name of the request extension that enables the explain mode and of the
+/// response extension that contains the plan.
+pub const EXPLAIN_EXTENSION: &str = "explain";
+
+/// Serializable view of an [OperationPlan] that shows what will be executed
+/// for every field of the operation.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Explain {
+    pub operation_type: &'static str,
+    pub is_const: bool,
+    pub is_dedupe: bool,
+    pub is_protected: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_cache_ttl: Option<u64>,
+    pub fields: Vec<FieldPlan>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldPlan {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    pub type_of: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_condition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolver: Option<Step>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub selection: Vec<FieldPlan>,
+}
+
+/// A single node of the resolver of a field.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Step {
+    #[serde(rename_all = "camelCase")]
+    Http {
+        method: String,
+        url: String,
+        batched: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        group_by: Option<Vec<String>>,
+        dedupe: bool,
+    },
+    #[serde(rename_all = "camelCase", rename = "graphQL")]
+    GraphQL {
+        url: String,
+        field_name: String,
+        batched: bool,
+        dedupe: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    Grpc {
+        url: String,
+        method: String,
+        batched: bool,
+        dedupe: bool,
+    },
+    Js {
+        name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Cache {
+        max_age: u64,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        stale_while_revalidate: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        stale_if_error: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+        private: bool,
+        io: Box<Step>,
+    },
+    Invalidate {
+        tags: Vec<String>,
+        step: Box<Step>,
+    },
+    Protected {
+        step: Box<Step>,
+    },
+    Discriminate {
+        step: Box<Step>,
+    },
+    /// Value that doesn't need any IO, e.g. `@expr`
+    Const,
+    Context {
+        path: Vec<String>,
+    },
+    Path {
+        path: Vec<String>,
+        step: Box<Step>,
+    },
+    Map {
+        step: Box<Step>,
+    },
+    Pipe {
+        steps: Vec<Step>,
+    },
+    Merge {
+        steps: Vec<Step>,
+    },
+    Entity {
+        types: Vec<String>,
+    },
+    Service,
+}
+
+impl From<&IO> for Step {
+    fn from(io: &IO) -> Self {
+        match io {
+            IO::Http { req_template, group_by, dl_id, dedupe, .. } => Step::Http {
+                method: req_template.method.to_string(),
+                url: req_template.root_url.to_string(),
+                batched: dl_id.is_some(),
+                group_by: group_by.as_ref().map(|group_by| group_by.path()),
+                dedupe: *dedupe,
+            },
+            IO::GraphQL { req_template, field_name, batch, dedupe, .. } => Step::GraphQL {
+                url: req_template.url.clone(),
+                field_name: field_name.clone(),
+                batched: *batch,
+                dedupe: *dedupe,
+            },
+            IO::Grpc { req_template, dl_id, dedupe, .. } => Step::Grpc {
+                url: req_template.url.to_string(),
+                method: req_template.operation.method.full_name().to_string(),
+                batched: dl_id.is_some(),
+                dedupe: *dedupe,
+            },
+            IO::Js { name } => Step::Js { name: name.clone() },
+        }
+    }
+}
+
+impl From<&IR> for Step {
+    fn from(ir: &IR) -> Self {
+        match ir {
+            IR::Dynamic(_) => Step::Const,
+            IR::IO(io) => io.into(),
+            IR::Cache(cache) => Step::Cache {
+                max_age: cache.max_age.get(),
+                tags: cache.tags.iter().map(|tag| tag.to_string()).collect(),
+                stale_while_revalidate: cache.stale.while_revalidate.map(|ttl| ttl.get()),
+                stale_if_error: cache.stale.if_error.map(|ttl| ttl.get()),
+                key: cache.key.template.as_ref().map(|key| key.to_string()),
+                private: cache.key.private,
+                io: Box::new(cache.io.as_ref().into()),
+            },
+            IR::Path(ir, path) => {
+                Step::Path { path: path.clone(), step: Box::new(ir.as_ref().into()) }
+            }
+            IR::ContextPath(path) => Step::Context { path: path.clone() },
+            IR::Protect(_, ir) => Step::Protected { step: Box::new(ir.as_ref().into()) },
+            IR::Map(map) => Step::Map { step: Box::new(map.input.as_ref().into()) },
+            IR::Pipe(first, second) => {
+                let mut steps = vec![first.as_ref().into()];
+                // flatten nested pipes to keep the tree readable
+                match Step::from(second.as_ref()) {
+                    Step::Pipe { steps: rest } => steps.extend(rest),
+                    step => steps.push(step),
+                }
+                Step::Pipe { steps }
+            }
+            IR::Merge(irs) => Step::Merge { steps: irs.iter().map(Step::from).collect() },
+            IR::Discriminate(_, ir) => Step::Discriminate { step: Box::new(ir.as_ref().into()) },
+            IR::Entity(map) => {
+                let mut types = map.keys().cloned().collect::<Vec<_>>();
+                types.sort();
+                Step::Entity { types }
+            }
+            IR::Service(_) => Step::Service,
+            IR::Invalidate(tags, ir) => Step::Invalidate {
+                tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                step: Box::new(ir.as_ref().into()),
+            },
+        }
+    }
+}
+
+/// Time spent resolving a field. A field inside a list is resolved once per
+/// item so the time is aggregated.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Timing {
+    pub calls: u32,
+    pub total_ms: f64,
+}
+
+/// Collects the time spent in the resolvers during execution.
+#[derive(Debug, Default)]
+pub struct Timings(Mutex<HashMap<FieldId, (u32, Duration)>>);
+
+impl Timings {
+    pub fn record(&self, field_id: &FieldId, start: Instant) {
+        let elapsed = start.elapsed();
+        if let Ok(mut timings) = self.0.lock() {
+            let (calls, total) = timings.entry(field_id.clone()).or_default();
+            *calls += 1;
+            *total += elapsed;
+        }
+    }
+
+    fn get(&self, field_id: &FieldId) -> Option<Timing> {
+        let timings = self.0.lock().ok()?;
+        let (calls, total) = timings.get(field_id)?;
+
+        Some(Timing { calls: *calls, total_ms: total.as_secs_f64() * 1000.0 })
+    }
+}
+
+impl Explain {
+    pub fn new<Input>(plan: &OperationPlan<Input>) -> Self {
+        Self::build(plan, None)
+    }
+
+    /// Builds the plan with the time spent in the resolvers of every field.
+    pub fn with_timings<Input>(plan: &OperationPlan<Input>, timings: &Timings) -> Self {
+        Self::build(plan, Some(timings))
+    }
+
+    fn build<Input>(plan: &OperationPlan<Input>, timings: Option<&Timings>) -> Self {
+        Self {
+            operation_type: match plan.operation_type {
+                OperationType::Query => "query",
+                OperationType::Mutation => "mutation",
+                OperationType::Subscription => "subscription",
+            },
+            is_const: plan.is_const,
+            is_dedupe: plan.is_dedupe,
+            is_protected: plan.is_protected,
+            min_cache_ttl: plan.min_cache_ttl.map(|ttl| ttl.get()),
+            fields: plan
+                .selection
+                .iter()
+                .map(|field| FieldPlan::new(field, timings))
+                .collect(),
+        }
+    }
+}
+
+impl FieldPlan {
+    fn new<Input>(field: &Field<Input>, timings: Option<&Timings>) -> Self {
+        Self {
+            name: field.name.clone(),
+            alias: (field.output_name != field.name).then(|| field.output_name.clone()),
+            type_of: format!("{:?}", field.type_of),
+            type_condition: field.type_condition.clone(),
+            resolver: field.ir.as_ref().map(Step::from),
+            timing: timings.and_then(|timings| timings.get(&field.id)),
+            selection: field
+                .selection
+                .iter()
+                .map(|field| FieldPlan::new(field, timings))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql_value::ConstValue;
+    use serde_json::json;
+    use tailcall_valid::Validator;
+
+    use super::*;
+    use crate::core::app_context::AppContext;
+    use crate::core::blueprint::Blueprint;
+    use crate::core::config::{Config, ConfigModule};
+    use crate::core::jit::Request;
+    use crate::core::rest::EndpointSet;
+    use crate::core::runtime::test::init;
+
+    const CONFIG: &str = r#"
+        schema @upstream(batch: {delay: 1}) { query: Query }
+        type Query {
+            posts: [Post] @http(url: "http://jsonplaceholder.typicode.com/posts") @cache(maxAge: 100)
+        }
+        type Post {
+            id: Int!
+            userId: Int!
+            user: User
+                @http(url: "http://jsonplaceholder.typicode.com/users", query: [{key: "id", value: "{{.value.userId}}"}], batchKey: ["id"])
+        }
+        type User { id: Int! name: String }
+    "#;
+
+    fn explain(query: &str) -> serde_json::Value {
+        let config = Config::from_sdl(CONFIG).to_result().unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let app_ctx = AppContext::new(blueprint, init(None), EndpointSet::default());
+        let plan = Request::<ConstValue>::new(query)
+            .create_plan(&app_ctx.blueprint)
+            .unwrap();
+
+        serde_json::to_value(Explain::new(&plan)).unwrap()
+    }
+
+    #[test]
+    fn test_explain() {
+        let actual = explain("query { items: posts { id user { name } } }");
+        let posts = &actual["fields"][0];
+
+        assert_eq!(actual["operationType"], json!("query"));
+        assert_eq!(posts["name"], json!("posts"));
+        assert_eq!(posts["alias"], json!("items"));
+        assert_eq!(posts["resolver"]["kind"], json!("cache"));
+        assert_eq!(posts["resolver"]["maxAge"], json!(100));
+        assert_eq!(
+            posts["resolver"]["io"]["url"],
+            json!("http://jsonplaceholder.typicode.com/posts")
+        );
+
+        let user = &posts["selection"][1];
+        assert_eq!(user["resolver"]["kind"], json!("http"));
+        assert_eq!(user["resolver"]["batched"], json!(true));
+    }
+}
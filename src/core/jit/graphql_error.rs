@@ -199,6 +199,21 @@ impl Error {
         Self { message: message.into(), extensions: None }
     }
 
+    /// Convert the error to an error of the async_graphql executor, keeping
+    /// its extensions.
+    #[must_use]
+    pub fn into_async_graphql(self) -> async_graphql::Error {
+        let mut error = async_graphql::Error::new(self.message);
+        if let Some(values) = self.extensions {
+            let mut extensions = async_graphql::ErrorExtensionValues::default();
+            for (name, value) in values.0 {
+                extensions.set(name, value);
+            }
+            error.extensions = Some(extensions);
+        }
+        error
+    }
+
     /// Convert the error to a server error.
     #[must_use]
     pub fn into_server_error(self, pos: Pos) -> GraphQLError {
@@ -267,4 +282,24 @@ mod test {
 
         assert_eq!(async_ext_str, owned_ext_str);
     }
+
+    #[test]
+    fn test_into_async_graphql() {
+        use super::ErrorExtensions;
+
+        let error = crate::core::ir::Error::GRPC {
+            grpc_code: 5,
+            grpc_description: "The requested entity was not found.".to_string(),
+            grpc_status_message: "user not found".to_string(),
+            grpc_status_details: async_graphql::Value::Null,
+        };
+        let error = error
+            .extend()
+            .into_async_graphql()
+            .into_server_error(async_graphql::Pos::default());
+        let actual = serde_json::to_value(error).unwrap();
+
+        assert_eq!(actual["extensions"]["grpcCode"], 5);
+        assert_eq!(actual["extensions"]["grpcStatusMessage"], "user not found");
+    }
 }
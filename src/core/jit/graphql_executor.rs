@@ -1,9 +1,13 @@
 use std::collections::BTreeMap;
 use std::future::Future;
 use std::hash::{Hash, Hasher};
+use std::num::NonZeroU64;
 use std::sync::Arc;
 
-use async_graphql::{BatchRequest, Value};
+use async_graphql::parser::types::{
+    Directive, DocumentOperations, ExecutableDocument, OperationDefinition, Selection, SelectionSet,
+};
+use async_graphql::{parser, BatchRequest, Name, Value};
 use async_graphql_value::{ConstValue, Extensions};
 use futures_util::stream::FuturesOrdered;
 use futures_util::StreamExt;
@@ -14,6 +18,7 @@ use crate::core::app_context::AppContext;
 use crate::core::async_graphql_hyper::OperationId;
 use crate::core::http::RequestContext;
 use crate::core::jit::{self, ConstValueExecutor, OPHash, Pos, Positioned};
+use crate::core::Cache;
 
 #[derive(Clone)]
 pub struct JITExecutor {
@@ -68,6 +73,59 @@ impl JITExecutor {
 
         OPHash::new(hasher.finish())
     }
+
+    /// Key of the response cache. The query is hashed from its parsed
+    /// document so that differently formatted queries share the entry, the
+    /// queries that can't be parsed aren't cached. The headers forwarded to
+    /// the upstream are part of the key as they can change the response.
+    fn response_cache_key(&self, request: &async_graphql::Request) -> Option<OPHash> {
+        let mut document = parser::parse_query(&request.query).ok()?;
+        clear_positions(&mut document);
+        let document = serde_json::to_value(document).ok()?;
+
+        let mut hasher = TailcallHasher::default();
+        sort_keys(document).to_string().hash(&mut hasher);
+        request.operation_name.hash(&mut hasher);
+        serde_json::to_string(&request.variables)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+
+        let mut allowed_headers = self
+            .req_ctx
+            .allowed_headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_bytes()))
+            .collect::<Vec<_>>();
+        allowed_headers.sort();
+        allowed_headers.hash(&mut hasher);
+
+        if let Some(response_cache) = self.app_ctx.blueprint.server.response_cache.as_ref() {
+            for name in response_cache.headers() {
+                self.req_ctx
+                    .vary_headers
+                    .get(name)
+                    .map(|value| value.as_bytes())
+                    .hash(&mut hasher);
+            }
+        }
+
+        Some(OPHash::new(hasher.finish()))
+    }
+
+    /// The smallest of the `@cache` TTL of the plan and the upstream
+    /// `Cache-Control` max age.
+    fn response_ttl(&self, plan_ttl: Option<NonZeroU64>) -> Option<NonZeroU64> {
+        let upstream_ttl = match self.req_ctx.get_min_max_age() {
+            Some(max_age) if max_age <= 0 => return None,
+            Some(max_age) => NonZeroU64::new(max_age as u64 * 1000),
+            None => None,
+        };
+
+        match (plan_ttl, upstream_ttl) {
+            (Some(plan_ttl), Some(upstream_ttl)) => Some(plan_ttl.min(upstream_ttl)),
+            (plan_ttl, upstream_ttl) => plan_ttl.or(upstream_ttl),
+        }
+    }
 }
 
 impl JITExecutor {
@@ -78,13 +136,30 @@ impl JITExecutor {
         // TODO: hash considering only the query itself ignoring specified operation and
         // variables that could differ for the same query
         let hash = Self::req_hash(&request);
+        let response_cache_key = self
+            .app_ctx
+            .response_cache
+            .as_ref()
+            .and_then(|_| self.response_cache_key(&request));
 
         async move {
-            if let Some(response) = self.app_ctx.const_execution_cache.get(&hash) {
-                return response.clone();
+            let jit_request = jit::Request::from(request);
+            // explained responses contain timings and shouldn't be shared
+            let is_explain =
+                self.app_ctx.blueprint.server.enable_explain && jit_request.is_explain();
+            let response_cache = self
+                .app_ctx
+                .response_cache
+                .as_ref()
+                .zip(response_cache_key)
+                .filter(|_| !is_explain);
+
+            if !is_explain {
+                if let Some(response) = self.app_ctx.const_execution_cache.get(&hash) {
+                    return response.clone();
+                }
             }
 
-            let jit_request = jit::Request::from(request);
             let exec = if let Some(op) = self.app_ctx.operation_plans.get(&hash) {
                 ConstValueExecutor::from(op.value().clone())
             } else {
@@ -104,20 +179,50 @@ impl JITExecutor {
 
             let is_const = exec.plan.is_const;
             let is_protected = exec.plan.is_protected;
+            let is_query = exec.plan.is_query();
+            let has_protected_fields = exec.plan.has_protected_fields;
+            let min_cache_ttl = exec.plan.min_cache_ttl;
+
+            // The cache is looked up before the auth of the request is verified, so
+            // responses containing protected fields are never shared.
+            let response_cache = response_cache.filter(|_| !has_protected_fields);
+            if let Some((cache, key)) = response_cache.as_ref() {
+                if let Ok(Some(response)) = cache.get(key).await {
+                    if response.cache_control.max_age > 0 {
+                        self.req_ctx.set_min_max_age(response.cache_control.max_age);
+                    }
+                    return response;
+                }
+            }
 
-            let response = if exec.plan.can_dedupe() {
+            let response = if exec.plan.can_dedupe() && !is_explain {
                 self.dedupe_and_exec(exec, jit_request).await
             } else {
                 self.exec(exec, jit_request).await
             };
 
-            // Cache the response if it's constant and not wrapped with protected.
-            if is_const && !is_protected {
+            // Cache the response if it's constant and has no protected fields.
+            if is_const && !is_protected && !has_protected_fields && !is_explain {
                 self.app_ctx
                     .const_execution_cache
                     .insert(hash, response.clone());
             }
 
+            // Private and stale responses must not be shared with other clients.
+            let is_shareable = is_query
+                && response.is_ok
+                && self.req_ctx.is_cache_public().unwrap_or(true)
+                && !self.req_ctx.is_stale();
+            if let Some((cache, key)) = response_cache.filter(|_| is_shareable) {
+                if let Some(ttl) = self.response_ttl(min_cache_ttl) {
+                    let mut cached = response.clone();
+                    cached.cache_control.max_age = self.req_ctx.get_min_max_age().unwrap_or(0);
+                    if let Err(err) = cache.set(key, cached, ttl).await {
+                        tracing::warn!("Failed to cache the response: {}", err);
+                    }
+                }
+            }
+
             response
         }
     }
@@ -138,6 +243,109 @@ impl JITExecutor {
 }
 
 // TODO: used only for introspection, simplify somehow?
+/// Sorts the objects of a serialized document, the fragments and operations
+/// of a document are kept in hash maps.
+fn sort_keys(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_keys(value)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(sort_keys).collect())
+        }
+        value => value,
+    }
+}
+
+/// Resets the positions of the nodes of a document, they only depend on the
+/// formatting of the query.
+fn clear_positions(document: &mut ExecutableDocument) {
+    match &mut document.operations {
+        DocumentOperations::Single(operation) => clear_operation(operation),
+        DocumentOperations::Multiple(operations) => {
+            operations.values_mut().for_each(clear_operation)
+        }
+    }
+    for fragment in document.fragments.values_mut() {
+        let fragment = clear(fragment);
+        clear(&mut clear(&mut fragment.type_condition).on);
+        clear_directives(&mut fragment.directives);
+        clear_selection_set(&mut fragment.selection_set);
+    }
+}
+
+fn clear<T>(node: &mut parser::Positioned<T>) -> &mut T {
+    node.pos = parser::Pos::default();
+    &mut node.node
+}
+
+fn clear_operation(operation: &mut parser::Positioned<OperationDefinition>) {
+    let operation = clear(operation);
+    for variable in operation.variable_definitions.iter_mut() {
+        let variable = clear(variable);
+        clear(&mut variable.name);
+        clear(&mut variable.var_type);
+        clear_directives(&mut variable.directives);
+        if let Some(value) = variable.default_value.as_mut() {
+            clear(value);
+        }
+    }
+    clear_directives(&mut operation.directives);
+    clear_selection_set(&mut operation.selection_set);
+}
+
+fn clear_directives(directives: &mut [parser::Positioned<Directive>]) {
+    for directive in directives {
+        let directive = clear(directive);
+        clear(&mut directive.name);
+        clear_arguments(&mut directive.arguments);
+    }
+}
+
+fn clear_arguments<T>(arguments: &mut [(parser::Positioned<Name>, parser::Positioned<T>)]) {
+    for (name, value) in arguments {
+        clear(name);
+        clear(value);
+    }
+}
+
+fn clear_selection_set(selection_set: &mut parser::Positioned<SelectionSet>) {
+    for selection in clear(selection_set).items.iter_mut() {
+        match clear(selection) {
+            Selection::Field(field) => {
+                let field = clear(field);
+                if let Some(alias) = field.alias.as_mut() {
+                    clear(alias);
+                }
+                clear(&mut field.name);
+                clear_arguments(&mut field.arguments);
+                clear_directives(&mut field.directives);
+                clear_selection_set(&mut field.selection_set);
+            }
+            Selection::FragmentSpread(spread) => {
+                let spread = clear(spread);
+                clear(&mut spread.fragment_name);
+                clear_directives(&mut spread.directives);
+            }
+            Selection::InlineFragment(fragment) => {
+                let fragment = clear(fragment);
+                if let Some(condition) = fragment.type_condition.as_mut() {
+                    clear(&mut clear(condition).on);
+                }
+                clear_directives(&mut fragment.directives);
+                clear_selection_set(&mut fragment.selection_set);
+            }
+        }
+    }
+}
+
 impl From<jit::Request<Value>> for async_graphql::Request {
     fn from(value: jit::Request<Value>) -> Self {
         let mut request = async_graphql::Request::new(value.query);
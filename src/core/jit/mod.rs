@@ -10,6 +10,7 @@ use store::*;
 mod context;
 mod error;
 mod exec_const;
+mod explain;
 mod request;
 mod response;
 
@@ -21,6 +22,7 @@ mod graphql_executor;
 // Public Exports
 pub use error::*;
 pub use exec_const::*;
+pub use explain::*;
 pub use graphql_executor::*;
 pub use model::*;
 pub use request::*;
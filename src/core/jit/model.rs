@@ -314,6 +314,7 @@ pub struct OperationPlan<Input> {
     pub is_dedupe: bool,
     pub is_const: bool,
     pub is_protected: bool,
+    pub has_protected_fields: bool,
     pub min_cache_ttl: Option<NonZeroU64>,
     pub selection: Vec<Field<Input>>,
     pub before: Option<IR>,
@@ -340,6 +341,7 @@ impl<Input> OperationPlan<Input> {
             is_dedupe: self.is_dedupe,
             is_const: self.is_const,
             is_protected: self.is_protected,
+            has_protected_fields: self.has_protected_fields,
             min_cache_ttl: self.min_cache_ttl,
             before: self.before,
             interfaces: None,
@@ -369,6 +371,7 @@ impl<Input> OperationPlan<Input> {
             is_dedupe: false,
             is_const: false,
             is_protected: false,
+            has_protected_fields: false,
             min_cache_ttl: None,
             before: Default::default(),
             interfaces,
@@ -5,7 +5,7 @@ use async_graphql_value::ConstValue;
 use serde::Deserialize;
 use tailcall_valid::Validator;
 
-use super::{transform, Builder, OperationPlan, Result, Variables};
+use super::{transform, Builder, OperationPlan, Result, Variables, EXPLAIN_EXTENSION};
 use crate::core::blueprint::Blueprint;
 use crate::core::transform::TransformerOps;
 use crate::core::Transform;
@@ -37,6 +37,15 @@ impl From<async_graphql::Request> for Request<ConstValue> {
 }
 
 impl Request<ConstValue> {
+    /// Checks if the client asked for the execution plan with the `explain`
+    /// extension.
+    pub fn is_explain(&self) -> bool {
+        matches!(
+            self.extensions.get(EXPLAIN_EXTENSION),
+            Some(ConstValue::Boolean(true))
+        )
+    }
+
     pub fn create_plan(
         &self,
         blueprint: &Blueprint,
@@ -75,7 +75,7 @@ pub fn update_ir(ir: &mut IR, vec: &mut Vec<Auth>) {
             update_ir(ir1, vec);
             update_ir(ir2, vec);
         }
-        IR::Discriminate(_, ir) => {
+        IR::Discriminate(_, ir) | IR::Invalidate(_, ir) => {
             update_ir(ir, vec);
         }
         IR::Merge(irs) => {
@@ -30,7 +30,11 @@ fn check_cache(ir: &IR) -> Option<NonZeroU64> {
         IR::Merge(vec) => vec.iter().map(check_cache).min().unwrap_or_default(),
         IR::Discriminate(_, ir) => check_cache(ir),
         IR::Entity(hash_map) => hash_map.values().map(check_cache).min().unwrap_or_default(),
-        IR::Dynamic(_) | IR::ContextPath(_) | IR::Map(_) | IR::Service(_) => None,
+        IR::Dynamic(_)
+        | IR::ContextPath(_)
+        | IR::Map(_)
+        | IR::Service(_)
+        | IR::Invalidate(_, _) => None,
     }
 }
 
@@ -29,6 +29,8 @@ pub fn is_const(ir: &IR) -> bool {
         IR::Discriminate(_, ir) => is_const(ir),
         IR::Entity(hash_map) => hash_map.values().all(is_const),
         IR::Service(_) => true,
+        // purging the cache is a side effect
+        IR::Invalidate(_, _) => false,
     }
 }
 
@@ -28,6 +28,7 @@ fn check_dedupe(ir: &IR) -> bool {
         IR::ContextPath(_) => true,
         IR::Map(_) => true,
         IR::Service(_) => true,
+        IR::Invalidate(_, _) => false,
     }
 }
 
@@ -29,6 +29,26 @@ pub fn is_protected(ir: &IR) -> bool {
         IR::Discriminate(_, ir) => is_protected(ir),
         IR::Entity(hash_map) => hash_map.values().any(is_protected),
         IR::Service(_) => false,
+        IR::Invalidate(_, ir) => is_protected(ir),
+    }
+}
+
+/// Checks if the IR evaluates to a Protected Value in any of its branches
+pub fn has_protected(ir: &IR) -> bool {
+    match ir {
+        IR::Dynamic(_) => false,
+        IR::IO(_) => false,
+        IR::Cache(_) => false,
+        IR::Path(ir, _) => has_protected(ir),
+        IR::ContextPath(_) => false,
+        IR::Protect(_, _) => true,
+        IR::Map(map) => has_protected(&map.input),
+        IR::Pipe(ir, ir1) => has_protected(ir) || has_protected(ir1),
+        IR::Merge(vec) => vec.iter().any(has_protected),
+        IR::Discriminate(_, ir) => has_protected(ir),
+        IR::Entity(hash_map) => hash_map.values().any(has_protected),
+        IR::Service(_) => false,
+        IR::Invalidate(_, ir) => has_protected(ir),
     }
 }
 
@@ -41,6 +61,9 @@ impl<A> Transform for CheckProtected<A> {
             Some(ref ir) => is_protected(ir),
             None => true,
         });
+        plan.has_protected_fields = plan
+            .iter_dfs()
+            .any(|field| field.ir.as_ref().is_some_and(has_protected));
 
         Valid::succeed(plan)
     }
@@ -83,6 +83,7 @@ where
             is_dedupe: self.plan.is_dedupe,
             is_const: self.plan.is_const,
             is_protected: self.plan.is_protected,
+            has_protected_fields: self.plan.has_protected_fields,
             min_cache_ttl: self.plan.min_cache_ttl,
             interfaces: None,
             selection,
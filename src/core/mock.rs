@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use async_graphql::{Name, Value};
+use indexmap::IndexMap;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use tailcall_hasher::TailcallHasher;
+
+use crate::core::blueprint::{
+    Blueprint, Definition, DynamicValue, FieldDefinition, ObjectTypeDefinition,
+};
+use crate::core::config::ConfigModule;
+use crate::core::ir::model::IR;
+use crate::core::scalar::Scalar;
+use crate::core::Type;
+
+const FIRST_NAMES: &[&str] = &["Alice", "Bob", "Carol", "Dave", "Erin", "Frank", "Grace"];
+const LAST_NAMES: &[&str] = &["Smith", "Johnson", "Brown", "Taylor", "Miller", "Davis"];
+const WORDS: &[&str] = &[
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+];
+
+/// Replaces the resolvers of a [Blueprint] with generated data so the schema
+/// can be served without any upstream.
+///
+/// Generation is deterministic: the same seed always produces the same values
+/// for a field.
+#[derive(Clone, Debug)]
+pub struct Mocker {
+    seed: u64,
+    list_length: usize,
+    max_depth: usize,
+    /// Fixed values keyed by `Type.field`
+    overrides: HashMap<String, Value>,
+}
+
+impl Mocker {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            list_length: 3,
+            max_depth: 3,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Collects the fixed values from `@mock` directives and linked fixture
+    /// files. Values from `@mock` take precedence over fixtures.
+    pub fn from_config_module(seed: u64, config_module: &ConfigModule) -> anyhow::Result<Self> {
+        let mut overrides = HashMap::new();
+
+        for fixtures in config_module.extensions().mocks.iter() {
+            for (key, value) in fixtures.iter() {
+                overrides.insert(key.clone(), Value::from_json(value.clone())?);
+            }
+        }
+
+        for (type_name, type_of) in config_module.types.iter() {
+            for (field_name, field) in type_of.fields.iter() {
+                if let Some(mock) = &field.mock {
+                    let key = format!("{}.{}", type_name, field_name);
+                    overrides.insert(key, Value::from_json(mock.value.clone())?);
+                }
+            }
+        }
+
+        Ok(Self { overrides, ..Self::new(seed) })
+    }
+
+    pub fn mock(&self, mut blueprint: Blueprint) -> Blueprint {
+        let definitions = blueprint
+            .definitions
+            .iter()
+            .map(|def| (def.name().to_string(), def.clone()))
+            .collect::<HashMap<_, _>>();
+        let generator = Generator { mocker: self, definitions: &definitions };
+
+        for def in blueprint.definitions.iter_mut() {
+            let (type_name, fields) = match def {
+                Definition::Object(def) => (&def.name, &mut def.fields),
+                Definition::Interface(def) => (&def.name, &mut def.fields),
+                _ => continue,
+            };
+
+            for field in fields.iter_mut() {
+                let key = format!("{}.{}", type_name, field.name);
+                if let Some(value) = self.overrides.get(&key) {
+                    field.resolver = Some(replace_io(field.resolver.take(), value.clone()));
+                } else if field.resolver.as_ref().is_some_and(has_io) {
+                    let mut rng = self.rng(&key);
+                    let value = generator.field(field, &mut rng, 0);
+                    field.resolver = Some(replace_io(field.resolver.take(), value));
+                }
+            }
+        }
+
+        blueprint
+    }
+
+    fn rng(&self, key: &str) -> StdRng {
+        let mut hasher = TailcallHasher::default();
+        self.seed.hash(&mut hasher);
+        key.hash(&mut hasher);
+        StdRng::seed_from_u64(hasher.finish())
+    }
+}
+
+/// Checks if the resolver makes any call to an upstream or a JS worker.
+fn has_io(ir: &IR) -> bool {
+    match ir {
+        IR::IO(_) | IR::Cache(_) | IR::Entity(_) | IR::Service(_) => true,
+        IR::Dynamic(_) | IR::ContextPath(_) => false,
+        IR::Path(ir, _) | IR::Protect(_, ir) | IR::Discriminate(_, ir) | IR::Invalidate(_, ir) => {
+            has_io(ir)
+        }
+        IR::Map(map) => has_io(&map.input),
+        IR::Pipe(first, second) => has_io(first) || has_io(second),
+        IR::Merge(irs) => irs.iter().any(has_io),
+    }
+}
+
+/// Replaces the resolver with a constant value while preserving the auth and
+/// type discrimination applied on top of it.
+fn replace_io(ir: Option<IR>, value: Value) -> IR {
+    match ir {
+        Some(IR::Protect(auth, ir)) => IR::Protect(auth, Box::new(replace_io(Some(*ir), value))),
+        Some(IR::Discriminate(discriminator, ir)) => {
+            IR::Discriminate(discriminator, Box::new(replace_io(Some(*ir), value)))
+        }
+        _ => IR::Dynamic(DynamicValue::Value(value)),
+    }
+}
+
+struct Generator<'a> {
+    mocker: &'a Mocker,
+    definitions: &'a HashMap<String, Definition>,
+}
+
+impl Generator<'_> {
+    fn field(&self, field: &FieldDefinition, rng: &mut StdRng, depth: usize) -> Value {
+        self.type_of(&field.of_type, &field.name, rng, depth)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn type_of(&self, type_of: &Type, field_name: &str, rng: &mut StdRng, depth: usize) -> Value {
+        match type_of {
+            Type::List { of_type, non_null } => {
+                if depth >= self.mocker.max_depth {
+                    return if *non_null {
+                        Value::List(vec![])
+                    } else {
+                        Value::Null
+                    };
+                }
+
+                Value::List(
+                    (0..self.mocker.list_length)
+                        .map(|_| self.type_of(of_type, field_name, rng, depth + 1))
+                        .collect(),
+                )
+            }
+            Type::Named { name, non_null } => match self.definitions.get(name) {
+                Some(Definition::Object(def)) => {
+                    self.object(&def.fields, None, rng, depth, *non_null)
+                }
+                // abstract types get the `__typename` of the chosen member so
+                // the type can be discriminated
+                Some(Definition::Interface(def)) => match self.implementation(&def.name, rng) {
+                    Some(member) => {
+                        self.object(&member.fields, Some(&member.name), rng, depth, *non_null)
+                    }
+                    None => self.object(&def.fields, None, rng, depth, *non_null),
+                },
+                Some(Definition::Union(def)) => {
+                    let types = def.types.iter().collect::<Vec<_>>();
+                    match types
+                        .choose(rng)
+                        .and_then(|name| self.definitions.get(*name))
+                    {
+                        Some(Definition::Object(member)) => {
+                            self.object(&member.fields, Some(&member.name), rng, depth, *non_null)
+                        }
+                        _ => Value::Null,
+                    }
+                }
+                Some(Definition::Enum(def)) => def
+                    .enum_values
+                    .choose(rng)
+                    .map(|value| Value::Enum(Name::new(&value.name)))
+                    .unwrap_or(Value::Null),
+                _ => scalar(name, field_name, rng),
+            },
+        }
+    }
+
+    fn implementation(&self, interface: &str, rng: &mut StdRng) -> Option<&ObjectTypeDefinition> {
+        let mut implementations = self
+            .definitions
+            .values()
+            .filter_map(|def| match def {
+                Definition::Object(def) if def.implements.contains(interface) => Some(def),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        implementations.sort_by(|a, b| a.name.cmp(&b.name));
+
+        implementations.choose(rng).copied()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn object(
+        &self,
+        fields: &[FieldDefinition],
+        type_name: Option<&str>,
+        rng: &mut StdRng,
+        depth: usize,
+        non_null: bool,
+    ) -> Value {
+        if depth >= self.mocker.max_depth && !non_null {
+            return Value::Null;
+        }
+
+        let mut object = IndexMap::new();
+        if let Some(type_name) = type_name {
+            object.insert(Name::new("__typename"), Value::from(type_name));
+        }
+
+        if depth < self.mocker.max_depth {
+            for field in fields {
+                // fields with their own resolver are mocked separately
+                if field.resolver.as_ref().is_some_and(has_io) {
+                    continue;
+                }
+
+                object.insert(Name::new(&field.name), self.field(field, rng, depth + 1));
+            }
+        }
+
+        Value::Object(object)
+    }
+}
+
+/// Generates a value that is valid for the scalar and looks realistic for the
+/// name of the field.
+fn scalar(name: &str, field_name: &str, rng: &mut StdRng) -> Value {
+    let first = FIRST_NAMES.choose(rng).copied().unwrap_or_default();
+    let last = LAST_NAMES.choose(rng).copied().unwrap_or_default();
+
+    match name {
+        "Int" => Value::from(rng.gen_range(1..1000)),
+        "Float" => Value::from((rng.gen_range(0.0..1000.0_f64) * 100.0).round() / 100.0),
+        "Boolean" => Value::from(rng.gen_bool(0.5)),
+        "ID" => Value::from(rng.gen_range(1..100_000).to_string()),
+        "String" => Value::from(string(field_name, first, last, rng)),
+        name => match Scalar::find(name) {
+            Some(Scalar::Empty) => Value::Null,
+            Some(Scalar::Email) => Value::from(format!(
+                "{}.{}@example.com",
+                first.to_lowercase(),
+                last.to_lowercase()
+            )),
+            Some(Scalar::PhoneNumber) => {
+                Value::from(format!("+1202555{:04}", rng.gen_range(100..200)))
+            }
+            Some(Scalar::Date) | Some(Scalar::DateTime) => Value::from(format!(
+                "{}-{:02}-{:02}T{:02}:{:02}:00Z",
+                rng.gen_range(2000..2030),
+                rng.gen_range(1..13),
+                rng.gen_range(1..29),
+                rng.gen_range(0..24),
+                rng.gen_range(0..60)
+            )),
+            Some(Scalar::Url) => Value::from(format!(
+                "https://example.com/{}",
+                WORDS.choose(rng).copied().unwrap_or_default()
+            )),
+            Some(Scalar::JSON) => Value::Object(IndexMap::from([(
+                Name::new(WORDS.choose(rng).copied().unwrap_or("key")),
+                Value::from(WORDS.choose(rng).copied().unwrap_or_default()),
+            )])),
+            Some(Scalar::Int8) => Value::from(rng.gen_range(0..i8::MAX)),
+            Some(Scalar::Int16) => Value::from(rng.gen_range(0..i16::MAX)),
+            Some(Scalar::Int32) => Value::from(rng.gen_range(0..i32::MAX)),
+            Some(Scalar::UInt8) => Value::from(rng.gen_range(0..u8::MAX)),
+            Some(Scalar::UInt16) => Value::from(rng.gen_range(0..u16::MAX)),
+            Some(Scalar::UInt32) => Value::from(rng.gen_range(0..u32::MAX)),
+            // 64 and 128 bit integers are represented as strings
+            Some(Scalar::Int64) | Some(Scalar::Int128) => {
+                Value::from(rng.gen_range(0..i64::MAX).to_string())
+            }
+            Some(Scalar::UInt64) | Some(Scalar::UInt128) => {
+                Value::from(rng.gen_range(0..u64::MAX).to_string())
+            }
+            Some(Scalar::Bytes) => Value::from("bW9jaw=="),
+            None => Value::Null,
+        },
+    }
+}
+
+fn string(field_name: &str, first: &str, last: &str, rng: &mut StdRng) -> String {
+    let field_name = field_name.to_lowercase();
+
+    if field_name.contains("email") {
+        format!("{}.{}@example.com", first, last).to_lowercase()
+    } else if field_name.contains("username") {
+        format!("{}{}", first, rng.gen_range(1..100)).to_lowercase()
+    } else if field_name.contains("name") {
+        format!("{} {}", first, last)
+    } else if field_name.contains("phone") {
+        format!("+1202555{:04}", rng.gen_range(100..200))
+    } else if field_name.contains("url") || field_name.contains("website") {
+        format!("https://{}.example.com", first.to_lowercase())
+    } else {
+        let mut words = WORDS.to_vec();
+        words.shuffle(rng);
+        words[..rng.gen_range(2..5)].join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tailcall_valid::Validator;
+
+    use super::*;
+    use crate::core::config::Config;
+
+    const CONFIG: &str = r#"
+        schema { query: Query }
+        type Query {
+            users: [User] @http(url: "http://jsonplaceholder.typicode.com/users")
+            user(id: Int!): User @http(url: "http://jsonplaceholder.typicode.com/users/{{.args.id}}")
+                @mock(value: { id: 1, name: "Leanne Graham", email: "leanne@example.com" })
+        }
+        type User {
+            id: Int!
+            name: String
+            email: Email
+            website: Url
+        }
+    "#;
+
+    fn mock(seed: u64) -> Blueprint {
+        let config = Config::from_sdl(CONFIG).to_result().unwrap();
+        let config_module = ConfigModule::from(config);
+        let blueprint = Blueprint::try_from(&config_module).unwrap();
+
+        Mocker::from_config_module(seed, &config_module)
+            .unwrap()
+            .mock(blueprint)
+    }
+
+    fn resolver<'a>(blueprint: &'a Blueprint, field_name: &str) -> &'a IR {
+        blueprint
+            .definitions
+            .iter()
+            .find_map(|def| match def {
+                Definition::Object(def) if def.name == "Query" => {
+                    def.fields.iter().find(|field| field.name == field_name)
+                }
+                _ => None,
+            })
+            .and_then(|field| field.resolver.as_ref())
+            .unwrap()
+    }
+
+    fn value(ir: &IR) -> &Value {
+        match ir {
+            IR::Dynamic(DynamicValue::Value(value)) => value,
+            ir => panic!("Expected a constant, found {:?}", ir),
+        }
+    }
+
+    #[test]
+    fn test_generated_values() {
+        let blueprint = mock(1);
+        let users = value(resolver(&blueprint, "users"));
+
+        let Value::List(users) = users else {
+            panic!("Expected a list")
+        };
+        assert_eq!(users.len(), 3);
+
+        for user in users {
+            let Value::Object(user) = user else {
+                panic!("Expected an object")
+            };
+            assert!(matches!(user.get("id"), Some(Value::Number(_))));
+            let email = user.get("email").unwrap();
+            assert!(Scalar::Email.validate(email));
+            let website = user.get("website").unwrap();
+            assert!(Scalar::Url.validate(website));
+        }
+    }
+
+    #[test]
+    fn test_deterministic_seed() {
+        let first = value(resolver(&mock(42), "users")).clone();
+        let second = value(resolver(&mock(42), "users")).clone();
+        let other = value(resolver(&mock(7), "users")).clone();
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn test_mock_directive_override() {
+        let blueprint = mock(1);
+        let actual = value(resolver(&blueprint, "user"))
+            .clone()
+            .into_json()
+            .unwrap();
+        let expected = serde_json::json!({
+            "id": 1,
+            "name": "Leanne Graham",
+            "email": "leanne@example.com"
+        });
+
+        assert_eq!(actual, expected);
+    }
+}
@@ -25,6 +25,7 @@ pub mod jit;
 pub mod json;
 mod lift;
 pub mod merge_right;
+pub mod mock;
 pub mod mustache;
 pub mod path;
 pub mod primitive;
@@ -32,6 +33,7 @@ pub mod print_schema;
 pub mod proto_reader;
 pub mod resource_reader;
 pub mod rest;
+pub mod rpc;
 pub mod runtime;
 pub mod scalar;
 pub mod schema_extension;
@@ -75,6 +77,19 @@ pub trait HttpIO: Sync + Send + 'static {
         &self,
         request: reqwest::Request,
     ) -> anyhow::Result<Response<hyper::body::Bytes>>;
+
+    /// Executes the request, the chunks of the response body are passed to
+    /// `inspect` as they arrive so that it can fail the request before the
+    /// body is read completely. By default the body is passed as a whole.
+    async fn execute_inspected(
+        &self,
+        request: reqwest::Request,
+        inspect: &mut (dyn for<'a> FnMut(&'a [u8]) -> anyhow::Result<()> + Send),
+    ) -> anyhow::Result<Response<hyper::body::Bytes>> {
+        let response = self.execute(request).await?;
+        inspect(&response.body)?;
+        Ok(response)
+    }
 }
 
 #[async_trait::async_trait]
@@ -85,7 +100,7 @@ pub trait FileIO: Send + Sync {
 
 #[async_trait::async_trait]
 pub trait Cache: Send + Sync {
-    type Key: Hash + Eq;
+    type Key: Hash + Eq + Send + Sync;
     type Value;
     async fn set<'a>(
         &'a self,
@@ -96,6 +111,38 @@ pub trait Cache: Send + Sync {
     async fn get<'a>(&'a self, key: &'a Self::Key) -> Result<Option<Self::Value>, cache::Error>;
 
     fn hit_rate(&self) -> Option<f64>;
+
+    /// Lookup counters of the cache, `None` if the cache doesn't track them.
+    fn stats(&self) -> Option<cache::CacheStats> {
+        None
+    }
+
+    /// Removes all the entries from the cache.
+    async fn clear<'a>(&'a self) -> Result<(), cache::Error> {
+        Ok(())
+    }
+
+    /// Associates the tags with the cached entry of the key so it can be
+    /// purged with [Cache::purge_tags], the tags are dropped along with the
+    /// entry. Caches without tag support ignore them.
+    async fn tag<'a>(
+        &'a self,
+        _key: &'a Self::Key,
+        _tags: Vec<String>,
+    ) -> Result<(), cache::Error> {
+        Ok(())
+    }
+
+    /// Removes the entries tagged with any of the tags and returns how many
+    /// were removed.
+    async fn purge_tags<'a>(&'a self, _tags: &'a [String]) -> Result<usize, cache::Error> {
+        Ok(0)
+    }
+
+    /// Removes the entry for the key and returns `true` if it was present.
+    async fn purge<'a>(&'a self, _key: &'a Self::Key) -> Result<bool, cache::Error> {
+        Ok(false)
+    }
 }
 
 pub type EntityCache = dyn Cache<Key = IoId, Value = ConstValue>;
@@ -158,7 +158,8 @@ impl GrpcReflection {
             Mustache::parse("application/grpc+proto"),
         ));
         let body_ = Some(RequestBody {
-            mustache: Some(Mustache::parse(body.to_string().as_str())),
+            // the reflection method is bidirectional, the request is a stream of one message
+            mustache: Some(Mustache::parse(json!([body]).to_string().as_str())),
             value: Default::default(),
         });
         let req_template = RequestTemplate {
@@ -167,6 +168,7 @@ impl GrpcReflection {
             body: body_,
             operation: operation.clone(),
             operation_type: Default::default(),
+            protocol: Default::default(),
         };
 
         let ctx = ConfigReaderContext::new(&self.target_runtime);
@@ -175,8 +177,11 @@ impl GrpcReflection {
         let resp = self.target_runtime.http2_only.execute(req).await?;
         let body = resp.body.as_bytes();
 
-        let response: ReflectionResponse = operation.convert_output(body)?;
-        Ok(response)
+        let response: Vec<ReflectionResponse> = operation.convert_output(body)?;
+        response
+            .into_iter()
+            .next()
+            .context("Empty response from the reflection server")
     }
 }
 
@@ -21,6 +21,20 @@ pub(crate) struct Rest {
     pub query: BTreeMap<String, String>,
     #[serde(default, skip_serializing_if = "is_default")]
     pub body: Option<String>,
+    /// Dot separated path of the value in `data` sent as the body of the
+    /// response.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub unwrap: Option<String>,
+    /// Status of the successful responses.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub status: Option<u16>,
+    /// Headers added to the responses.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub headers: BTreeMap<String, String>,
+    /// `Cache-Control` of the successful responses, it takes precedence over
+    /// the one computed from `@cache`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub cache_control: Option<String>,
 }
 
 impl TryFrom<&Directive> for Rest {
@@ -63,6 +77,32 @@ impl TryFrom<&Directive> for Rest {
                         rest.body = Some(v.to_string());
                     }
                 }
+                "unwrap" => {
+                    if let Value::String(path) = &v.node {
+                        rest.unwrap = Some(path.to_owned());
+                    }
+                }
+                "status" => {
+                    rest.status = serde_json::from_str(v.node.to_string().as_str())?;
+                }
+                "headers" => {
+                    if let Value::List(list) = &v.node {
+                        for header in list {
+                            if let Value::Object(map) = header {
+                                if let (Some(Value::String(key)), Some(Value::String(value))) =
+                                    (map.get("key"), map.get("value"))
+                                {
+                                    rest.headers.insert(key.to_owned(), value.to_owned());
+                                }
+                            }
+                        }
+                    }
+                }
+                "cacheControl" => {
+                    if let Value::String(cache_control) = &v.node {
+                        rest.cache_control = Some(cache_control.to_owned());
+                    }
+                }
                 _ => {}
             };
         }
@@ -215,6 +255,26 @@ mod tests {
         pretty_assertions::assert_eq!(directives, vec![true; 5]);
     }
 
+    #[test]
+    fn test_directive_to_rest_response_options() {
+        let query = generate_query_with_directive(
+            r#"method: POST, path: "/users", body: $v, unwrap: "createUser.user", status: 201, headers: [{key: "X-Version", value: "1"}], cacheControl: "no-store""#,
+            DEFAULT_QUERY_PARAM,
+        );
+        let directive = query_to_directive(&query);
+        let actual = Rest::try_from(&directive).unwrap();
+        let expected = Rest::default()
+            .path("/users".to_string())
+            .method(Some(Method::POST))
+            .body(Some("v".to_string()))
+            .unwrap(Some("createUser.user".to_string()))
+            .status(Some(201))
+            .headers(BTreeMap::from([("X-Version".to_string(), "1".to_string())]))
+            .cache_control(Some("no-store".to_string()));
+
+        pretty_assertions::assert_eq!(actual, expected);
+    }
+
     #[test]
     #[should_panic(expected = "Path not provided in the directive")]
     fn directive_to_rest_without_path() {
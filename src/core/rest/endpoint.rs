@@ -9,6 +9,7 @@ use super::directive::Rest;
 use super::partial_request::PartialRequest;
 use super::path::{Path, Segment};
 use super::query_params::QueryParams;
+use super::response::ResponseOptions;
 use super::type_map::TypeMap;
 use super::{Request, Result};
 use crate::core::async_graphql_hyper::GraphQLRequest;
@@ -19,12 +20,14 @@ use crate::core::rest::typed_variables::{UrlParamType, N};
 /// An executable Http Endpoint created from a GraphQL query
 #[derive(Debug, Setters, Clone)]
 pub struct Endpoint {
+    name: Option<String>,
     method: Method,
     path: Path,
 
     // Can use persisted queries for better performance
     query_params: QueryParams,
     body: Option<String>,
+    response: ResponseOptions,
     pub doc: ExecutableDocument,
 }
 
@@ -37,11 +40,28 @@ impl Endpoint {
     pub fn get_path(&self) -> &Path {
         &self.path
     }
+
+    pub fn get_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn get_query_params(&self) -> &QueryParams {
+        &self.query_params
+    }
+
+    pub fn get_body(&self) -> Option<&String> {
+        self.body.as_ref()
+    }
+
+    pub fn get_response(&self) -> &ResponseOptions {
+        &self.response
+    }
+
     pub fn try_new(operations: &str) -> Result<Vec<Self>> {
         let doc = async_graphql::parser::parse_query(operations)?;
         let mut endpoints = Vec::new();
 
-        for (_, op) in doc.operations.iter() {
+        for (name, op) in doc.operations.iter() {
             let type_map = TypeMap::new(
                 op.node
                     .variable_definitions
@@ -66,10 +86,14 @@ impl Endpoint {
 
             if let Some(rest) = rest {
                 let rest = rest?;
+                let response = ResponseOptions::try_from(&rest)?;
+                response.check_unwrap(&op.node.selection_set.node, &doc.fragments)?;
                 let endpoint = Self {
+                    name: name.map(|name| name.to_string()),
                     method: rest.method.unwrap_or_default(),
                     path: Path::parse(&type_map, &rest.path)?,
                     query_params: QueryParams::try_from_map(&type_map, rest.query)?,
+                    response,
                     body: rest.body,
                     doc: ExecutableDocument {
                         operations: DocumentOperations::Single(op.clone()),
@@ -132,8 +156,8 @@ impl Endpoint {
     }
 
     fn drop_rest_directive(directives: &mut Vec<Positioned<Directive>>) {
-        let name = Name::new("rest");
-        directives.retain(|v| v.node.name.node != name)
+        // the operation can be exposed as a gRPC method too
+        directives.retain(|v| !matches!(v.node.name.node.as_str(), "rest" | "rpc"))
     }
 
     pub fn matches<'a>(&'a self, request: &Request) -> Option<PartialRequest<'a>> {
@@ -162,6 +186,7 @@ impl Endpoint {
 
         Some(PartialRequest {
             body: self.body.as_ref(),
+            response: &self.response,
             doc: &self.doc,
             variables,
             path: &self.path,
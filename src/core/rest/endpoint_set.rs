@@ -3,6 +3,7 @@ use std::sync::Arc;
 use tailcall_valid::Validator;
 
 use super::endpoint::Endpoint;
+use super::openapi::OpenApi;
 use super::partial_request::PartialRequest;
 use super::{Request, Result};
 use crate::core::blueprint::Blueprint;
@@ -26,6 +27,13 @@ pub struct Checked;
 #[derive(Default, Clone, Debug)]
 pub struct Unchecked;
 
+impl<Status> EndpointSet<Status> {
+    /// OpenAPI 3.1 document describing the endpoints.
+    pub fn to_openapi(&self, blueprint: &Blueprint) -> serde_json::Value {
+        OpenApi::new(blueprint).document(&self.endpoints)
+    }
+}
+
 impl From<Endpoint> for EndpointSet<Unchecked> {
     fn from(endpoint: Endpoint) -> Self {
         let mut set = EndpointSet::default();
@@ -55,6 +55,10 @@ pub enum Error {
 
     #[error("Async Graphql Server Error: {}", _0)]
     GraphQLServer(ServerError),
+
+    #[error("Unwrap path doesn't match the selection of the operation: {}", _0)]
+    #[from(ignore)]
+    UnwrapPath(String),
 }
 
 pub type Result<A> = std::result::Result<A, Error>;
@@ -2,14 +2,18 @@ mod directive;
 mod endpoint;
 mod endpoint_set;
 pub mod error;
+mod openapi;
 mod operation;
 mod partial_request;
 mod path;
 mod query_params;
+mod response;
 mod type_map;
 mod typed_variables;
 
 pub use endpoint_set::{Checked, EndpointSet, Unchecked};
+pub use openapi::OPENAPI_PATH;
+pub(crate) use response::error_status;
 
 type Request = http::Request<hyper::Body>;
 pub use error::{Error, Result};
@@ -0,0 +1,636 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use async_graphql::parser::types::{
+    DocumentOperations, ExecutableDocument, OperationDefinition, OperationType, Selection,
+    SelectionSet,
+};
+use hyper::StatusCode;
+use serde_json::{json, Map, Value};
+
+use super::endpoint::Endpoint;
+use super::path::Segment;
+use crate::core::blueprint::{Blueprint, Definition, FieldDefinition};
+use crate::core::http::API_URL_PREFIX;
+use crate::core::Type;
+
+/// Path the OpenAPI document is served at, relative to the REST prefix.
+pub const OPENAPI_PATH: &str = "/openapi.json";
+
+/// Builds an OpenAPI 3.1 document out of the `@rest` endpoints, the schemas
+/// of the responses are derived from the selection sets of the operations.
+pub struct OpenApi<'a> {
+    blueprint: &'a Blueprint,
+    definitions: HashMap<&'a str, &'a Definition>,
+    schemas: BTreeMap<String, Value>,
+}
+
+impl<'a> OpenApi<'a> {
+    pub fn new(blueprint: &'a Blueprint) -> Self {
+        let definitions = blueprint
+            .definitions
+            .iter()
+            .map(|definition| (definition.name(), definition))
+            .collect();
+        Self { blueprint, definitions, schemas: BTreeMap::new() }
+    }
+
+    pub fn document(mut self, endpoints: &[Endpoint]) -> Value {
+        let mut paths: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+        for endpoint in endpoints {
+            let Some(operation) = single_operation(&endpoint.doc) else {
+                continue;
+            };
+            let path = endpoint
+                .get_path()
+                .segments
+                .iter()
+                .map(|segment| match segment {
+                    Segment::Literal(literal) => format!("/{}", literal),
+                    Segment::Param(param) => format!("/{{{}}}", param.name()),
+                })
+                .collect::<String>();
+            let value = self.operation(endpoint, operation);
+            paths
+                .entry(format!("{}{}", API_URL_PREFIX, path))
+                .or_default()
+                .insert(endpoint.get_method().to_string().to_lowercase(), value);
+        }
+
+        self.schemas.insert("Problem".to_string(), problem_schema());
+
+        json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Tailcall REST API", "version": "1.0.0" },
+            "paths": paths,
+            "components": { "schemas": self.schemas },
+        })
+    }
+
+    fn operation(&mut self, endpoint: &Endpoint, operation: &OperationDefinition) -> Value {
+        let variables = operation
+            .variable_definitions
+            .iter()
+            .map(|variable| (variable.node.name.node.as_str(), &variable.node))
+            .collect::<HashMap<_, _>>();
+        let is_required = |name: &str| {
+            variables.get(name).is_some_and(|variable| {
+                !variable.var_type.node.nullable && variable.default_value.is_none()
+            })
+        };
+
+        let mut parameters = vec![];
+        for segment in endpoint.get_path().segments.iter() {
+            if let Segment::Param(param) = segment {
+                let schema = variables
+                    .get(param.name())
+                    .map(|variable| {
+                        self.input_type(&Type::from(&variable.var_type.node).into_required())
+                    })
+                    .unwrap_or_default();
+                parameters.push(json!({
+                    "name": param.name(),
+                    "in": "path",
+                    "required": true,
+                    "schema": schema,
+                }));
+            }
+        }
+        for (key, param) in endpoint.get_query_params().iter() {
+            let schema = variables
+                .get(param.name())
+                .map(|variable| self.input_type(&Type::from(&variable.var_type.node)))
+                .unwrap_or_default();
+            parameters.push(json!({
+                "name": key,
+                "in": "query",
+                "required": is_required(param.name()),
+                "schema": schema,
+            }));
+        }
+
+        let mut value = Map::new();
+        let operation_id = endpoint.get_name().map(str::to_string).unwrap_or_else(|| {
+            format!(
+                "{}{}",
+                endpoint.get_method().to_string().to_lowercase(),
+                endpoint.get_path().as_str().replace(['/', '$'], "_")
+            )
+        });
+        value.insert("operationId".to_string(), json!(operation_id));
+        if !parameters.is_empty() {
+            value.insert("parameters".to_string(), json!(parameters));
+        }
+
+        if let Some(body) = endpoint.get_body() {
+            let schema = variables
+                .get(body.as_str())
+                .map(|variable| self.input_type(&Type::from(&variable.var_type.node)))
+                .unwrap_or_default();
+            value.insert(
+                "requestBody".to_string(),
+                json!({
+                    "required": is_required(body),
+                    "content": { "application/json": { "schema": schema } },
+                }),
+            );
+        }
+
+        let root = match operation.ty {
+            OperationType::Mutation => self.blueprint.schema.mutation.clone(),
+            _ => Some(self.blueprint.schema.query.clone()),
+        }
+        .unwrap_or_default();
+        let options = endpoint.get_response();
+        let status = options.get_status();
+        let mut success = json!({ "description": "Successful response" });
+        if status != StatusCode::NO_CONTENT {
+            let response = self.response(
+                &endpoint.doc,
+                &root,
+                &operation.selection_set.node,
+                options.get_unwrap(),
+            );
+            success["content"] = json!({ "application/json": { "schema": response } });
+        }
+        let problem = json!({
+            "application/problem+json": { "schema": { "$ref": "#/components/schemas/Problem" } }
+        });
+        value.insert(
+            "responses".to_string(),
+            json!({
+                status.as_str(): success,
+                "404": { "description": "The resource doesn't exist", "content": problem },
+                "default": { "description": "The operation failed", "content": problem },
+            }),
+        );
+
+        Value::Object(value)
+    }
+
+    /// Schema of the body of a successful response, the data of operations
+    /// selecting a single field is flattened to the value of that field
+    /// unless the endpoint unwraps another path.
+    #[allow(clippy::too_many_arguments)]
+    fn response(
+        &mut self,
+        doc: &ExecutableDocument,
+        root: &str,
+        selection: &SelectionSet,
+        unwrap: Option<&[String]>,
+    ) -> Value {
+        let mut properties = Map::new();
+        let mut required = BTreeSet::new();
+        self.collect_fields(doc, root, selection, false, &mut properties, &mut required);
+
+        if let Some(path) = unwrap {
+            let data = object_schema(properties, required);
+            path.iter()
+                .try_fold(&data, |schema, key| schema.get("properties")?.get(key))
+                .cloned()
+                .unwrap_or_default()
+        } else if properties.len() == 1 {
+            properties
+                .into_iter()
+                .next()
+                .map(|(_, v)| v)
+                .unwrap_or_default()
+        } else {
+            object_schema(properties, required)
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn collect_fields(
+        &mut self,
+        doc: &ExecutableDocument,
+        type_name: &str,
+        selection_set: &SelectionSet,
+        optional: bool,
+        properties: &mut Map<String, Value>,
+        required: &mut BTreeSet<String>,
+    ) {
+        for selection in selection_set.items.iter() {
+            match &selection.node {
+                Selection::Field(field) => {
+                    let field = &field.node;
+                    let key = field.response_key().node.to_string();
+                    let (schema, non_null) = if field.name.node.as_str() == "__typename" {
+                        (json!({ "type": "string" }), true)
+                    } else {
+                        let Some(definition) = self.field(type_name, field.name.node.as_str())
+                        else {
+                            continue;
+                        };
+                        let of_type = definition.of_type.clone();
+                        let description = definition.description.clone();
+                        let mut schema = self.output_type(doc, &of_type, &field.selection_set.node);
+                        if let (Some(description), Value::Object(map)) = (description, &mut schema)
+                        {
+                            map.insert("description".to_string(), json!(description));
+                        }
+                        (schema, !of_type.is_nullable())
+                    };
+                    if non_null && !optional {
+                        required.insert(key.clone());
+                    }
+                    properties.insert(key, schema);
+                }
+                Selection::FragmentSpread(spread) => {
+                    let Some(fragment) = doc.fragments.get(&spread.node.fragment_name.node) else {
+                        continue;
+                    };
+                    let on = fragment.node.type_condition.node.on.node.to_string();
+                    self.collect_fields(
+                        doc,
+                        &on,
+                        &fragment.node.selection_set.node,
+                        optional || on != type_name,
+                        properties,
+                        required,
+                    );
+                }
+                Selection::InlineFragment(fragment) => {
+                    let on = fragment
+                        .node
+                        .type_condition
+                        .as_ref()
+                        .map(|condition| condition.node.on.node.to_string())
+                        .unwrap_or_else(|| type_name.to_string());
+                    self.collect_fields(
+                        doc,
+                        &on,
+                        &fragment.node.selection_set.node,
+                        optional || on != type_name,
+                        properties,
+                        required,
+                    );
+                }
+            }
+        }
+    }
+
+    fn field(&self, type_name: &str, field_name: &str) -> Option<&'a FieldDefinition> {
+        let fields = match self.definitions.get(type_name).copied()? {
+            Definition::Object(definition) => &definition.fields,
+            Definition::Interface(definition) => &definition.fields,
+            _ => return None,
+        };
+        fields.iter().find(|field| field.name == field_name)
+    }
+
+    fn output_type(
+        &mut self,
+        doc: &ExecutableDocument,
+        of_type: &Type,
+        selection_set: &SelectionSet,
+    ) -> Value {
+        let schema = match of_type {
+            Type::Named { name, .. } => match self.definitions.get(name.as_str()).copied() {
+                Some(Definition::Object(_) | Definition::Interface(_) | Definition::Union(_)) => {
+                    let mut properties = Map::new();
+                    let mut required = BTreeSet::new();
+                    self.collect_fields(
+                        doc,
+                        name,
+                        selection_set,
+                        false,
+                        &mut properties,
+                        &mut required,
+                    );
+                    object_schema(properties, required)
+                }
+                _ => self.named_input(name),
+            },
+            Type::List { of_type, .. } => {
+                json!({ "type": "array", "items": self.output_type(doc, of_type, selection_set) })
+            }
+        };
+
+        if of_type.is_nullable() {
+            nullable(schema)
+        } else {
+            schema
+        }
+    }
+
+    fn input_type(&mut self, of_type: &Type) -> Value {
+        let schema = match of_type {
+            Type::Named { name, .. } => self.named_input(name),
+            Type::List { of_type, .. } => {
+                json!({ "type": "array", "items": self.input_type(of_type) })
+            }
+        };
+
+        if of_type.is_nullable() {
+            nullable(schema)
+        } else {
+            schema
+        }
+    }
+
+    /// Schema of a scalar, or a reference to the component describing an
+    /// enum or an input type.
+    fn named_input(&mut self, name: &str) -> Value {
+        let reference = json!({ "$ref": format!("#/components/schemas/{}", name) });
+        if self.schemas.contains_key(name) {
+            return reference;
+        }
+
+        let schema = match self.definitions.get(name).copied() {
+            Some(Definition::Enum(definition)) => {
+                let values = definition
+                    .enum_values
+                    .iter()
+                    .map(|value| value.name.clone())
+                    .collect::<Vec<_>>();
+                with_description(
+                    json!({ "type": "string", "enum": values }),
+                    definition.description.as_deref(),
+                )
+            }
+            Some(Definition::InputObject(definition)) => {
+                // registered upfront, so that recursive inputs refer to themselves
+                self.schemas.insert(name.to_string(), Value::Null);
+                let mut properties = Map::new();
+                let mut required = BTreeSet::new();
+                for field in definition.fields.iter() {
+                    let schema = with_description(
+                        self.input_type(&field.of_type),
+                        field.description.as_deref(),
+                    );
+                    if !field.of_type.is_nullable() && field.default_value.is_none() {
+                        required.insert(field.name.clone());
+                    }
+                    properties.insert(field.name.clone(), schema);
+                }
+                with_description(
+                    object_schema(properties, required),
+                    definition.description.as_deref(),
+                )
+            }
+            _ => return scalar_schema(name),
+        };
+
+        self.schemas.insert(name.to_string(), schema);
+        reference
+    }
+}
+
+fn single_operation(doc: &ExecutableDocument) -> Option<&OperationDefinition> {
+    match &doc.operations {
+        DocumentOperations::Single(operation) => Some(&operation.node),
+        DocumentOperations::Multiple(operations) => {
+            operations.values().next().map(|operation| &operation.node)
+        }
+    }
+}
+
+fn scalar_schema(name: &str) -> Value {
+    match name {
+        "Int" => json!({ "type": "integer", "format": "int32" }),
+        "Int8" | "Int16" | "Int32" | "Int64" | "Int128" | "UInt8" | "UInt16" | "UInt32"
+        | "UInt64" | "UInt128" => json!({ "type": "integer", "format": name.to_lowercase() }),
+        "Float" => json!({ "type": "number" }),
+        "Boolean" => json!({ "type": "boolean" }),
+        "String" | "ID" | "PhoneNumber" | "Bytes" => json!({ "type": "string" }),
+        "Email" => json!({ "type": "string", "format": "email" }),
+        "Date" => json!({ "type": "string", "format": "date" }),
+        "DateTime" => json!({ "type": "string", "format": "date-time" }),
+        "Url" => json!({ "type": "string", "format": "uri" }),
+        "Empty" => json!({ "type": "null" }),
+        // JSON and the custom scalars accept any value
+        _ => json!({}),
+    }
+}
+
+fn object_schema(properties: Map<String, Value>, required: BTreeSet<String>) -> Value {
+    let mut schema = json!({ "type": "object", "properties": properties });
+    if !required.is_empty() {
+        schema["required"] = json!(required);
+    }
+    schema
+}
+
+fn nullable(mut schema: Value) -> Value {
+    match schema.get("type").cloned() {
+        Some(Value::String(ty)) if ty != "null" => {
+            schema["type"] = json!([ty, "null"]);
+            schema
+        }
+        Some(_) => schema,
+        None if schema.as_object().is_some_and(Map::is_empty) => schema,
+        None => json!({ "anyOf": [schema, { "type": "null" }] }),
+    }
+}
+
+fn with_description(mut schema: Value, description: Option<&str>) -> Value {
+    if let (Some(description), Value::Object(map)) = (description, &mut schema) {
+        map.insert("description".to_string(), json!(description));
+    }
+    schema
+}
+
+/// Body of the error responses, as described by RFC 9457.
+fn problem_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "type": { "type": "string" },
+            "title": { "type": "string" },
+            "status": { "type": "integer" },
+            "detail": { "type": "string" },
+            "errors": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "message": { "type": "string" },
+                        "path": { "type": "array", "items": { "type": ["string", "integer"] } },
+                        "extensions": { "type": "object" },
+                    },
+                    "required": ["message"],
+                },
+            },
+        },
+        "required": ["type", "title", "status"],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::core::config::{Config, ConfigModule, Source};
+    use crate::core::rest::{EndpointSet, Unchecked};
+
+    const CONFIG: &str = r#"
+        schema {
+          query: Query
+          mutation: Mutation
+        }
+
+        type Query {
+          user(id: Int!): User @http(url: "http://localhost/users/{{.args.id}}")
+          users(name: String): [User!]! @http(url: "http://localhost/users")
+        }
+
+        type Mutation {
+          createUser(input: UserInput!): User! @http(url: "http://localhost/users", method: POST, body: "{{.args.input}}")
+        }
+
+        "A user of the app"
+        type User {
+          id: Int!
+          name: String
+          role: Role
+        }
+
+        input UserInput {
+          name: String!
+          role: Role
+        }
+
+        enum Role {
+          ADMIN
+          USER
+        }
+    "#;
+
+    const OPERATIONS: &str = r#"
+        query user($id: Int!) @rest(method: GET, path: "/users/$id") {
+          user(id: $id) {
+            id
+            fullName: name
+          }
+        }
+
+        mutation createUser($input: UserInput!) @rest(method: POST, path: "/users", body: $input, status: 201) {
+          createUser(input: $input) {
+            id
+          }
+        }
+    "#;
+
+    // anonymous operations can't share a document with other operations
+    const ANONYMOUS_OPERATION: &str = r#"
+        query ($name: String) @rest(method: GET, path: "/users", query: {name: $name}) {
+          users(name: $name) {
+            id
+            role
+          }
+        }
+    "#;
+
+    fn document() -> Value {
+        let config = Config::from_source(Source::GraphQL, CONFIG).unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let mut endpoints = EndpointSet::<Unchecked>::try_new(OPERATIONS).unwrap();
+        endpoints.extend(EndpointSet::try_new(ANONYMOUS_OPERATION).unwrap());
+        endpoints.to_openapi(&blueprint)
+    }
+
+    #[test]
+    fn test_path_params() {
+        let actual = document()["paths"]["/api/users/{id}"]["get"].clone();
+        let expected = json!({
+            "operationId": "user",
+            "parameters": [
+                { "name": "id", "in": "path", "required": true, "schema": { "type": "integer", "format": "int32" } }
+            ],
+            "responses": {
+                "200": {
+                    "description": "Successful response",
+                    "content": {
+                        "application/json": {
+                            "schema": {
+                                "type": ["object", "null"],
+                                "properties": {
+                                    "fullName": { "type": ["string", "null"] },
+                                    "id": { "type": "integer", "format": "int32" },
+                                },
+                                "required": ["id"],
+                            }
+                        }
+                    },
+                },
+                "404": {
+                    "description": "The resource doesn't exist",
+                    "content": {
+                        "application/problem+json": {
+                            "schema": { "$ref": "#/components/schemas/Problem" }
+                        }
+                    },
+                },
+                "default": {
+                    "description": "The operation failed",
+                    "content": {
+                        "application/problem+json": {
+                            "schema": { "$ref": "#/components/schemas/Problem" }
+                        }
+                    },
+                },
+            },
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_query_params() {
+        let actual = document()["paths"]["/api/users"]["get"].clone();
+
+        assert_eq!(actual["operationId"], json!("get_users"));
+        assert_eq!(
+            actual["parameters"],
+            json!([{
+                "name": "name",
+                "in": "query",
+                "required": false,
+                "schema": { "type": ["string", "null"] },
+            }])
+        );
+        assert_eq!(
+            actual["responses"]["200"]["content"]["application/json"]["schema"]["type"],
+            json!("array")
+        );
+    }
+
+    #[test]
+    fn test_request_body() {
+        let document = document();
+        let actual = document["paths"]["/api/users"]["post"]["requestBody"].clone();
+        let expected = json!({
+            "required": true,
+            "content": {
+                "application/json": {
+                    "schema": { "$ref": "#/components/schemas/UserInput" }
+                }
+            },
+        });
+        assert_eq!(actual, expected);
+
+        let actual = document["components"]["schemas"]["UserInput"].clone();
+        let expected = json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "role": { "anyOf": [{ "$ref": "#/components/schemas/Role" }, { "type": "null" }] },
+            },
+            "required": ["name"],
+        });
+        assert_eq!(actual, expected);
+        assert_eq!(
+            document["paths"]["/api/users"]["post"]["responses"]["201"]["content"]
+                ["application/json"]["schema"],
+            json!({
+                "type": "object",
+                "properties": { "id": { "type": "integer", "format": "int32" } },
+                "required": ["id"],
+            })
+        );
+        assert_eq!(
+            document["components"]["schemas"]["Role"],
+            json!({ "type": "string", "enum": ["ADMIN", "USER"] })
+        );
+    }
+}
@@ -3,24 +3,27 @@ use async_graphql::{Name, Variables};
 use async_graphql_value::ConstValue;
 
 use super::path::Path;
-use super::{Request, Result};
+use super::response::ResponseOptions;
+use super::Result;
 use crate::core::async_graphql_hyper::GraphQLRequest;
 
 /// A partial GraphQLRequest that contains a parsed executable GraphQL document.
 #[derive(Debug)]
 pub struct PartialRequest<'a> {
     pub body: Option<&'a String>,
+    pub response: &'a ResponseOptions,
     pub doc: &'a ExecutableDocument,
     pub variables: Variables,
     pub path: &'a Path,
 }
 
 impl PartialRequest<'_> {
-    pub async fn into_request(self, request: Request) -> Result<GraphQLRequest> {
+    /// Creates the request, `body` is read by the caller within the body size
+    /// limit and is ignored unless the endpoint binds it to a variable.
+    pub fn into_request(self, body: &[u8]) -> Result<GraphQLRequest> {
         let mut variables = self.variables;
         if let Some(key) = self.body {
-            let bytes = hyper::body::to_bytes(request.into_body()).await?;
-            let body: ConstValue = serde_json::from_slice(&bytes)?;
+            let body: ConstValue = serde_json::from_slice(body)?;
             variables.insert(Name::new(key), body);
         }
 
@@ -32,6 +32,10 @@ impl QueryParams {
         Ok(Self { params })
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &TypedVariable)> {
+        self.params.iter().map(|(key, t_var)| (key.as_str(), t_var))
+    }
+
     pub fn matches(&self, query_params: BTreeMap<String, String>) -> Option<Variables> {
         let mut variables = Variables::default();
         for (key, t_var) in &self.params {
@@ -0,0 +1,358 @@
+use std::collections::HashMap;
+
+use async_graphql::parser::types::{FragmentDefinition, Selection, SelectionSet};
+use async_graphql::{BatchResponse, Name, Positioned, ServerError};
+use hyper::header::{HeaderName, HeaderValue, CACHE_CONTROL, CONTENT_TYPE};
+use hyper::{Body, HeaderMap, Response, StatusCode};
+use once_cell::sync::Lazy;
+use serde_json::json;
+
+use super::directive::Rest;
+use super::Result;
+use crate::core::async_graphql_hyper::GraphQLResponse;
+use crate::core::{auth, ir};
+
+static MISSING_CREDENTIALS: Lazy<String> =
+    Lazy::new(|| ir::Error::Auth(auth::error::Error::Missing).to_string());
+static INVALID_CREDENTIALS: Lazy<String> =
+    Lazy::new(|| ir::Error::Auth(auth::error::Error::Invalid).to_string());
+
+/// Shape of the responses of an endpoint, set with the options of `@rest`.
+#[derive(Clone, Debug, Default)]
+pub struct ResponseOptions {
+    unwrap: Option<Vec<String>>,
+    status: Option<StatusCode>,
+    headers: HeaderMap,
+    cache_control: Option<HeaderValue>,
+}
+
+impl TryFrom<&Rest> for ResponseOptions {
+    type Error = super::Error;
+
+    fn try_from(rest: &Rest) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in rest.headers.iter() {
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes()).map_err(http::Error::from)?,
+                HeaderValue::from_str(value).map_err(http::Error::from)?,
+            );
+        }
+
+        Ok(Self {
+            unwrap: rest
+                .unwrap
+                .as_ref()
+                .map(|path| path.split('.').map(str::to_string).collect()),
+            status: rest
+                .status
+                .map(StatusCode::from_u16)
+                .transpose()
+                .map_err(http::Error::from)?,
+            headers,
+            cache_control: rest
+                .cache_control
+                .as_deref()
+                .map(HeaderValue::from_str)
+                .transpose()
+                .map_err(http::Error::from)?,
+        })
+    }
+}
+
+impl ResponseOptions {
+    /// Checks that the unwrap path selects a field of the operation, so that
+    /// mistakes are reported at startup rather than as `404` responses.
+    pub fn check_unwrap(
+        &self,
+        selection_set: &SelectionSet,
+        fragments: &HashMap<Name, Positioned<FragmentDefinition>>,
+    ) -> Result<()> {
+        let Some(path) = &self.unwrap else {
+            return Ok(());
+        };
+
+        path.iter()
+            .try_fold(selection_set, |selection_set, key| {
+                select(selection_set, fragments, key)
+            })
+            .map(|_| ())
+            .ok_or_else(|| super::Error::UnwrapPath(path.join(".")))
+    }
+
+    pub fn get_unwrap(&self) -> Option<&[String]> {
+        self.unwrap.as_deref()
+    }
+
+    pub fn get_status(&self) -> StatusCode {
+        self.status.unwrap_or(StatusCode::OK)
+    }
+
+    /// Turns the result of the operation into the response of the endpoint.
+    /// When the value sent back is null, the errors are sent as an
+    /// `application/problem+json` body with a status derived from them, or a
+    /// `404` without errors. Partial results keep the GraphQL envelope, so
+    /// that the data isn't lost along with the errors.
+    pub fn to_response(&self, response: GraphQLResponse) -> anyhow::Result<Response<Body>> {
+        let response = match response.0 {
+            BatchResponse::Single(response) => response,
+            batch => return GraphQLResponse(batch).into_rest_response(),
+        };
+
+        let data = response.data.into_json()?;
+        let value = match &self.unwrap {
+            Some(path) => path.iter().try_fold(&data, |value, key| value.get(key)),
+            None => match &data {
+                serde_json::Value::Object(map) if map.len() == 1 => map.values().next(),
+                data => Some(data),
+            },
+        }
+        .cloned()
+        .unwrap_or_default();
+
+        if let Some(error) = response.errors.first() {
+            let status = error_status(error);
+            if value.is_null() {
+                return problem(status, Some(&error.message), &response.errors);
+            }
+
+            let body = json!({"data": data, "errors": response.errors});
+            return Ok(Response::builder()
+                .status(status)
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_vec(&body)?))?);
+        }
+
+        let status = self.get_status();
+        let mut builder = Response::builder().status(status);
+        let body = if status == StatusCode::NO_CONTENT {
+            Body::empty()
+        } else if value.is_null() {
+            return problem(StatusCode::NOT_FOUND, None, &[]);
+        } else {
+            builder = builder.header(CONTENT_TYPE, "application/json");
+            Body::from(serde_json::to_vec(&value)?)
+        };
+
+        let cache_control = self.cache_control.clone().or_else(|| {
+            response
+                .cache_control
+                .value()
+                .and_then(|value| HeaderValue::from_str(&value).ok())
+        });
+        if let Some(cache_control) = cache_control {
+            builder = builder.header(CACHE_CONTROL, cache_control);
+        }
+
+        Ok(builder.body(body)?)
+    }
+
+    /// Adds the headers of the endpoint, they override the ones of the
+    /// server.
+    pub fn extend_headers(&self, headers: &mut HeaderMap) {
+        headers.extend(self.headers.clone());
+    }
+}
+
+/// Selection set of the field with the response key in the selection set.
+fn select<'a>(
+    selection_set: &'a SelectionSet,
+    fragments: &'a HashMap<Name, Positioned<FragmentDefinition>>,
+    key: &str,
+) -> Option<&'a SelectionSet> {
+    selection_set
+        .items
+        .iter()
+        .find_map(|selection| match &selection.node {
+            Selection::Field(field) => {
+                (field.node.response_key().node == key).then_some(&field.node.selection_set.node)
+            }
+            Selection::InlineFragment(fragment) => {
+                select(&fragment.node.selection_set.node, fragments, key)
+            }
+            Selection::FragmentSpread(spread) => fragments
+                .get(&spread.node.fragment_name.node)
+                .and_then(|fragment| select(&fragment.node.selection_set.node, fragments, key)),
+        })
+}
+
+/// Status of the response of an operation that failed with the error.
+pub(crate) fn error_status(error: &ServerError) -> StatusCode {
+    let code = error
+        .extensions
+        .as_ref()
+        .and_then(|extensions| extensions.get("code"))
+        .and_then(|code| match code {
+            async_graphql::Value::String(code) => Some(code.as_str()),
+            _ => None,
+        });
+
+    match code {
+        Some("UNAUTHENTICATED") => StatusCode::UNAUTHORIZED,
+        Some("FORBIDDEN") => StatusCode::FORBIDDEN,
+        Some("NOT_FOUND") => StatusCode::NOT_FOUND,
+        Some("BAD_USER_INPUT" | "GRAPHQL_PARSE_FAILED" | "GRAPHQL_VALIDATION_FAILED") => {
+            StatusCode::BAD_REQUEST
+        }
+        _ if error.message == *MISSING_CREDENTIALS => StatusCode::UNAUTHORIZED,
+        _ if error.message == *INVALID_CREDENTIALS => StatusCode::FORBIDDEN,
+        // errors that aren't raised by a field are raised by the validation
+        // of the request, e.g. a variable of the wrong type
+        _ if error.path.is_empty() => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Error response in the format of RFC 9457.
+fn problem(
+    status: StatusCode,
+    detail: Option<&str>,
+    errors: &[ServerError],
+) -> anyhow::Result<Response<Body>> {
+    let mut body = json!({
+        "type": "about:blank",
+        "title": status.canonical_reason().unwrap_or_default(),
+        "status": status.as_u16(),
+    });
+    if let Some(detail) = detail {
+        body["detail"] = json!(detail);
+    }
+    if !errors.is_empty() {
+        body["errors"] = serde_json::to_value(errors)?;
+    }
+
+    Ok(Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/problem+json")
+        .body(Body::from(serde_json::to_vec(&body)?))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::Value;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn options(rest: Rest) -> ResponseOptions {
+        ResponseOptions::try_from(&rest).unwrap()
+    }
+
+    fn graphql_response(data: serde_json::Value, errors: Vec<ServerError>) -> GraphQLResponse {
+        let mut response = async_graphql::Response::new(Value::from_json(data).unwrap());
+        response.errors = errors;
+        GraphQLResponse::from(response)
+    }
+
+    async fn body(response: Response<Body>) -> serde_json::Value {
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_unwrap() {
+        let rest = Rest::default()
+            .unwrap(Some("createUser.user".to_string()))
+            .status(Some(201))
+            .headers([("X-Version".to_string(), "1".to_string())].into())
+            .cache_control(Some("no-store".to_string()));
+        let options = options(rest);
+        let data = serde_json::json!({"createUser": {"user": {"id": 1}}});
+        let mut response = options.to_response(graphql_response(data, vec![])).unwrap();
+        options.extend_headers(response.headers_mut());
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()["x-version"], "1");
+        assert_eq!(response.headers()[CACHE_CONTROL], "no-store");
+        assert_eq!(body(response).await, serde_json::json!({"id": 1}));
+    }
+
+    #[tokio::test]
+    async fn test_not_found() {
+        let options = ResponseOptions::default();
+        let data = serde_json::json!({"user": null});
+        let response = options.to_response(graphql_response(data, vec![])).unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/problem+json");
+        assert_eq!(
+            body(response).await,
+            serde_json::json!({"type": "about:blank", "title": "Not Found", "status": 404})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_status() {
+        let options = ResponseOptions::default();
+        let cases = [
+            (MISSING_CREDENTIALS.as_str(), Some("user"), None, 401),
+            (INVALID_CREDENTIALS.as_str(), Some("user"), None, 403),
+            ("Invalid value for argument \"id\"", None, None, 400),
+            ("Upstream failed", Some("user"), None, 500),
+            ("Not allowed", Some("user"), Some("FORBIDDEN"), 403),
+        ];
+
+        for (message, path, code, expected) in cases {
+            let mut error = ServerError::new(message, None);
+            if let Some(path) = path {
+                error.path = vec![async_graphql::PathSegment::Field(path.to_string())];
+            }
+            if let Some(code) = code {
+                let mut extensions = async_graphql::ErrorExtensionValues::default();
+                extensions.set("code", code);
+                error.extensions = Some(extensions);
+            }
+            let data = serde_json::json!({"user": null});
+            let response = options
+                .to_response(graphql_response(data, vec![error]))
+                .unwrap();
+
+            assert_eq!(response.status().as_u16(), expected, "{}", message);
+            let body = body(response).await;
+            assert_eq!(body["status"], serde_json::json!(expected));
+            assert_eq!(body["detail"], serde_json::json!(message));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_no_content() {
+        let options = options(Rest::default().status(Some(204)));
+        let data = serde_json::json!({"deleteUser": true});
+        let response = options.to_response(graphql_response(data, vec![])).unwrap();
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(!response.headers().contains_key(CONTENT_TYPE));
+    }
+
+    #[tokio::test]
+    async fn test_partial_errors() {
+        let options = ResponseOptions::default();
+        let mut error = ServerError::new("Upstream failed", None);
+        error.path = vec![async_graphql::PathSegment::Field("name".to_string())];
+        let data = serde_json::json!({"user": {"id": 1, "name": null}});
+        let response = options
+            .to_response(graphql_response(data.clone(), vec![error]))
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+        let body = body(response).await;
+        assert_eq!(body["data"], data);
+        assert_eq!(body["errors"][0]["message"], "Upstream failed");
+    }
+
+    #[test]
+    fn test_check_unwrap() {
+        let doc = async_graphql::parser::parse_query(
+            "mutation { createUser { ...User } } fragment User on CreateUser { user: newUser { id } }",
+        )
+        .unwrap();
+        let (_, operation) = doc.operations.iter().next().unwrap();
+        let selection_set = &operation.node.selection_set.node;
+
+        let valid = options(Rest::default().unwrap(Some("createUser.user".to_string())));
+        assert!(valid.check_unwrap(selection_set, &doc.fragments).is_ok());
+
+        let invalid = options(Rest::default().unwrap(Some("createUser.newUser".to_string())));
+        assert!(invalid.check_unwrap(selection_set, &doc.fragments).is_err());
+    }
+}
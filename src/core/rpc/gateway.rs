@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use async_graphql::{BatchResponse, ServerError, Variables};
+use hyper::header::{HeaderMap, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use prost_reflect::prost_types::FileDescriptorSet;
+use protox::file::{FileResolver, GoogleFileResolver};
+use tonic::{Code, Status};
+
+use super::method::Method;
+use super::proto::{ProtoGenerator, PACKAGE, PROTO_FILE, SERVICE, STRUCT_PROTO};
+use crate::core::async_graphql_hyper::{GraphQLRequest, GraphQLResponse};
+use crate::core::blueprint::{Blueprint, GrpcMethod};
+use crate::core::grpc::protobuf::{ProtobufOperation, ProtobufSet};
+use crate::core::rest;
+
+const GRPC_CONTENT_TYPE: &str = "application/grpc";
+
+/// Serves the operations annotated with `@rpc` as the unary methods of a
+/// gRPC service, described by a generated `.proto` file.
+#[derive(Debug)]
+pub struct Gateway {
+    proto: String,
+    methods: HashMap<String, (Method, ProtobufOperation)>,
+}
+
+impl Gateway {
+    pub fn try_new(methods: &[Method], blueprint: &Blueprint) -> Result<Self> {
+        let proto = ProtoGenerator::new(blueprint).generate(methods)?;
+
+        let mut file_descriptor_set = FileDescriptorSet::default();
+        if proto.contains(STRUCT_PROTO) {
+            let file = GoogleFileResolver::new().open_file(STRUCT_PROTO)?;
+            let source = file
+                .source()
+                .context("Unable to extract content of google well-known proto file")?;
+            file_descriptor_set
+                .file
+                .push(protox_parse::parse(STRUCT_PROTO, source)?);
+        }
+        file_descriptor_set
+            .file
+            .push(protox_parse::parse(PROTO_FILE, &proto)?);
+        let service =
+            ProtobufSet::from_proto_file(file_descriptor_set)?.find_service(&grpc_method(""))?;
+
+        let methods = methods
+            .iter()
+            .map(|method| {
+                let operation = service.find_operation(&grpc_method(method.get_name()))?;
+                Ok((method.get_name().to_string(), (method.clone(), operation)))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { proto, methods })
+    }
+
+    /// The `.proto` file describing the service.
+    pub fn get_proto(&self) -> &str {
+        &self.proto
+    }
+
+    /// Checks if the request is a gRPC call to the service.
+    pub fn is_grpc_request(&self, request: &Request<Body>) -> bool {
+        let is_grpc = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with(GRPC_CONTENT_TYPE));
+
+        is_grpc
+            && request.method() == hyper::Method::POST
+            && method_name(request.uri().path()).is_some()
+    }
+
+    /// Turns the call into the request of the operation of the method, along
+    /// with the operation encoding the response.
+    #[allow(clippy::result_large_err)]
+    pub fn to_request(
+        &self,
+        path: &str,
+        body: &[u8],
+    ) -> std::result::Result<(GraphQLRequest, &ProtobufOperation), Status> {
+        let name = method_name(path)
+            .ok_or_else(|| Status::unimplemented(format!("Unknown path {}", path)))?;
+        let (method, operation) = self
+            .methods
+            .get(name)
+            .ok_or_else(|| Status::unimplemented(format!("Unknown method {}", name)))?;
+        let input = operation
+            .decode_input(body)
+            .map_err(|error| Status::invalid_argument(error.to_string()))?;
+
+        Ok((method.to_request(Variables::from_json(input)), operation))
+    }
+}
+
+/// Turns the result of the operation into the response of the method, the
+/// errors are sent as the status of a trailers-only response.
+pub fn to_response(
+    operation: &ProtobufOperation,
+    response: GraphQLResponse,
+) -> Result<Response<Body>> {
+    let response = match response.0 {
+        BatchResponse::Single(response) => response,
+        BatchResponse::Batch(_) => {
+            return status_response(&Status::internal("Unexpected batch response"))
+        }
+    };
+
+    if let Some(error) = response.errors.first() {
+        return status_response(&Status::new(error_code(error), error.message.clone()));
+    }
+
+    let data = match response.data {
+        async_graphql::Value::Null => "{}".to_string(),
+        data => serde_json::to_string(&data)?,
+    };
+    let message = match operation.encode_output(&data) {
+        Ok(message) => message,
+        Err(error) => return status_response(&Status::internal(error.to_string())),
+    };
+
+    // the status is sent in the trailers, after the message
+    let mut trailers = HeaderMap::new();
+    Status::new(Code::Ok, "").add_header(&mut trailers)?;
+    let (mut sender, body) = Body::channel();
+    tokio::spawn(async move {
+        if sender.send_data(message.into()).await.is_ok() {
+            let _ = sender.send_trailers(trailers).await;
+        }
+    });
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, GRPC_CONTENT_TYPE)
+        .body(body)?)
+}
+
+/// Response without message, the status is sent in the headers.
+pub fn status_response(status: &Status) -> Result<Response<Body>> {
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, GRPC_CONTENT_TYPE)
+        .body(Body::empty())?;
+    status.add_header(response.headers_mut())?;
+
+    Ok(response)
+}
+
+/// Status code of a call whose operation failed with the error, it matches
+/// the status of the REST endpoints.
+fn error_code(error: &ServerError) -> Code {
+    match rest::error_status(error) {
+        StatusCode::BAD_REQUEST => Code::InvalidArgument,
+        StatusCode::UNAUTHORIZED => Code::Unauthenticated,
+        StatusCode::FORBIDDEN => Code::PermissionDenied,
+        StatusCode::NOT_FOUND => Code::NotFound,
+        _ => Code::Internal,
+    }
+}
+
+fn grpc_method(name: &str) -> GrpcMethod {
+    GrpcMethod {
+        package: PACKAGE.to_string(),
+        service: SERVICE.to_string(),
+        name: name.to_string(),
+    }
+}
+
+/// Name of the method called at the path, i.e. `/{package}.{service}/{name}`.
+fn method_name(path: &str) -> Option<&str> {
+    path.strip_prefix('/')?
+        .strip_prefix(PACKAGE)?
+        .strip_prefix('.')?
+        .strip_prefix(SERVICE)?
+        .strip_prefix('/')
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+    use crate::core::config::{Config, ConfigModule, Source};
+
+    const CONFIG: &str = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          user(id: Int!): User @http(url: "http://localhost/users/{{.args.id}}")
+        }
+
+        type User {
+          id: Int!
+          name: String
+        }
+    "#;
+
+    const OPERATIONS: &str = r#"
+        query getUser($id: Int!) @rpc {
+          user(id: $id) { id name }
+        }
+    "#;
+
+    fn gateway() -> Gateway {
+        let config = Config::from_source(Source::GraphQL, CONFIG).unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let methods = Method::try_new(OPERATIONS).unwrap();
+        Gateway::try_new(&methods, &blueprint).unwrap()
+    }
+
+    #[test]
+    fn test_method_name() {
+        assert_eq!(
+            method_name("/tailcall.gateway.Gateway/GetUser"),
+            Some("GetUser")
+        );
+        assert_eq!(method_name("/other.Gateway/GetUser"), None);
+    }
+
+    #[test]
+    fn test_to_request() {
+        let gateway = gateway();
+        let path = "/tailcall.gateway.Gateway/GetUser";
+        let (_, operation) = gateway.methods.get("GetUser").unwrap();
+        let body = operation.convert_input(r#"{"id": 7}"#).unwrap();
+
+        let (request, _) = gateway.to_request(path, &body).unwrap();
+        assert_eq!(
+            request.0.variables.into_value().into_json().unwrap(),
+            json!({"id": 7})
+        );
+
+        // the frame declares more bytes than it holds
+        let error = gateway.to_request(path, &[0, 0, 0, 0, 9]).unwrap_err();
+        assert_eq!(error.code(), Code::InvalidArgument);
+
+        let error = gateway
+            .to_request("/tailcall.gateway.Gateway/ListUsers", &body)
+            .unwrap_err();
+        assert_eq!(error.code(), Code::Unimplemented);
+    }
+
+    #[tokio::test]
+    async fn test_to_response() {
+        let gateway = gateway();
+        let (_, operation) = gateway.methods.get("GetUser").unwrap();
+        let data = async_graphql::Value::from_json(json!({"user": {"id": 1, "name": "a"}}));
+        let response = async_graphql::Response::new(data.unwrap());
+        let response = to_response(operation, GraphQLResponse::from(response)).unwrap();
+
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/grpc");
+        let mut body = response.into_body();
+        let message = hyper::body::HttpBody::data(&mut body)
+            .await
+            .unwrap()
+            .unwrap();
+        let output: serde_json::Value = operation.convert_output(&message).unwrap();
+        assert_eq!(output, json!({"user": {"id": 1, "name": "a"}}));
+        let trailers = hyper::body::HttpBody::trailers(&mut body)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(trailers["grpc-status"], "0");
+    }
+
+    #[test]
+    fn test_error_response() {
+        let gateway = gateway();
+        let (_, operation) = gateway.methods.get("GetUser").unwrap();
+        let mut response = async_graphql::Response::new(async_graphql::Value::Null);
+        let mut error = ServerError::new("Upstream failed", None);
+        error.path = vec![async_graphql::PathSegment::Field("user".to_string())];
+        response.errors = vec![error];
+        let response = to_response(operation, GraphQLResponse::from(response)).unwrap();
+
+        let status = Status::from_header_map(response.headers()).unwrap();
+        assert_eq!(status.code(), Code::Internal);
+        assert_eq!(status.message(), "Upstream failed");
+    }
+}
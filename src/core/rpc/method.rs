@@ -0,0 +1,134 @@
+use anyhow::{bail, Result};
+use async_graphql::parser::types::{
+    Directive, DocumentOperations, ExecutableDocument, OperationDefinition,
+};
+use async_graphql::{Positioned, Variables};
+use async_graphql_value::Value;
+
+use crate::core::async_graphql_hyper::GraphQLRequest;
+
+/// Directives that only configure how the operation is exposed, they are
+/// removed before the operation is executed.
+const EXPOSURE_DIRECTIVES: [&str; 2] = ["rest", "rpc"];
+
+/// A GraphQL operation exposed as a unary gRPC method with `@rpc`.
+#[derive(Clone, Debug)]
+pub struct Method {
+    name: String,
+    pub doc: ExecutableDocument,
+}
+
+impl Method {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// The operation executed by the method.
+    pub fn operation(&self) -> Option<&OperationDefinition> {
+        match &self.doc.operations {
+            DocumentOperations::Single(operation) => Some(&operation.node),
+            DocumentOperations::Multiple(operations) => {
+                operations.values().next().map(|operation| &operation.node)
+            }
+        }
+    }
+
+    pub fn try_new(operations: &str) -> Result<Vec<Self>> {
+        let doc = async_graphql::parser::parse_query(operations)?;
+        let mut methods = Vec::new();
+
+        for (name, op) in doc.operations.iter() {
+            let Some(rpc) = op
+                .node
+                .directives
+                .iter()
+                .find(|directive| directive.node.name.node == "rpc")
+            else {
+                continue;
+            };
+
+            let method_name = match rpc.node.get_argument("name").map(|v| &v.node) {
+                Some(Value::String(method_name)) => method_name.to_owned(),
+                Some(_) => bail!("The name of @rpc must be a string"),
+                None => match name {
+                    Some(name) => upper_case_first_letter(name.as_str()),
+                    None => bail!("Anonymous operations exposed with @rpc need a name"),
+                },
+            };
+            if methods
+                .iter()
+                .any(|method: &Method| method.name == method_name)
+            {
+                bail!("Duplicate @rpc method {}", method_name);
+            }
+
+            let mut op = op.clone();
+            drop_exposure_directives(&mut op.node.directives);
+            methods.push(Self {
+                name: method_name,
+                doc: ExecutableDocument {
+                    operations: DocumentOperations::Single(op),
+                    fragments: doc.fragments.clone(),
+                },
+            });
+        }
+
+        // the operations of a document aren't ordered
+        methods.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(methods)
+    }
+
+    pub fn to_request(&self, variables: Variables) -> GraphQLRequest {
+        let mut req = async_graphql::Request::new("").variables(variables);
+        req.set_parsed_query(self.doc.clone());
+        GraphQLRequest(req)
+    }
+}
+
+fn drop_exposure_directives(directives: &mut Vec<Positioned<Directive>>) {
+    directives.retain(|v| !EXPOSURE_DIRECTIVES.contains(&v.node.name.node.as_str()))
+}
+
+fn upper_case_first_letter(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_method_names() {
+        let methods = Method::try_new(
+            r#"
+            query getUser($id: Int!) @rpc { user(id: $id) { name } }
+            query users @rpc(name: "ListUsers") { users { name } }
+            query posts @rest(method: GET, path: "/posts") { posts { title } }
+            "#,
+        )
+        .unwrap();
+        let names = methods.iter().map(Method::get_name).collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["GetUser", "ListUsers"]);
+        assert!(methods[0].operation().unwrap().directives.is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_method() {
+        let error = Method::try_new(
+            r#"
+            query a @rpc(name: "Users") { users { name } }
+            query b @rpc(name: "Users") { users { id } }
+            "#,
+        )
+        .unwrap_err();
+
+        assert_eq!(error.to_string(), "Duplicate @rpc method Users");
+    }
+}
@@ -0,0 +1,7 @@
+mod gateway;
+mod method;
+mod proto;
+
+pub use gateway::{status_response, to_response, Gateway};
+pub use method::Method;
+pub use proto::{PACKAGE, SERVICE};
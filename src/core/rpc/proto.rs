@@ -0,0 +1,467 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use anyhow::{bail, Context, Result};
+use async_graphql::parser::types::{ExecutableDocument, OperationType, Selection, SelectionSet};
+use convert_case::{Case, Casing};
+
+use super::method::Method;
+use crate::core::blueprint::{Blueprint, Definition, FieldDefinition};
+use crate::core::Type;
+
+/// Package of the generated service.
+pub const PACKAGE: &str = "tailcall.gateway";
+/// Name of the generated service.
+pub const SERVICE: &str = "Gateway";
+/// Name of the generated file.
+pub const PROTO_FILE: &str = "gateway.proto";
+/// Well-known file declaring `google.protobuf.Value`.
+pub const STRUCT_PROTO: &str = "google/protobuf/struct.proto";
+
+/// Type of the values that have no protobuf counterpart, e.g. `JSON` or
+/// nested lists.
+const ANY_VALUE: &str = "google.protobuf.Value";
+
+/// Generates the `.proto` file of the service exposing the operations
+/// annotated with `@rpc`. The request of a method holds the variables of
+/// the operation and the response the fields it selects.
+pub struct ProtoGenerator<'a> {
+    blueprint: &'a Blueprint,
+    definitions: HashMap<&'a str, &'a Definition>,
+    /// Enums and input messages shared by the methods, keyed by name.
+    types: BTreeMap<String, String>,
+    uses_any_value: bool,
+}
+
+/// A message being generated, the messages of the selected objects are
+/// nested in the message selecting them.
+struct Message {
+    name: String,
+    fields: Vec<(String, String)>,
+    nested: Vec<Message>,
+}
+
+impl Message {
+    fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), fields: vec![], nested: vec![] }
+    }
+
+    fn has_field(&self, name: &str) -> bool {
+        self.fields.iter().any(|(field, _)| field == name)
+    }
+
+    fn add_field(&mut self, label: Option<&str>, of_type: &str, name: &str) {
+        let number = self.fields.len() + 1;
+        let label = label.map(|label| format!("{} ", label)).unwrap_or_default();
+        self.fields.push((
+            name.to_string(),
+            format!("{}{} {} = {};", label, of_type, name, number),
+        ));
+    }
+
+    fn render(&self, indent: usize) -> String {
+        let pad = "  ".repeat(indent);
+        let mut out = format!("{}message {} {{\n", pad, self.name);
+        for message in self.nested.iter() {
+            out.push_str(&message.render(indent + 1));
+        }
+        for (_, field) in self.fields.iter() {
+            let _ = writeln!(out, "{}  {}", pad, field);
+        }
+        let _ = writeln!(out, "{}}}", pad);
+        out
+    }
+}
+
+impl<'a> ProtoGenerator<'a> {
+    pub fn new(blueprint: &'a Blueprint) -> Self {
+        let definitions = blueprint
+            .definitions
+            .iter()
+            .map(|definition| (definition.name(), definition))
+            .collect();
+        Self {
+            blueprint,
+            definitions,
+            types: BTreeMap::new(),
+            uses_any_value: false,
+        }
+    }
+
+    pub fn generate(mut self, methods: &[Method]) -> Result<String> {
+        let mut rpcs = String::new();
+        let mut messages = vec![];
+        for method in methods {
+            let name = method.get_name();
+            let operation = method
+                .operation()
+                .with_context(|| format!("Method {} has no operation", name))?;
+
+            let mut request = Message::new(format!("{}Request", name));
+            for variable in operation.variable_definitions.iter() {
+                let of_type = Type::from(&variable.node.var_type.node);
+                let (element, is_message) = self.named_input(of_type.name())?;
+                self.add_field(
+                    &mut request,
+                    &of_type,
+                    &element,
+                    is_message,
+                    variable.node.name.node.as_str(),
+                );
+            }
+
+            let root = match operation.ty {
+                OperationType::Query => self.blueprint.schema.query.clone(),
+                OperationType::Mutation => self
+                    .blueprint
+                    .schema
+                    .mutation
+                    .clone()
+                    .context("The schema has no mutation type")?,
+                OperationType::Subscription => {
+                    bail!("Method {} can't be a subscription", name)
+                }
+            };
+            let mut response = Message::new(format!("{}Response", name));
+            self.collect_fields(
+                &method.doc,
+                &root,
+                &operation.selection_set.node,
+                false,
+                &mut response,
+            )?;
+
+            let _ = writeln!(rpcs, "  rpc {0} ({0}Request) returns ({0}Response);", name);
+            messages.push(request.render(0));
+            messages.push(response.render(0));
+        }
+
+        let mut out = format!("syntax = \"proto3\";\n\npackage {};\n\n", PACKAGE);
+        if self.uses_any_value {
+            let _ = writeln!(out, "import \"{}\";\n", STRUCT_PROTO);
+        }
+        let _ = writeln!(out, "service {} {{\n{}}}", SERVICE, rpcs);
+        for definition in messages.iter().chain(self.types.values()) {
+            out.push('\n');
+            out.push_str(definition);
+        }
+
+        Ok(out)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn collect_fields(
+        &mut self,
+        doc: &ExecutableDocument,
+        type_name: &str,
+        selection_set: &SelectionSet,
+        optional: bool,
+        message: &mut Message,
+    ) -> Result<()> {
+        for selection in selection_set.items.iter() {
+            match &selection.node {
+                Selection::Field(field) => {
+                    let field = &field.node;
+                    let key = field.response_key().node.as_str();
+                    if message.has_field(key) {
+                        continue;
+                    }
+                    if field.name.node.as_str() == "__typename" {
+                        message.add_field(None, "string", key);
+                        continue;
+                    }
+
+                    let definition = self
+                        .field(type_name, field.name.node.as_str())
+                        .with_context(|| {
+                            format!("Field {} not found on type {}", field.name.node, type_name)
+                        })?;
+                    let mut of_type = definition.of_type.clone();
+                    if optional {
+                        of_type = of_type.into_nullable();
+                    }
+
+                    let named = self.definitions.get(of_type.name().as_str()).copied();
+                    let (element, is_message) = match named {
+                        Some(
+                            Definition::Object(_) | Definition::Interface(_) | Definition::Union(_),
+                        ) => {
+                            let mut nested = Message::new(key.to_case(Case::Pascal));
+                            self.collect_fields(
+                                doc,
+                                of_type.name(),
+                                &field.selection_set.node,
+                                false,
+                                &mut nested,
+                            )?;
+                            let name = nested.name.clone();
+                            message.nested.push(nested);
+                            (name, true)
+                        }
+                        _ => self.named_input(of_type.name())?,
+                    };
+                    self.add_field(message, &of_type, &element, is_message, key);
+                }
+                Selection::FragmentSpread(spread) => {
+                    let fragment = doc
+                        .fragments
+                        .get(&spread.node.fragment_name.node)
+                        .with_context(|| {
+                            format!("Fragment {} not found", spread.node.fragment_name.node)
+                        })?;
+                    let on = fragment.node.type_condition.node.on.node.to_string();
+                    self.collect_fields(
+                        doc,
+                        &on,
+                        &fragment.node.selection_set.node,
+                        optional || on != type_name,
+                        message,
+                    )?;
+                }
+                Selection::InlineFragment(fragment) => {
+                    let on = fragment
+                        .node
+                        .type_condition
+                        .as_ref()
+                        .map(|condition| condition.node.on.node.to_string())
+                        .unwrap_or_else(|| type_name.to_string());
+                    self.collect_fields(
+                        doc,
+                        &on,
+                        &fragment.node.selection_set.node,
+                        optional || on != type_name,
+                        message,
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn field(&self, type_name: &str, field_name: &str) -> Option<&'a FieldDefinition> {
+        let fields = match self.definitions.get(type_name).copied()? {
+            Definition::Object(definition) => &definition.fields,
+            Definition::Interface(definition) => &definition.fields,
+            _ => return None,
+        };
+        fields.iter().find(|field| field.name == field_name)
+    }
+
+    /// Adds a field of the given type, lists become repeated fields and the
+    /// nullable scalars optional ones. Lists of lists have no protobuf
+    /// counterpart and are sent as values.
+    #[allow(clippy::too_many_arguments)]
+    fn add_field(
+        &mut self,
+        message: &mut Message,
+        of_type: &Type,
+        element: &str,
+        is_message: bool,
+        name: &str,
+    ) {
+        match of_type {
+            Type::List { of_type, .. } if of_type.is_list() => {
+                self.uses_any_value = true;
+                message.add_field(None, ANY_VALUE, name)
+            }
+            Type::List { .. } => message.add_field(Some("repeated"), element, name),
+            Type::Named { .. } if of_type.is_nullable() && !is_message => {
+                message.add_field(Some("optional"), element, name)
+            }
+            Type::Named { .. } => message.add_field(None, element, name),
+        }
+    }
+
+    /// Protobuf type of a scalar, an enum or an input type, along with
+    /// whether it's a message.
+    fn named_input(&mut self, name: &str) -> Result<(String, bool)> {
+        match self.definitions.get(name).copied() {
+            Some(Definition::Enum(definition)) => {
+                if !self.types.contains_key(name) {
+                    // proto3 enums must start with a zero value
+                    let mut out = format!(
+                        "enum {} {{\n  {}_UNSPECIFIED = 0;\n",
+                        name,
+                        name.to_case(Case::UpperSnake)
+                    );
+                    for (i, value) in definition.enum_values.iter().enumerate() {
+                        let _ = writeln!(out, "  {} = {};", value.name, i + 1);
+                    }
+                    out.push_str("}\n");
+                    self.types.insert(name.to_string(), out);
+                }
+                Ok((name.to_string(), false))
+            }
+            Some(Definition::InputObject(definition)) => {
+                if !self.types.contains_key(name) {
+                    // registered upfront, so that recursive inputs refer to themselves
+                    self.types.insert(name.to_string(), String::new());
+                    let mut message = Message::new(name);
+                    for field in definition.fields.iter() {
+                        let (element, is_message) = self.named_input(field.of_type.name())?;
+                        self.add_field(
+                            &mut message,
+                            &field.of_type,
+                            &element,
+                            is_message,
+                            &field.name,
+                        );
+                    }
+                    self.types.insert(name.to_string(), message.render(0));
+                }
+                Ok((name.to_string(), true))
+            }
+            Some(Definition::Object(_) | Definition::Interface(_) | Definition::Union(_)) => {
+                bail!("Type {} can't be used as an input", name)
+            }
+            _ => match scalar_type(name) {
+                Some(scalar) => Ok((scalar.to_string(), false)),
+                None => {
+                    self.uses_any_value = true;
+                    Ok((ANY_VALUE.to_string(), true))
+                }
+            },
+        }
+    }
+}
+
+fn scalar_type(name: &str) -> Option<&'static str> {
+    match name {
+        "Int" | "Int8" | "Int16" | "Int32" => Some("int32"),
+        "UInt8" | "UInt16" | "UInt32" => Some("uint32"),
+        "Int64" => Some("int64"),
+        "UInt64" => Some("uint64"),
+        "Float" => Some("double"),
+        "Boolean" => Some("bool"),
+        "String" | "ID" | "Email" | "PhoneNumber" | "Date" | "DateTime" | "Url" | "Bytes"
+        | "Int128" | "UInt128" => Some("string"),
+        // JSON, Empty and the custom scalars accept any value
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::core::config::{Config, ConfigModule, Source};
+
+    const CONFIG: &str = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          user(id: Int!): User @http(url: "http://localhost/users/{{.args.id}}")
+          users(role: Role, filter: Filter): [User!]! @http(url: "http://localhost/users")
+        }
+
+        type User {
+          id: Int!
+          name: String
+          role: Role
+          posts: [Post!]!
+          meta: JSON
+        }
+
+        type Post {
+          title: String!
+        }
+
+        input Filter {
+          name: String
+          roles: [Role!]
+        }
+
+        enum Role {
+          ADMIN
+          USER
+        }
+    "#;
+
+    fn generate(operations: &str) -> Result<String> {
+        let config = Config::from_source(Source::GraphQL, CONFIG).unwrap();
+        let blueprint = Blueprint::try_from(&ConfigModule::from(config)).unwrap();
+        let methods = Method::try_new(operations)?;
+        ProtoGenerator::new(&blueprint).generate(&methods)
+    }
+
+    #[test]
+    fn test_generate() {
+        let proto = generate(
+            r#"
+            query getUser($id: Int!) @rpc {
+              user(id: $id) { id name posts { title } }
+            }
+
+            query listUsers($role: Role, $filter: Filter) @rpc {
+              users(role: $role, filter: $filter) { id role meta }
+            }
+            "#,
+        )
+        .unwrap();
+
+        let expected = r#"syntax = "proto3";
+
+package tailcall.gateway;
+
+import "google/protobuf/struct.proto";
+
+service Gateway {
+  rpc GetUser (GetUserRequest) returns (GetUserResponse);
+  rpc ListUsers (ListUsersRequest) returns (ListUsersResponse);
+}
+
+message GetUserRequest {
+  int32 id = 1;
+}
+
+message GetUserResponse {
+  message User {
+    message Posts {
+      string title = 1;
+    }
+    int32 id = 1;
+    optional string name = 2;
+    repeated Posts posts = 3;
+  }
+  User user = 1;
+}
+
+message ListUsersRequest {
+  optional Role role = 1;
+  Filter filter = 2;
+}
+
+message ListUsersResponse {
+  message Users {
+    int32 id = 1;
+    optional Role role = 2;
+    google.protobuf.Value meta = 3;
+  }
+  repeated Users users = 1;
+}
+
+message Filter {
+  optional string name = 1;
+  repeated Role roles = 2;
+}
+
+enum Role {
+  ROLE_UNSPECIFIED = 0;
+  ADMIN = 1;
+  USER = 2;
+}
+"#;
+        assert_eq!(proto, expected);
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        let error = generate("query name @rpc { users { fullName } }").unwrap_err();
+
+        assert_eq!(error.to_string(), "Field fullName not found on type User");
+    }
+}
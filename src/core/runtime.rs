@@ -176,21 +176,21 @@ pub mod test {
         let file = TestFileIO::init();
         let env = TestEnvIO::init();
 
-        TargetRuntime {
-            http,
+        let mut runtime = TargetRuntime {
+            http: http.clone(),
             http2_only: http2,
             env: Arc::new(env),
             file: Arc::new(file),
             cache: Arc::new(InMemoryCache::default()),
             extensions: Arc::new(vec![]),
-            cmd_worker: match &script {
-                Some(script) => Some(init_worker_io::<Event, Command>(script.to_owned())),
-                None => None,
-            },
-            worker: match &script {
-                Some(script) => Some(init_worker_io::<Value, Value>(script.to_owned())),
-                None => None,
-            },
+            cmd_worker: None,
+            worker: None,
+        };
+        if let Some(script) = script {
+            runtime.cmd_worker = Some(init_worker_io::<Event, Command>(script.clone(), &runtime));
+            runtime.worker = Some(init_worker_io::<Value, Value>(script, &runtime));
         }
+
+        runtime
     }
 }
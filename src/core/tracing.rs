@@ -1,7 +1,9 @@
 use std::str::FromStr;
+use std::sync::RwLock;
 use std::{env, fmt};
 
 use colored::Colorize;
+use once_cell::sync::Lazy;
 use tracing::level_filters::LevelFilter;
 use tracing::{Event, Level, Metadata, Subscriber};
 use tracing_subscriber::filter::{filter_fn, FilterFn};
@@ -89,6 +91,28 @@ pub fn get_log_level() -> Option<Level> {
         .and_then(|v| Level::from_str(&v).ok())
 }
 
+static LOG_LEVEL: Lazy<RwLock<LevelFilter>> = Lazy::new(|| {
+    RwLock::new(LevelFilter::from_level(
+        get_log_level().unwrap_or(Level::INFO),
+    ))
+});
+
+/// Level of the emitted logs, initialized from the environment and
+/// changeable at runtime with [set_log_level].
+pub fn log_level() -> LevelFilter {
+    *LOG_LEVEL.read().unwrap()
+}
+
+pub fn set_log_level(level: LevelFilter) {
+    *LOG_LEVEL.write().unwrap() = level;
+    // tracing caches whether a callsite is enabled
+    tracing::callsite::rebuild_interest_cache();
+}
+
+pub fn log_level_filter() -> FilterFn<impl Fn(&Metadata<'_>) -> bool> {
+    filter_fn(|metadata: &Metadata<'_>| *metadata.level() <= log_level())
+}
+
 pub fn default_tracing<S>() -> impl Layer<S>
 where
     S: Subscriber,
@@ -98,9 +122,7 @@ where
         .without_time()
         .with_target(false)
         .event_format(CliFmt)
-        .with_filter(LevelFilter::from_level(
-            get_log_level().unwrap_or(Level::INFO),
-        ))
+        .with_filter(log_level_filter())
 }
 
 pub fn tailcall_filter_target() -> FilterFn<impl Fn(&Metadata<'_>) -> bool> {
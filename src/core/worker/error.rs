@@ -1,5 +1,6 @@
 use std::fmt::Display;
 use std::sync::Arc;
+use std::time::Duration;
 
 use derive_more::{Debug, From};
 use tokio::task::JoinError;
@@ -58,6 +59,44 @@ pub enum Error {
 
     #[debug("Error : {}", _0)]
     Anyhow(Arc<anyhow::Error>),
+
+    #[debug("{} exceeded the {}", _0, _1)]
+    #[from(ignore)]
+    LimitExceeded(String, Limit),
+}
+
+/// Execution limit of the functions of the scripts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Limit {
+    /// Time a call can run without yielding.
+    Time(Duration),
+    /// Time a call can take, including the time its promises are pending.
+    CallTime(Duration),
+    /// Size of the heap of the JS runtime, in bytes.
+    Memory(usize),
+}
+
+impl Limit {
+    /// Machine readable reason reported in the error extensions.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Limit::Time(_) => "timeout",
+            Limit::CallTime(_) => "callTimeout",
+            Limit::Memory(_) => "outOfMemory",
+        }
+    }
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Limit::Time(timeout) => write!(f, "time limit of {}ms", timeout.as_millis()),
+            Limit::CallTime(timeout) => {
+                write!(f, "call time limit of {}ms", timeout.as_millis())
+            }
+            Limit::Memory(max_memory) => write!(f, "memory limit of {} bytes", max_memory),
+        }
+    }
 }
 
 impl From<serde_json::Error> for Error {
@@ -108,6 +147,7 @@ impl Display for Error {
             Error::GlobalThisNotInitialised(error) => write!(f, "globalThis not initialized: {}", error),
             Error::FunctionValueParseError(error, name) => write!(f, "Error: {}\nUnable to parse value from js function: {} maybe because it's not returning a string?", error, name),
             Error::Anyhow(msg) => write!(f, "Error: {}", msg),
+            Error::LimitExceeded(name, limit) => write!(f, "{} exceeded the {}", name, limit),
         }
     }
 }
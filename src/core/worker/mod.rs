@@ -1,4 +1,8 @@
 pub mod error;
+#[cfg(feature = "js")]
+mod typescript;
 pub mod worker;
-pub use error::Error;
+pub use error::{Error, Limit};
+#[cfg(feature = "js")]
+pub use typescript::transpile;
 pub use worker::*;
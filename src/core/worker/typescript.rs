@@ -0,0 +1,97 @@
+use anyhow::anyhow;
+use swc_core::common::comments::SingleThreadedComments;
+use swc_core::common::sync::Lrc;
+use swc_core::common::{FileName, Globals, Mark, SourceMap, Span, Spanned, GLOBALS};
+use swc_core::ecma::ast::EsVersion;
+use swc_core::ecma::codegen::text_writer::JsWriter;
+use swc_core::ecma::codegen::Emitter;
+use swc_core::ecma::parser::lexer::Lexer;
+use swc_core::ecma::parser::{Parser, StringInput, Syntax, TsSyntax};
+use swc_core::ecma::transforms::base::resolver;
+use swc_core::ecma::transforms::typescript::strip;
+
+/// Transpiles a TypeScript script to JS by stripping its types, the script
+/// isn't type checked.
+pub fn transpile(path: &str, source: &str) -> anyhow::Result<String> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let file = cm.new_source_file(
+        FileName::Custom(path.to_string()).into(),
+        source.to_string(),
+    );
+    let comments = SingleThreadedComments::default();
+    let lexer = Lexer::new(
+        Syntax::Typescript(TsSyntax::default()),
+        EsVersion::Es2020,
+        StringInput::from(&*file),
+        Some(&comments),
+    );
+
+    let mut parser = Parser::new_from(lexer);
+    let syntax_error = |span: Span, message: &str| {
+        let loc = cm.lookup_char_pos(span.lo);
+        anyhow!(
+            "Failed to parse {}:{}:{}: {}",
+            path,
+            loc.line,
+            loc.col_display + 1,
+            message
+        )
+    };
+    let mut program = parser
+        .parse_program()
+        .map_err(|error| syntax_error(error.span(), &error.kind().msg()))?;
+    if let Some(error) = parser.take_errors().into_iter().next() {
+        return Err(syntax_error(error.span(), &error.kind().msg()));
+    }
+
+    GLOBALS.set(&Globals::new(), || {
+        let unresolved_mark = Mark::new();
+        let top_level_mark = Mark::new();
+        program.mutate(resolver(unresolved_mark, top_level_mark, true));
+        program.mutate(strip(unresolved_mark, top_level_mark));
+    });
+
+    let mut buf = vec![];
+    {
+        let mut emitter = Emitter {
+            cfg: Default::default(),
+            cm: cm.clone(),
+            comments: Some(&comments),
+            wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+        };
+        emitter.emit_program(&program)?;
+    }
+
+    Ok(String::from_utf8(buf)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transpile() {
+        let source = r#"
+            interface User {
+                id: number
+            }
+
+            export function name(user: User): string {
+                return `user-${user.id as number}`
+            }
+        "#;
+        let actual = transpile("worker.ts", source).unwrap();
+
+        assert!(!actual.contains("interface"));
+        assert!(actual.contains("export function name(user)"));
+    }
+
+    #[test]
+    fn test_transpile_error() {
+        let actual = transpile("worker.ts", "function (: {").unwrap_err();
+
+        assert!(actual
+            .to_string()
+            .starts_with("Failed to parse worker.ts:1:"));
+    }
+}
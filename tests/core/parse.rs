@@ -289,30 +289,24 @@ impl ExecutionSpec {
 
         let http2_only = http.clone();
 
-        let http_worker: Option<Arc<dyn WorkerIO<Event, Command>>> =
-            if let Some(script) = script.clone() {
-                Some(javascript::init_worker_io(script))
-            } else {
-                None
-            };
-
-        let worker: Option<Arc<dyn WorkerIO<ConstValue, ConstValue>>> = if let Some(script) = script
-        {
-            Some(javascript::init_worker_io(script))
-        } else {
-            None
-        };
-
-        let runtime = TargetRuntime {
+        let mut runtime = TargetRuntime {
             http,
             http2_only,
             file: Arc::new(File::new(self.clone())),
             env: Arc::new(Env::init(env)),
             cache: Arc::new(InMemoryCache::default()),
             extensions: Arc::new(vec![]),
-            cmd_worker: http_worker,
-            worker,
+            cmd_worker: None,
+            worker: None,
         };
+        if let Some(script) = script {
+            let http_worker: Arc<dyn WorkerIO<Event, Command>> =
+                javascript::init_worker_io(script.clone(), &runtime);
+            let worker: Arc<dyn WorkerIO<ConstValue, ConstValue>> =
+                javascript::init_worker_io(script, &runtime);
+            runtime.cmd_worker = Some(http_worker);
+            runtime.worker = Some(worker);
+        }
 
         let endpoints = config
             .extensions()
@@ -138,31 +138,38 @@ pub mod test {
         let file = TestFileIO::init();
         let env = TestEnvIO::init();
 
-        TargetRuntime {
-            http,
+        let mut runtime = TargetRuntime {
+            http: http.clone(),
             http2_only: http2,
             env: Arc::new(env),
             file: Arc::new(file),
             cache: Arc::new(InMemoryCache::default()),
             extensions: Arc::new(vec![]),
-            cmd_worker: match &script {
-                Some(script) => Some(init_worker_io::<Event, Command>(script.to_owned())),
-                None => None,
-            },
-            worker: match &script {
-                Some(script) => Some(init_worker_io::<Value, Value>(script.to_owned())),
-                None => None,
-            },
+            cmd_worker: None,
+            worker: None,
+        };
+        if let Some(script) = script {
+            runtime.cmd_worker = Some(init_worker_io::<Event, Command>(script.clone(), &runtime));
+            runtime.worker = Some(init_worker_io::<Value, Value>(script, &runtime));
         }
+
+        runtime
     }
 }
 
 #[cfg(test)]
 mod server_spec {
+    use std::time::Duration;
+
+    use httpmock::MockServer;
     use reqwest::Client;
     use serde_json::json;
     use tailcall::cli::server::Server;
     use tailcall::core::config::reader::ConfigReader;
+    use tailcall::core::config::{Config, ConfigModule};
+    use tailcall_valid::Validator;
+    use tokio::sync::oneshot;
+    use tokio::task::JoinHandle;
 
     async fn test_server(configs: &[&str], url: &str) {
         let runtime = crate::test::init(None);
@@ -266,4 +273,95 @@ mod server_spec {
         )
         .await
     }
+
+    /// Starts the server, the returned sender starts the graceful shutdown.
+    async fn start_server(sdl: &str) -> (JoinHandle<anyhow::Result<()>>, oneshot::Sender<()>) {
+        let config = Config::from_sdl(sdl).to_result().unwrap();
+        let mut server = Server::new(ConfigModule::from(config));
+        let server_up_receiver = server.server_up_receiver();
+        let shutdown_sender = server.shutdown_sender();
+        let server = tokio::spawn(server.start());
+
+        server_up_receiver
+            .await
+            .expect("Server did not start up correctly");
+
+        (server, shutdown_sender)
+    }
+
+    #[tokio::test]
+    async fn server_drain() {
+        let upstream = MockServer::start_async().await;
+        upstream
+            .mock_async(|when, then| {
+                when.path("/greet");
+                then.status(200)
+                    .header("content-type", "application/json")
+                    .json_body(json!("Hello World!"))
+                    .delay(Duration::from_millis(500));
+            })
+            .await;
+        let sdl = format!(
+            r#"
+            schema @server(port: 8805, drainTimeout: 10) {{
+              query: Query
+            }}
+
+            type Query {{
+              greet: String @http(url: "{}")
+            }}
+            "#,
+            upstream.url("/greet")
+        );
+        let (server, shutdown_sender) = start_server(&sdl).await;
+
+        let url = "http://localhost:8805/graphql";
+        let query = json!({ "query": "{ greet }" });
+        let in_flight = tokio::spawn(Client::new().post(url).json(&query).send());
+        // lets the request reach the upstream
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        shutdown_sender.send(()).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // the listener is closed while the in-flight request completes
+        let refused = Client::new().post(url).json(&query).send().await;
+        assert!(refused.unwrap_err().is_connect());
+
+        let response = in_flight.await.unwrap().unwrap();
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body, json!({ "data": { "greet": "Hello World!" } }));
+
+        // the server stops once drained, well before the drain timeout
+        tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("Server did not stop after draining")
+            .unwrap()
+            .unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn server_drain_unix() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tailcall.sock");
+        let sdl = format!(
+            r#"
+            schema @server(listeners: [{{ address: "unix:{}" }}]) {{
+              query: Query
+            }}
+
+            type Query {{
+              greet: String @expr(body: "Hello World!")
+            }}
+            "#,
+            path.display()
+        );
+        let (server, shutdown_sender) = start_server(&sdl).await;
+        assert!(path.exists());
+
+        shutdown_sender.send(()).unwrap();
+        server.await.unwrap().unwrap();
+
+        assert!(!path.exists(), "The socket file should be removed");
+    }
 }